@@ -1,52 +1,79 @@
+mod admin;
+
 use std::sync::Arc;
 
-use ::metrics::{
-    prometheus::{self, Encoder, Registry, TextEncoder},
-    HealthChecker,
-};
+use ::metrics::prometheus::{self, Encoder, Registry, TextEncoder};
 use actix_web::{
-    error::InternalError, get, http::StatusCode, web, App, HttpResponse, HttpServer, Responder,
+    dev::Server, error::InternalError, get, http::StatusCode, web, App, HttpResponse, HttpServer,
+    Responder,
 };
-use ports::storage::Storage;
-use services::{HealthReporter, StatusReporter};
+use ports::types::{Page, TransactionState};
+use serde::Deserialize;
+use services::{Admin, BlockLookup, HealthReporter, QueryReporter, StatusReporter};
 
+use self::admin::{AdminToken, ForwardedForHeader};
 use crate::{
     config::Config,
     errors::{Error, Result},
-    Database,
+    Database, FuelApi,
 };
 
-pub async fn launch_api_server(
+/// Binds the server, which then has to be polled to serve. It leaves signal handling to the
+/// caller.
+pub fn launch_api_server(
     config: &Config,
     metrics_registry: Registry,
-    storage: impl Storage + 'static,
-    fuel_health_check: HealthChecker,
-    eth_health_check: HealthChecker,
-) -> Result<()> {
+    status_reporter: StatusReporter<Database, FuelApi>,
+    health_reporter: HealthReporter,
+    query_reporter: QueryReporter<Database>,
+    admin: Admin<Database>,
+) -> Result<Server> {
     let metrics_registry = Arc::new(metrics_registry);
-    let status_reporter = Arc::new(StatusReporter::new(storage));
-    let health_reporter = Arc::new(HealthReporter::new(fuel_health_check, eth_health_check));
-    HttpServer::new(move || {
+    let status_reporter = Arc::new(status_reporter);
+    let health_reporter = Arc::new(health_reporter);
+    let query_reporter = Arc::new(query_reporter);
+    let admin = Arc::new(admin);
+    let admin_token = AdminToken(config.app.admin_token.as_deref().map(Into::into));
+    let forwarded_for_header = ForwardedForHeader(
+        config
+            .app
+            .admin_forwarded_for_header
+            .as_deref()
+            .map(Into::into),
+    );
+    let server = HttpServer::new(move || {
         App::new()
             .app_data(web::Data::new(Arc::clone(&metrics_registry)))
             .app_data(web::Data::new(Arc::clone(&status_reporter)))
             .app_data(web::Data::new(Arc::clone(&health_reporter)))
+            .app_data(web::Data::new(Arc::clone(&query_reporter)))
+            .app_data(web::Data::new(Arc::clone(&admin)))
+            .app_data(admin_token.clone())
+            .app_data(forwarded_for_header.clone())
             .service(status)
             .service(metrics)
             .service(health)
+            .service(liveness)
+            .service(readiness)
+            .service(block_submissions)
+            .service(block_submission)
+            .service(state_submissions)
+            .service(submission_txs)
+            .configure(admin::configure)
     })
+    .disable_signals()
     .bind((config.app.host, config.app.port))
     .map_err(|e| Error::Other(e.to_string()))?
-    .run()
-    .await
-    .map_err(|e| Error::Other(e.to_string()))
+    .run();
+
+    Ok(server)
 }
 
 #[get("/health")]
 async fn health(data: web::Data<Arc<HealthReporter>>) -> impl Responder {
-    let report = data.report();
+    let report = data.generate_report();
 
-    let mut response = if report.healthy() {
+    let mut response = if report.is_healthy() {
         HttpResponse::Ok()
     } else {
         HttpResponse::InternalServerError()
@@ -55,25 +82,155 @@ async fn health(data: web::Data<Arc<HealthReporter>>) -> impl Responder {
     response.json(report)
 }
 
+/// Answers as long as the server does, restarting the committer is not expected to fix anything
+/// else.
+#[get("/health/live")]
+async fn liveness() -> impl Responder {
+    HttpResponse::Ok().json(serde_json::json!({ "alive": true }))
+}
+
+#[get("/health/ready")]
+async fn readiness(data: web::Data<Arc<HealthReporter>>) -> impl Responder {
+    let report = data.readiness_report().await;
+
+    let mut response = if report.is_ready() {
+        HttpResponse::Ok()
+    } else {
+        HttpResponse::ServiceUnavailable()
+    };
+
+    response.json(report)
+}
+
 #[get("/status")]
-async fn status(data: web::Data<Arc<StatusReporter<Database>>>) -> impl Responder {
+async fn status(data: web::Data<Arc<StatusReporter<Database, FuelApi>>>) -> impl Responder {
     let report = data.current_status().await?;
 
     Result::Ok(web::Json(report))
 }
 
+#[derive(Deserialize)]
+struct PageQuery {
+    #[serde(default)]
+    offset: u32,
+    #[serde(default = "PageQuery::default_limit")]
+    limit: u32,
+}
+
+impl PageQuery {
+    fn default_limit() -> u32 {
+        20
+    }
+}
+
+impl From<PageQuery> for Page {
+    fn from(query: PageQuery) -> Self {
+        Self {
+            offset: query.offset,
+            limit: query.limit,
+            before: None,
+        }
+    }
+}
+
+#[get("/submissions")]
+async fn block_submissions(
+    data: web::Data<Arc<QueryReporter<Database>>>,
+    page: web::Query<PageQuery>,
+) -> impl Responder {
+    let submissions = data.block_submissions(page.into_inner().into()).await?;
+
+    Result::Ok(web::Json(submissions))
+}
+
+/// Looks the submission up by fuel block hash if given 64 hex characters, by height otherwise.
+#[get("/submissions/{height_or_hash}")]
+async fn block_submission(
+    data: web::Data<Arc<QueryReporter<Database>>>,
+    height_or_hash: web::Path<String>,
+) -> Result<HttpResponse> {
+    let height_or_hash = height_or_hash.into_inner();
+    let lookup = if height_or_hash.len() == 64 {
+        let mut hash = [0; 32];
+        match hex::decode_to_slice(&height_or_hash, &mut hash) {
+            Ok(()) => Some(BlockLookup::Hash(hash)),
+            Err(_) => None,
+        }
+    } else {
+        height_or_hash.parse().ok().map(BlockLookup::Height)
+    };
+
+    let Some(lookup) = lookup else {
+        return Ok(HttpResponse::BadRequest().body("expected a block height or a 32 byte hex hash"));
+    };
+
+    let response = match data.block_submission(lookup).await? {
+        Some(submission) => HttpResponse::Ok().json(submission),
+        None => HttpResponse::NotFound().finish(),
+    };
+
+    Ok(response)
+}
+
+#[get("/state_submissions")]
+async fn state_submissions(
+    data: web::Data<Arc<QueryReporter<Database>>>,
+    page: web::Query<PageQuery>,
+) -> impl Responder {
+    let submissions = data.state_submissions(page.into_inner().into()).await?;
+
+    Result::Ok(web::Json(submissions))
+}
+
+// not flattening a `PageQuery` in, as numbers don't survive that in query strings
+#[derive(Deserialize)]
+struct TxsQuery {
+    #[serde(default)]
+    offset: u32,
+    #[serde(default = "PageQuery::default_limit")]
+    limit: u32,
+    state: Option<String>,
+}
+
+#[get("/txs")]
+async fn submission_txs(
+    data: web::Data<Arc<QueryReporter<Database>>>,
+    query: web::Query<TxsQuery>,
+) -> Result<HttpResponse> {
+    let TxsQuery {
+        offset,
+        limit,
+        state,
+    } = query.into_inner();
+    let state = match state.as_deref() {
+        None => None,
+        Some("pending") => Some(TransactionState::Pending),
+        Some("finalized") => Some(TransactionState::Finalized),
+        Some("failed") => Some(TransactionState::Failed),
+        Some(_) => {
+            return Ok(HttpResponse::BadRequest()
+                .body("expected state to be one of: pending, finalized, failed"))
+        }
+    };
+
+    let page = Page {
+        offset,
+        limit,
+        before: None,
+    };
+    let txs = data.submission_txs(page, state).await?;
+
+    Ok(HttpResponse::Ok().json(txs))
+}
+
 #[get("/metrics")]
 async fn metrics(registry: web::Data<Arc<Registry>>) -> impl Responder {
     let encoder = TextEncoder::new();
     let mut buf: Vec<u8> = vec![];
-    let mut encode = |metrics: &_| {
-        encoder
-            .encode(metrics, &mut buf)
-            .map_err(map_to_internal_err)
-    };
-
-    encode(&registry.gather())?;
-    encode(&prometheus::gather())?;
+    encoder
+        .encode(&registry.gather(), &mut buf)
+        .and_then(|()| encoder.encode(&prometheus::gather(), &mut buf))
+        .map_err(map_to_internal_err)?;
 
     let text = String::from_utf8(buf).map_err(map_to_internal_err)?;
 
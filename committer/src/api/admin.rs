@@ -0,0 +1,256 @@
+use std::{
+    future::{ready, Ready},
+    sync::Arc,
+};
+
+use actix_web::{
+    dev::Payload, error::InternalError, http::StatusCode, post, web, FromRequest, HttpRequest,
+    HttpResponse,
+};
+use serde::{Deserialize, Serialize};
+use services::{Admin, LeaderOnly};
+
+use crate::{errors::Result, Database};
+
+/// The bearer token admin requests must carry, `None` if the admin api is disabled.
+#[derive(Clone)]
+pub(super) struct AdminToken(pub Option<Arc<str>>);
+
+/// The header holding the address of the client behind the proxy, `None` if there is no proxy.
+#[derive(Clone)]
+pub(super) struct ForwardedForHeader(pub Option<Arc<str>>);
+
+pub(super) fn configure(config: &mut web::ServiceConfig) {
+    config
+        .service(pause_runner)
+        .service(resume_runner)
+        .service(force_resubmission)
+        .service(drop_tx)
+        .service(requeue_fragments);
+}
+
+/// Who made an authenticated admin request, for the audit log.
+struct Operator(String);
+
+impl FromRequest for Operator {
+    type Error = InternalError<&'static str>;
+    type Future = Ready<std::result::Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(
+            authenticate(req)
+                .map(Operator)
+                .map_err(|(message, status)| InternalError::new(message, status)),
+        )
+    }
+}
+
+// the operator's address if the request carries the admin token
+fn authenticate(req: &HttpRequest) -> std::result::Result<String, (&'static str, StatusCode)> {
+    let Some(expected) = req
+        .app_data::<AdminToken>()
+        .and_then(|token| token.0.clone())
+    else {
+        return Err(("admin api is disabled", StatusCode::NOT_FOUND));
+    };
+
+    let presented = req
+        .headers()
+        .get("Authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    match presented {
+        Some(presented) if constant_time_eq(presented.as_bytes(), expected.as_bytes()) => {
+            Ok(client_address(req))
+        }
+        _ => {
+            tracing::warn!("rejected unauthenticated admin request to {}", req.path());
+            Err(("missing or invalid admin token", StatusCode::UNAUTHORIZED))
+        }
+    }
+}
+
+// Entries before the last one of a forwarded-for header are whatever the client sent, so only the
+// last one, added by the proxy, is trusted.
+fn client_address(req: &HttpRequest) -> String {
+    let forwarded_for = req
+        .app_data::<ForwardedForHeader>()
+        .and_then(|header| header.0.as_deref())
+        .and_then(|header| req.headers().get_all(header).last())
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.rsplit(',').next())
+        .map(str::trim)
+        .filter(|address| !address.is_empty());
+
+    match forwarded_for {
+        Some(address) => address.to_string(),
+        None => req
+            .peer_addr()
+            .map_or_else(|| "unknown".to_string(), |addr| addr.ip().to_string()),
+    }
+}
+
+// doesn't stop at the first mismatching byte, so that the token can't be guessed by timing
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+#[derive(Serialize)]
+struct RunnerToggled {
+    runner: &'static str,
+    paused: bool,
+}
+
+#[post("/admin/runners/{runner}/pause")]
+async fn pause_runner(
+    operator: Operator,
+    admin: web::Data<Arc<Admin<Database>>>,
+    runner: web::Path<String>,
+) -> Result<HttpResponse> {
+    let paused = admin.pause_runner(&runner, &operator.0).await?;
+
+    Ok(leader_only(paused, |runner| runner_toggled(runner, true)))
+}
+
+#[post("/admin/runners/{runner}/resume")]
+async fn resume_runner(
+    operator: Operator,
+    admin: web::Data<Arc<Admin<Database>>>,
+    runner: web::Path<String>,
+) -> Result<HttpResponse> {
+    let resumed = admin.resume_runner(&runner, &operator.0).await?;
+
+    Ok(leader_only(resumed, |runner| runner_toggled(runner, false)))
+}
+
+fn runner_toggled(runner: Option<&'static str>, paused: bool) -> HttpResponse {
+    match runner {
+        Some(runner) => HttpResponse::Ok().json(RunnerToggled { runner, paused }),
+        None => HttpResponse::NotFound().body("no such runner"),
+    }
+}
+
+// a replica that doesn't lead isn't running the runners, so it can't pause or resubmit anything
+fn leader_only<T>(result: LeaderOnly<T>, respond: impl FnOnce(T) -> HttpResponse) -> HttpResponse {
+    match result {
+        LeaderOnly::Done(done) => respond(done),
+        LeaderOnly::NotLeading => HttpResponse::ServiceUnavailable()
+            .body("this replica is not the leader, send the request to the leading replica"),
+    }
+}
+
+#[post("/admin/resubmissions/{commit_height}")]
+async fn force_resubmission(
+    operator: Operator,
+    admin: web::Data<Arc<Admin<Database>>>,
+    commit_height: web::Path<u32>,
+) -> Result<HttpResponse> {
+    let queued = admin
+        .force_resubmission(commit_height.into_inner(), &operator.0)
+        .await?;
+
+    Ok(leader_only(queued, |()| HttpResponse::Accepted().finish()))
+}
+
+#[post("/admin/txs/{hash}/drop")]
+async fn drop_tx(
+    operator: Operator,
+    admin: web::Data<Arc<Admin<Database>>>,
+    hash: web::Path<String>,
+) -> Result<HttpResponse> {
+    let mut tx_hash = [0; 32];
+    if hex::decode_to_slice(hash.trim_start_matches("0x"), &mut tx_hash).is_err() {
+        return Ok(HttpResponse::BadRequest().body("expected a 32 byte hex tx hash"));
+    }
+
+    let response = if admin.drop_tx(tx_hash, &operator.0).await? {
+        HttpResponse::Ok().finish()
+    } else {
+        HttpResponse::NotFound().body("no pending tx with that hash")
+    };
+
+    Ok(response)
+}
+
+#[derive(Deserialize)]
+struct RequeueFragments {
+    fragment_ids: Vec<u32>,
+}
+
+#[derive(Serialize)]
+struct FragmentsRequeued {
+    detached_from_txs: u64,
+}
+
+#[post("/admin/fragments/requeue")]
+async fn requeue_fragments(
+    operator: Operator,
+    admin: web::Data<Arc<Admin<Database>>>,
+    body: web::Json<RequeueFragments>,
+) -> Result<HttpResponse> {
+    let detached_from_txs = admin
+        .requeue_fragments(body.into_inner().fragment_ids, &operator.0)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(FragmentsRequeued { detached_from_txs }))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::TestRequest;
+
+    use super::*;
+
+    fn given_request(token: Option<&str>, authorization: Option<&str>) -> HttpRequest {
+        let mut request = TestRequest::default().app_data(AdminToken(token.map(Into::into)));
+        if let Some(authorization) = authorization {
+            request = request.insert_header(("Authorization", authorization));
+        }
+
+        request.to_http_request()
+    }
+
+    #[test]
+    fn requests_need_the_configured_token() {
+        let status = |request: HttpRequest| authenticate(&request).err().map(|(_, status)| status);
+
+        assert_eq!(
+            status(given_request(None, Some("Bearer secret"))),
+            Some(StatusCode::NOT_FOUND)
+        );
+        assert_eq!(
+            status(given_request(Some("secret"), None)),
+            Some(StatusCode::UNAUTHORIZED)
+        );
+        assert_eq!(
+            status(given_request(Some("secret"), Some("Bearer wrong"))),
+            Some(StatusCode::UNAUTHORIZED)
+        );
+        assert_eq!(
+            status(given_request(Some("secret"), Some("Bearer secret"))),
+            None
+        );
+    }
+
+    #[test]
+    fn operator_is_the_last_address_in_the_configured_header() {
+        let request = TestRequest::default()
+            .peer_addr("10.0.0.1:8080".parse().unwrap())
+            .app_data(ForwardedForHeader(Some("X-Forwarded-For".into())))
+            .insert_header(("X-Forwarded-For", "1.2.3.4, 192.168.0.7"))
+            .to_http_request();
+
+        assert_eq!(client_address(&request), "192.168.0.7");
+    }
+
+    #[test]
+    fn operator_is_the_peer_without_a_configured_header() {
+        let request = TestRequest::default()
+            .peer_addr("10.0.0.1:8080".parse().unwrap())
+            .insert_header(("X-Forwarded-For", "1.2.3.4"))
+            .to_http_request();
+
+        assert_eq!(client_address(&request), "10.0.0.1");
+    }
+}
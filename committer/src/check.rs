@@ -0,0 +1,271 @@
+use std::{fmt::Display, num::NonZeroUsize};
+
+use ports::{
+    fuel::Api,
+    l1::Contract,
+    types::{WalletRole, U256},
+};
+use url::Url;
+use validator::Validator;
+
+use crate::{config, setup, Database, FuelApi, L1};
+
+enum Outcome {
+    Passed(String),
+    Failed(String),
+    /// Could not be checked, either as it is not configured or as a check it depends on failed.
+    Skipped(String),
+}
+
+/// Outcome of every preflight check, in the order they were run.
+#[derive(Default)]
+pub struct Report {
+    checks: Vec<(&'static str, Outcome)>,
+}
+
+impl Report {
+    /// Skipped checks don't fail the report.
+    pub fn passed(&self) -> bool {
+        !self
+            .checks
+            .iter()
+            .any(|(_, outcome)| matches!(outcome, Outcome::Failed(_)))
+    }
+
+    fn pass(&mut self, check: &'static str, details: impl Display) {
+        self.checks
+            .push((check, Outcome::Passed(details.to_string())));
+    }
+
+    fn fail(&mut self, check: &'static str, details: impl Display) {
+        self.checks
+            .push((check, Outcome::Failed(details.to_string())));
+    }
+
+    fn skip(&mut self, check: &'static str, details: impl Display) {
+        self.checks
+            .push((check, Outcome::Skipped(details.to_string())));
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (check, outcome) in &self.checks {
+            let (label, details) = match outcome {
+                Outcome::Passed(details) => ("PASS", details),
+                Outcome::Failed(details) => ("FAIL", details),
+                Outcome::Skipped(details) => ("SKIP", details),
+            };
+            writeln!(f, "[{label}] {check:<20} {details}")?;
+        }
+
+        let verdict = if self.passed() { "passed" } else { "failed" };
+        write!(f, "preflight check {verdict}")
+    }
+}
+
+/// Checks `config` against the database, the fuel node and L1 without changing any of them.
+pub async fn run(config: &config::Config) -> Report {
+    let mut report = Report::default();
+
+    match config.validate() {
+        Ok(()) => report.pass("config", "valid"),
+        Err(e) => report.fail("config", e),
+    }
+
+    // boxed, as the futures would otherwise be too deeply nested for the compiler to lay out
+    Box::pin(check_database(config, &mut report)).await;
+    Box::pin(check_fuel(config, &mut report)).await;
+    Box::pin(check_l1(config, &mut report)).await;
+
+    report
+}
+
+async fn check_database(config: &config::Config, report: &mut Report) {
+    let database = match Database::connect(&config.app.db).await {
+        Ok(database) => database,
+        Err(e) => return report.fail("database", format!("could not connect: {e}")),
+    };
+
+    match database.migration_status().await {
+        Ok(status) if !status.is_compatible() => report.fail(
+            "database",
+            format!(
+                "migrations {:?} were changed and {:?} are unknown to this version",
+                status.modified, status.unknown
+            ),
+        ),
+        Ok(status) if !status.pending.is_empty() => report.pass(
+            "database",
+            format!(
+                "migrations {:?} are pending and will be applied on startup",
+                status.pending
+            ),
+        ),
+        Ok(_) => report.pass("database", "migrations up to date"),
+        Err(e) => report.fail("database", format!("could not read migrations: {e}")),
+    }
+
+    database.close().await;
+}
+
+async fn check_fuel(config: &config::Config, report: &mut Report) {
+    let internal_config = config::Internal::default();
+
+    // the committer gets by with a single reachable endpoint, so each is checked on its own
+    for url in config.fuel.graphql_endpoints() {
+        let endpoint = FuelApi::connect(
+            std::slice::from_ref(&url),
+            internal_config.fuel_errors_before_unhealthy,
+            NonZeroUsize::MIN,
+        );
+        match endpoint.latest_block().await {
+            Ok(block) => report.pass(
+                "fuel endpoint",
+                format!("{}: at height {}", endpoint_name(&url), block.header.height),
+            ),
+            Err(e) => report.fail(
+                "fuel endpoint",
+                format!("{}: could not fetch latest block: {e}", endpoint_name(&url)),
+            ),
+        }
+    }
+
+    let fuel = FuelApi::connect(
+        &config.fuel.graphql_endpoints(),
+        internal_config.fuel_errors_before_unhealthy,
+        config.fuel.min_agreeing_endpoints,
+    );
+
+    let block = match fuel.latest_block().await {
+        Ok(block) => block,
+        Err(e) => return report.fail("fuel node", format!("could not fetch latest block: {e}")),
+    };
+    report.pass(
+        "fuel node",
+        format!("latest block at height {}", block.header.height),
+    );
+
+    match setup::block_validator(config).validate(&block) {
+        Ok(_) => report.pass(
+            "block producer",
+            "latest block signed by the configured producer",
+        ),
+        Err(e) => report.fail("block producer", e),
+    }
+}
+
+async fn check_l1(config: &config::Config, report: &mut Report) {
+    let (main_signer, blob_signer) = match setup::signers(config).await {
+        Ok(signers) => signers,
+        Err(e) => {
+            report.fail("signers", e);
+            return report.skip("l1", "needs the signers");
+        }
+    };
+    report.pass("signers", "built");
+
+    for url in config.eth.rpcs() {
+        let name = endpoint_name(&url);
+        let endpoint = L1::connect(
+            vec![url],
+            config.eth.state_contract_address,
+            main_signer.clone(),
+            blob_signer.clone(),
+            config::Internal::default().eth_errors_before_unhealthy,
+        )
+        .await;
+        let block_number = match endpoint {
+            Ok(endpoint) => ports::l1::Api::get_block_number(&endpoint).await,
+            Err(e) => Err(e),
+        };
+        match block_number {
+            Ok(height) => report.pass(
+                "l1 endpoint",
+                format!("{name}: at block {}", u64::from(height)),
+            ),
+            Err(e) => report.fail("l1 endpoint", format!("{name}: {e}")),
+        }
+    }
+
+    let l1 = match L1::connect(
+        config.eth.rpcs(),
+        config.eth.state_contract_address,
+        main_signer,
+        blob_signer,
+        config::Internal::default().eth_errors_before_unhealthy,
+    )
+    .await
+    {
+        Ok(l1) => l1,
+        Err(e) => return report.fail("l1", format!("could not connect: {e}")),
+    };
+    // the commit interval is read from the contract on connecting
+    report.pass(
+        "commit interval",
+        format!("BLOCKS_PER_COMMIT_INTERVAL is {}", l1.commit_interval()),
+    );
+
+    match (l1.chain_id().await, config.eth.chain_id) {
+        (Err(e), _) => report.fail("l1 chain id", e),
+        (Ok(actual), None) => report.fail(
+            "l1 chain id",
+            format!("endpoints are on chain {actual}, `eth.chain_id` must be set to check it"),
+        ),
+        (Ok(actual), Some(expected)) if actual == expected => {
+            report.pass("l1 chain id", format!("chain {actual}"));
+        }
+        (Ok(actual), Some(expected)) => report.fail(
+            "l1 chain id",
+            format!("expected chain {expected}, endpoints are on chain {actual}"),
+        ),
+    }
+
+    check_balance(&l1, WalletRole::Main, "main wallet", report).await;
+    if config.eth.blob_pool_key.is_some() {
+        check_balance(&l1, WalletRole::Blob, "blob pool wallet", report).await;
+    }
+}
+
+// leaves out the path and query, which may carry an api key
+fn endpoint_name(url: &Url) -> String {
+    match (url.host_str(), url.port()) {
+        (Some(host), Some(port)) => format!("{host}:{port}"),
+        (Some(host), None) => host.to_string(),
+        (None, _) => url.scheme().to_string(),
+    }
+}
+
+async fn check_balance(l1: &L1, wallet: WalletRole, check: &'static str, report: &mut Report) {
+    match ports::l1::Api::balance(l1, wallet).await {
+        Ok(balance) if balance == U256::ZERO => report.fail(check, "no balance"),
+        Ok(balance) => report.pass(check, format!("balance of {balance} wei")),
+        Err(e) => report.fail(check, format!("could not fetch balance: {e}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fails_only_if_a_check_failed() {
+        let mut report = Report::default();
+        report.pass("database", "migrations up to date");
+        report.skip("l1", "needs the signers");
+        assert!(report.passed());
+
+        report.fail("main wallet", "no balance");
+        assert!(!report.passed());
+        assert!(report
+            .to_string()
+            .contains("[FAIL] main wallet          no balance"));
+    }
+
+    #[test]
+    fn endpoints_are_named_without_their_api_key() {
+        let url = Url::parse("https://mainnet.infura.io:8545/v3/secret-key").unwrap();
+
+        assert_eq!(endpoint_name(&url), "mainnet.infura.io:8545");
+    }
+}
@@ -0,0 +1,326 @@
+use std::{
+    borrow::Cow,
+    future::Future,
+    io::{self, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use ports::{storage::Storage, types::Page};
+use serde::Serialize;
+use services::{BlockSubmissionView, QueryReporter};
+
+use crate::{
+    check,
+    config::{
+        self, Command, ExportFormat, ExportTable, FragmentsCommand, SubmissionsCommand, TxsCommand,
+    },
+    errors::{Error, Result, WithContext},
+    Database,
+};
+
+/// Runs one of the subcommands, none of which start the services of the committer.
+pub async fn run(command: Command) -> Result<ExitCode> {
+    match command {
+        Command::Check { config_path } => {
+            let config = config::load(config_path).with_context(|| "failed to parse config")?;
+            let report = check::run(&config).await;
+            println!("{report}");
+
+            if !report.passed() {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        Command::Status { config_path } => {
+            let database = connect(config_path).await?;
+            print_json(&status(&database).await?)?;
+        }
+        Command::Submissions(SubmissionsCommand::List { config_path, page }) => {
+            let reporter = QueryReporter::new(connect(config_path).await?);
+            print_json(&reporter.block_submissions(page.into()).await?)?;
+        }
+        Command::Fragments(FragmentsCommand::List {
+            config_path,
+            unsubmitted,
+            page,
+        }) => {
+            let reporter = QueryReporter::new(connect(config_path).await?);
+            if unsubmitted {
+                print_json(&reporter.unsubmitted_fragments(page.into()).await?)?;
+            } else {
+                print_json(&reporter.state_submissions(page.into()).await?)?;
+            }
+        }
+        Command::Txs(TxsCommand::List {
+            config_path,
+            state,
+            page,
+        }) => {
+            let reporter = QueryReporter::new(connect(config_path).await?);
+            let txs = reporter
+                .submission_txs(page.into(), state.map(Into::into))
+                .await?;
+            print_json(&txs)?;
+        }
+        Command::Migrate { config_path } => {
+            let database = connect(config_path).await?;
+            database
+                .migrate()
+                .await
+                .map_err(Error::from)
+                .with_context(|| "failed to migrate the database")?;
+            println!("database migrated");
+        }
+        Command::Export {
+            config_path,
+            table,
+            format,
+        } => {
+            let reporter = QueryReporter::new(connect(config_path).await?);
+            export(&reporter, table, format, &mut io::stdout().lock()).await?;
+        }
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+async fn connect(config_path: PathBuf) -> Result<Database> {
+    let config = config::load(config_path).with_context(|| "failed to parse config")?;
+
+    Database::connect(&config.app.db)
+        .await
+        .map_err(Error::from)
+        .with_context(|| "failed to connect to database")
+}
+
+#[derive(Serialize)]
+struct Status {
+    latest_submission: Option<BlockSubmissionView>,
+    latest_completed_submission: Option<BlockSubmissionView>,
+    pending_txs: usize,
+    unsubmitted_fragments: UnsubmittedFragments,
+    latest_finalized_state_height: Option<u32>,
+    last_processed_l1_height: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct UnsubmittedFragments {
+    count: u64,
+    bytes: u64,
+    /// RFC 3339.
+    oldest_created_at: Option<String>,
+}
+
+async fn status(database: &Database) -> Result<Status> {
+    let fragments = database.unsubmitted_fragments_summary().await?;
+
+    Ok(Status {
+        latest_submission: database.submission_w_latest_block().await?.map(Into::into),
+        latest_completed_submission: database
+            .completed_submission_w_latest_block()
+            .await?
+            .map(Into::into),
+        pending_txs: database.get_pending_txs().await?.len(),
+        unsubmitted_fragments: UnsubmittedFragments {
+            count: fragments.count,
+            bytes: fragments.bytes,
+            oldest_created_at: fragments
+                .oldest_created_at
+                .map(|created_at| created_at.to_rfc3339()),
+        },
+        latest_finalized_state_height: database.latest_finalized_state_height().await?,
+        last_processed_l1_height: database.last_processed_l1_height().await?.map(Into::into),
+    })
+}
+
+fn print_json(value: &impl Serialize) -> Result<()> {
+    let mut stdout = io::stdout().lock();
+    serde_json::to_writer_pretty(&mut stdout, value)?;
+    writeln!(stdout)?;
+
+    Ok(())
+}
+
+async fn export(
+    reporter: &QueryReporter<Database>,
+    table: ExportTable,
+    format: ExportFormat,
+    out: &mut impl Write,
+) -> Result<()> {
+    match (table, format) {
+        (ExportTable::Submissions, ExportFormat::Json) => write_json(
+            out,
+            &all(
+                |page| reporter.block_submissions(page),
+                |row| row.fuel_block_height,
+            )
+            .await?,
+        ),
+        (ExportTable::Submissions, ExportFormat::Csv) => {
+            let submissions = all(
+                |page| reporter.block_submissions(page),
+                |row| row.fuel_block_height,
+            )
+            .await?;
+            write_csv(
+                out,
+                &[
+                    "fuel_block_hash",
+                    "fuel_block_height",
+                    "completed",
+                    "submittal_height",
+                ],
+                submissions.into_iter().map(|submission| {
+                    vec![
+                        submission.fuel_block_hash,
+                        submission.fuel_block_height.to_string(),
+                        submission.completed.to_string(),
+                        submission.submittal_height.to_string(),
+                    ]
+                }),
+            )
+        }
+        (ExportTable::Fragments, ExportFormat::Json) => write_json(
+            out,
+            &all(
+                |page| reporter.state_submissions(page),
+                |row| row.fuel_block_height,
+            )
+            .await?,
+        ),
+        (ExportTable::Fragments, ExportFormat::Csv) => {
+            let submissions = all(
+                |page| reporter.state_submissions(page),
+                |row| row.fuel_block_height,
+            )
+            .await?;
+            write_csv(
+                out,
+                &[
+                    "fuel_block_hash",
+                    "fuel_block_height",
+                    "id",
+                    "fragment_idx",
+                    "size",
+                    "created_at",
+                    "status",
+                ],
+                submissions.into_iter().flat_map(|submission| {
+                    submission.fragments.into_iter().map(move |fragment| {
+                        vec![
+                            submission.fuel_block_hash.clone(),
+                            submission.fuel_block_height.to_string(),
+                            fragment.id.to_string(),
+                            fragment.fragment_idx.to_string(),
+                            fragment.size.to_string(),
+                            fragment.created_at,
+                            fragment.status.to_string(),
+                        ]
+                    })
+                }),
+            )
+        }
+        (ExportTable::Txs, ExportFormat::Json) => write_json(
+            out,
+            &all(|page| reporter.submission_txs(page, None), |row| row.id).await?,
+        ),
+        (ExportTable::Txs, ExportFormat::Csv) => {
+            let txs = all(|page| reporter.submission_txs(page, None), |row| row.id).await?;
+            write_csv(
+                out,
+                &["hash", "state", "fragment_ids"],
+                txs.into_iter().map(|tx| {
+                    let fragment_ids: Vec<_> = tx
+                        .fragments
+                        .iter()
+                        .map(|fragment| fragment.fragment_id.to_string())
+                        .collect();
+                    vec![tx.hash, tx.state.to_string(), fragment_ids.join(";")]
+                }),
+            )
+        }
+    }
+}
+
+/// Pages through `fetch` until it runs out of rows. Every page starts below the `key` of the last
+/// row of the one before, so rows added while exporting don't shift the pages.
+async fn all<T, F, Fut>(fetch: F, key: impl Fn(&T) -> u32) -> Result<Vec<T>>
+where
+    F: Fn(Page) -> Fut,
+    Fut: Future<Output = services::Result<Vec<T>>>,
+{
+    let limit = QueryReporter::<Database>::MAX_PAGE_LIMIT;
+    let mut rows = vec![];
+    loop {
+        let page = fetch(Page {
+            offset: 0,
+            limit,
+            before: rows.last().map(|row| u64::from(key(row))),
+        })
+        .await?;
+        let is_last = page.len() < limit as usize;
+        rows.extend(page);
+
+        if is_last {
+            return Ok(rows);
+        }
+    }
+}
+
+fn write_json(out: &mut impl Write, value: &impl Serialize) -> Result<()> {
+    serde_json::to_writer_pretty(&mut *out, value)?;
+    writeln!(out)?;
+
+    Ok(())
+}
+
+fn write_csv(
+    out: &mut impl Write,
+    header: &[&str],
+    rows: impl IntoIterator<Item = Vec<String>>,
+) -> Result<()> {
+    writeln!(out, "{}", header.join(","))?;
+    for row in rows {
+        let fields: Vec<_> = row.iter().map(|field| csv_field(field)).collect();
+        writeln!(out, "{}", fields.join(","))?;
+    }
+
+    Ok(())
+}
+
+/// Quotes the field if it would otherwise break the row, as per RFC 4180.
+fn csv_field(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_fields_are_quoted_only_when_needed() {
+        // given
+        let mut out = vec![];
+
+        // when
+        write_csv(
+            &mut out,
+            &["hash", "note"],
+            [
+                vec!["abc".to_string(), "plain".to_string()],
+                vec!["def".to_string(), "a, \"quoted\" note".to_string()],
+            ],
+        )
+        .unwrap();
+
+        // then
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "hash,note\nabc,plain\ndef,\"a, \"\"quoted\"\" note\"\n"
+        );
+    }
+}
@@ -1,7 +1,7 @@
-use std::{net::Ipv4Addr, path::PathBuf, str::FromStr, time::Duration};
+use std::{net::Ipv4Addr, num::NonZeroUsize, path::PathBuf, str::FromStr, time::Duration};
 
-use clap::{command, Parser};
-use eth::Address;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use eth::{Address, KeySource};
 use serde::Deserialize;
 use storage::DbConfig;
 use url::Url;
@@ -15,14 +15,31 @@ pub struct Config {
 
 impl Config {
     pub fn validate(&self) -> crate::errors::Result<()> {
-        if let Some(blob_pool_wallet_key) = &self.eth.blob_pool_key_arn {
-            if blob_pool_wallet_key == &self.eth.main_key_arn {
+        if let Some(blob_pool_wallet_key) = &self.eth.blob_pool_key {
+            if blob_pool_wallet_key == &self.eth.main_key {
                 return Err(crate::errors::Error::Other(
                     "Wallet key and blob pool wallet key must be different".to_string(),
                 ));
             }
         }
 
+        let num_fuel_endpoints = self.fuel.graphql_endpoints().len();
+        if self.fuel.min_agreeing_endpoints.get() > num_fuel_endpoints {
+            return Err(crate::errors::Error::Other(format!(
+                "{} fuel endpoints are required to agree, but only {num_fuel_endpoints} are \
+                 configured",
+                self.fuel.min_agreeing_endpoints
+            )));
+        }
+
+        if self.app.max_fragments_per_tx.get() > ports::l1::MAX_BLOBS_PER_BLOCK {
+            return Err(crate::errors::Error::Other(format!(
+                "{} fragments per tx exceed the {} blobs an L1 block can carry",
+                self.app.max_fragments_per_tx,
+                ports::l1::MAX_BLOBS_PER_BLOCK
+            )));
+        }
+
         Ok(())
     }
 }
@@ -32,21 +49,109 @@ pub struct Fuel {
     /// URL to a fuel-core graphql endpoint.
     #[serde(deserialize_with = "parse_url")]
     pub graphql_endpoint: Url,
+    /// Further fuel-core graphql endpoints to fail over to when others are down or lagging.
+    #[serde(default, deserialize_with = "parse_urls")]
+    pub fallback_graphql_endpoints: Vec<Url>,
+    /// How many endpoints must report the same block id before a block is acted upon.
+    #[serde(default = "default_min_agreeing_endpoints")]
+    pub min_agreeing_endpoints: NonZeroUsize,
     /// Block producer address
     pub block_producer_address: ports::fuel::FuelBytes32,
+    /// Chain config hash expected in the genesis block. Genesis blocks are rejected if unset.
+    pub chain_config_hash: Option<ports::fuel::FuelBytes32>,
+    /// How often the endpoints are asked for the next block once the chain tip is reached, and
+    /// how long to wait before asking again after an error.
+    #[serde(
+        default = "default_block_poll_interval",
+        deserialize_with = "human_readable_duration"
+    )]
+    pub block_poll_interval: Duration,
+}
+
+impl Fuel {
+    /// The primary endpoint followed by the fallbacks.
+    pub fn graphql_endpoints(&self) -> Vec<Url> {
+        std::iter::once(&self.graphql_endpoint)
+            .chain(&self.fallback_graphql_endpoints)
+            .cloned()
+            .collect()
+    }
+}
+
+fn default_min_agreeing_endpoints() -> NonZeroUsize {
+    NonZeroUsize::MIN
+}
+
+fn default_block_poll_interval() -> Duration {
+    fuel::DEFAULT_BLOCK_POLL_INTERVAL
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Eth {
-    /// The AWS KMS key ID authorized by the L1 bridging contracts to post block commitments.
-    pub main_key_arn: String,
-    /// The AWS KMS key ID for posting L2 state to L1.
-    pub blob_pool_key_arn: Option<String>,
-    /// URL to a Ethereum RPC endpoint.
+    /// Key authorized by the L1 bridging contracts to post block commitments. One of
+    /// `Kms(<key id>)`, `Env(<variable>)`, `Keystore(<path>,<password variable>)` or
+    /// `Web3Signer(<url>,<public key>)`, a bare value is taken to be a KMS key id.
+    #[serde(alias = "main_key_arn", deserialize_with = "parse_key_source")]
+    pub main_key: KeySource,
+    /// Key for posting L2 state to L1, same format as `main_key`.
+    #[serde(
+        default,
+        alias = "blob_pool_key_arn",
+        deserialize_with = "parse_optional_key_source"
+    )]
+    pub blob_pool_key: Option<KeySource>,
+    /// URL to a Ethereum RPC endpoint. `ws(s)://` urls stream events over a subscription,
+    /// `http(s)://` urls poll for them.
     #[serde(deserialize_with = "parse_url")]
     pub rpc: Url,
+    /// Further Ethereum RPC endpoints. Reads are spread over all healthy endpoints, transactions
+    /// only go here when `rpc` is unhealthy or failing.
+    #[serde(default, deserialize_with = "parse_urls")]
+    pub fallback_rpcs: Vec<Url>,
     /// Ethereum address of the fuel chain state contract.
     pub state_contract_address: Address,
+    /// Chain id the RPC endpoints are expected to report. Only checked by the `check` subcommand,
+    /// which fails without it.
+    #[serde(default)]
+    pub chain_id: Option<u64>,
+    /// Balance [gwei] below which the main wallet is reported as low and the committer as
+    /// degraded. 0 turns the check off.
+    #[serde(default)]
+    pub main_key_low_balance_gwei: u64,
+    /// Same as `main_key_low_balance_gwei`, for the blob pool wallet.
+    #[serde(default)]
+    pub blob_pool_key_low_balance_gwei: u64,
+}
+
+impl Eth {
+    /// The primary endpoint followed by the fallbacks.
+    pub fn rpcs(&self) -> Vec<Url> {
+        std::iter::once(&self.rpc)
+            .chain(&self.fallback_rpcs)
+            .cloned()
+            .collect()
+    }
+
+    pub fn uses_kms(&self) -> bool {
+        self.main_key.is_kms() || self.blob_pool_key.as_ref().is_some_and(KeySource::is_kms)
+    }
+}
+
+fn parse_key_source<'de, D>(deserializer: D) -> Result<KeySource, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let key: String = Deserialize::deserialize(deserializer)?;
+    key.parse().map_err(serde::de::Error::custom)
+}
+
+fn parse_optional_key_source<'de, D>(deserializer: D) -> Result<Option<KeySource>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let key: Option<String> = Deserialize::deserialize(deserializer)?;
+    key.map(|key| key.parse().map_err(serde::de::Error::custom))
+        .transpose()
 }
 
 fn parse_url<'de, D>(deserializer: D) -> Result<Url, D::Error>
@@ -60,6 +165,22 @@ where
     })
 }
 
+fn parse_urls<'de, D>(deserializer: D) -> Result<Vec<Url>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let url_strs: Vec<String> = Deserialize::deserialize(deserializer)?;
+    url_strs
+        .into_iter()
+        .map(|url_str| {
+            Url::from_str(&url_str).map_err(|e| {
+                let msg = format!("Failed to parse URL '{url_str}': {e};");
+                serde::de::Error::custom(msg)
+            })
+        })
+        .collect()
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct App {
     /// Port used by the started server
@@ -68,11 +189,68 @@ pub struct App {
     pub host: Ipv4Addr,
     /// Postgres database configuration
     pub db: DbConfig,
-    /// How often to check the latest fuel block
+    /// How often the state committer and listener poll for work. The block committer and state
+    /// importer react to streamed fuel blocks and only use this as a retry delay.
     #[serde(deserialize_with = "human_readable_duration")]
     pub block_check_interval: Duration,
     /// Number of L1 blocks that need to pass to accept the tx as finalized
     pub num_blocks_to_finalize_tx: u64,
+    /// Number of L1 blocks that need to be built on top of a commit event before the submission
+    /// is marked as completed. Defaults to 0, i.e. completed as soon as the event is seen.
+    #[serde(default)]
+    pub num_blocks_to_confirm_commit: u64,
+    /// How often commit events waiting for confirmations are checked against the L1 chain, the
+    /// L1 block time by default.
+    #[serde(
+        default = "default_commit_confirmation_check_interval",
+        deserialize_with = "human_readable_duration"
+    )]
+    pub commit_confirmation_check_interval: Duration,
+    /// How long a runner may go without a successful iteration before it is reported as stale.
+    /// The commit listener is exempt, it runs for as long as its event stream lasts.
+    #[serde(
+        default = "default_runner_max_age",
+        deserialize_with = "human_readable_duration"
+    )]
+    pub runner_max_age: Duration,
+    /// How long to wait on shutdown for in-flight L1 sends and database writes to finish.
+    #[serde(
+        default = "default_shutdown_timeout",
+        deserialize_with = "human_readable_duration"
+    )]
+    pub shutdown_timeout: Duration,
+    /// Bearer token the admin api expects. The admin api is disabled if not set.
+    #[serde(default)]
+    pub admin_token: Option<String>,
+    /// Header the proxy in front of the api puts the client address in, e.g. `X-Forwarded-For`.
+    /// Admin actions are recorded as taken by its last entry, the one the proxy added. If not
+    /// set they are recorded as taken by the peer address, which behind a proxy is the proxy's.
+    #[serde(default)]
+    pub admin_forwarded_for_header: Option<String>,
+    /// Most state fragments posted in a single blob tx. Cannot exceed the blobs an L1 block can
+    /// carry, which is also the default.
+    #[serde(default = "default_max_fragments_per_tx")]
+    pub max_fragments_per_tx: NonZeroUsize,
+    /// Runs the committer against an in-memory database, logging the L1 txs it would have sent
+    /// instead of sending them. Can also be turned on with `--dry-run`.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+fn default_commit_confirmation_check_interval() -> Duration {
+    Duration::from_secs(12)
+}
+
+fn default_runner_max_age() -> Duration {
+    Duration::from_secs(10 * 60)
+}
+
+fn default_shutdown_timeout() -> Duration {
+    Duration::from_secs(25)
+}
+
+fn default_max_fragments_per_tx() -> NonZeroUsize {
+    NonZeroUsize::new(ports::l1::MAX_BLOBS_PER_BLOCK).expect("an L1 block carries blobs")
 }
 
 fn human_readable_duration<'de, D>(deserializer: D) -> Result<Duration, D::Error>
@@ -92,6 +270,11 @@ pub struct Internal {
     pub between_eth_event_stream_restablishing_attempts: Duration,
     pub eth_errors_before_unhealthy: usize,
     pub balance_update_interval: Duration,
+    pub balance_runway_window: Duration,
+    pub runner_initial_backoff: Duration,
+    pub runner_max_backoff: Duration,
+    pub leader_election_interval: Duration,
+    pub unseen_tx_timeout: Duration,
 }
 
 impl Default for Internal {
@@ -101,6 +284,11 @@ impl Default for Internal {
             between_eth_event_stream_restablishing_attempts: Duration::from_secs(3),
             eth_errors_before_unhealthy: 3,
             balance_update_interval: Duration::from_secs(10),
+            balance_runway_window: Duration::from_secs(6 * 60 * 60),
+            runner_initial_backoff: Duration::from_secs(1),
+            runner_max_backoff: Duration::from_secs(5 * 60),
+            leader_election_interval: Duration::from_secs(5),
+            unseen_tx_timeout: Duration::from_secs(5 * 60),
         }
     }
 }
@@ -110,19 +298,158 @@ impl Default for Internal {
     version,
     about,
     propagate_version = true,
-    arg_required_else_help(true)
+    arg_required_else_help(true),
+    args_conflicts_with_subcommands(true),
+    subcommand_negates_reqs(true)
 )]
-struct Cli {
-    #[arg(value_name = "FILE", help = "Path to the configuration file")]
-    config_path: PathBuf,
+pub struct Cli {
+    #[arg(
+        value_name = "FILE",
+        help = "Path to the configuration file",
+        required = true
+    )]
+    pub config_path: Option<PathBuf>,
+    /// Logs the L1 txs the committer would send instead of sending them. Nothing is written to
+    /// the database.
+    #[arg(long)]
+    pub dry_run: bool,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Checks the configuration against the database, the fuel node and L1 without starting the
+    /// committer. Exits with a non-zero code if any check fails.
+    Check {
+        #[arg(value_name = "FILE", help = "Path to the configuration file")]
+        config_path: PathBuf,
+    },
+    /// Prints what the database holds on the progress of the committer.
+    Status {
+        #[arg(value_name = "FILE", help = "Path to the configuration file")]
+        config_path: PathBuf,
+    },
+    /// Lists the fuel block submissions, latest first.
+    #[command(subcommand)]
+    Submissions(SubmissionsCommand),
+    /// Lists the fragments state submissions were split into.
+    #[command(subcommand)]
+    Fragments(FragmentsCommand),
+    /// Lists the L1 transactions carrying fragments, latest first.
+    #[command(subcommand)]
+    Txs(TxsCommand),
+    /// Applies the pending database migrations and exits.
+    Migrate {
+        #[arg(value_name = "FILE", help = "Path to the configuration file")]
+        config_path: PathBuf,
+    },
+    /// Writes every row of a table to stdout.
+    Export {
+        #[arg(value_name = "FILE", help = "Path to the configuration file")]
+        config_path: PathBuf,
+        #[arg(value_enum)]
+        table: ExportTable,
+        #[arg(long, value_enum, default_value_t = ExportFormat::Json)]
+        format: ExportFormat,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SubmissionsCommand {
+    List {
+        #[arg(value_name = "FILE", help = "Path to the configuration file")]
+        config_path: PathBuf,
+        #[command(flatten)]
+        page: PageArgs,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum FragmentsCommand {
+    List {
+        #[arg(value_name = "FILE", help = "Path to the configuration file")]
+        config_path: PathBuf,
+        /// Lists the fragments not carried by any pending or finalized tx, in the order they will
+        /// be submitted in.
+        #[arg(long)]
+        unsubmitted: bool,
+        #[command(flatten)]
+        page: PageArgs,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TxsCommand {
+    List {
+        #[arg(value_name = "FILE", help = "Path to the configuration file")]
+        config_path: PathBuf,
+        #[arg(long, value_enum)]
+        state: Option<TxState>,
+        #[command(flatten)]
+        page: PageArgs,
+    },
 }
 
-pub fn parse() -> crate::errors::Result<Config> {
-    let cli = Cli::parse();
+#[derive(Args)]
+pub struct PageArgs {
+    #[arg(long, default_value_t = 0)]
+    pub offset: u32,
+    #[arg(long, default_value_t = 20)]
+    pub limit: u32,
+}
+
+impl From<PageArgs> for ports::types::Page {
+    fn from(args: PageArgs) -> Self {
+        Self {
+            offset: args.offset,
+            limit: args.limit,
+            before: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum TxState {
+    Pending,
+    Finalized,
+    Failed,
+}
+
+impl From<TxState> for ports::types::TransactionState {
+    fn from(state: TxState) -> Self {
+        match state {
+            TxState::Pending => Self::Pending,
+            TxState::Finalized => Self::Finalized,
+            TxState::Failed => Self::Failed,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ExportTable {
+    Submissions,
+    Fragments,
+    Txs,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
 
+pub fn load(config_path: PathBuf) -> crate::errors::Result<Config> {
     let config = config::Config::builder()
-        .add_source(config::File::from(cli.config_path))
-        .add_source(config::Environment::with_prefix("COMMITTER").separator("__"))
+        .add_source(config::File::from(config_path))
+        .add_source(
+            config::Environment::with_prefix("COMMITTER")
+                .separator("__")
+                .try_parsing(true)
+                .list_separator(",")
+                .with_list_parse_key("fuel.fallback_graphql_endpoints")
+                .with_list_parse_key("eth.fallback_rpcs"),
+        )
         .build()?;
 
     Ok(config.try_deserialize()?)
@@ -1,28 +1,60 @@
 #![deny(unused_crate_dependencies)]
 mod api;
+mod check;
+mod commands;
 mod config;
 mod errors;
 mod setup;
 
+use std::process::ExitCode;
+
 use api::launch_api_server;
-use errors::{Result, WithContext};
+use clap::Parser;
+use errors::{Error, Result, WithContext};
+use eth::NonceManager;
 use metrics::prometheus::Registry;
 use ports::l1::Contract;
+use services::{
+    Admin, HealthReporter, LeadFenced, Leadership, QueryReporter, RunnerHealth, StatusReporter,
+    Supervisor,
+};
 use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
 
 use crate::setup::shut_down;
 
-pub type L1 = eth::WebsocketClient;
+pub type L1 = eth::RpcClient;
 pub type AwsClient = eth::AwsClient;
 pub type Database = storage::Postgres;
-pub type FuelApi = fuel::HttpClient;
+pub type FuelApi = fuel::FailoverClient;
 pub type Validator = validator::BlockValidator;
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
     setup::logger();
 
-    let config = config::parse().with_context(|| "failed to parse config")?;
+    let cli = config::Cli::parse();
+    match cli.command {
+        Some(command) => commands::run(command).await,
+        None => {
+            let config_path = cli
+                .config_path
+                .expect("clap to require a config path unless given a subcommand");
+            let mut config = config::load(config_path).with_context(|| "failed to parse config")?;
+            config.app.dry_run |= cli.dry_run;
+
+            if config.app.dry_run {
+                dry_run(config).await?;
+            } else {
+                run(config).await?;
+            }
+
+            Ok(ExitCode::SUCCESS)
+        }
+    }
+}
+
+async fn run(config: config::Config) -> Result<()> {
     config
         .validate()
         .with_context(|| "config validation failed")?;
@@ -33,45 +65,90 @@ async fn main() -> Result<()> {
 
     let internal_config = config::Internal::default();
     let cancel_token = CancellationToken::new();
+    setup::cancel_on_shutdown_signal(cancel_token.clone())
+        .with_context(|| "could not listen for shutdown signals")?;
 
     let metrics_registry = Registry::default();
+    let leadership = Leadership::default();
+    let supervisor = Supervisor::new(RunnerHealth::default())
+        .with_backoff(
+            internal_config.runner_initial_backoff,
+            internal_config.runner_max_backoff,
+        )
+        .with_max_age(config.app.runner_max_age)
+        .with_leadership(leadership.clone());
 
     let (fuel_adapter, fuel_health_check) =
         setup::fuel_adapter(&config, &internal_config, &metrics_registry);
 
-    let (ethereum_rpc, eth_health_check) =
-        setup::l1_adapter(&config, &internal_config, &metrics_registry)
-            .await
-            .with_context(|| "could not setup l1 adapter")?;
+    let (ethereum_rpc, eth_health_check) = setup::l1_adapter(
+        &config,
+        &internal_config,
+        Some(
+            NonceManager::new(storage.clone())
+                .with_unseen_tx_timeout(internal_config.unseen_tx_timeout),
+        ),
+        &metrics_registry,
+    )
+    .await
+    .with_context(|| "could not setup l1 adapter")?;
 
-    let commit_interval = ethereum_rpc.commit_interval();
+    // replicas sharing the database take turns, only the leader writes to L1
+    let leader_election = storage.leader_election();
+    let leader_elector_handle = setup::leader_elector(
+        &internal_config,
+        leader_election.clone(),
+        leadership.clone(),
+        ethereum_rpc.clone(),
+        &metrics_registry,
+        &supervisor,
+        cancel_token.clone(),
+    );
+
+    // the committers confirm the lead right before every L1 write
+    let fenced_l1 = LeadFenced::new(
+        ethereum_rpc.clone(),
+        leadership.clone(),
+        leader_election.clone(),
+    );
 
-    let wallet_balance_tracker_handle = setup::wallet_balance_tracker(
+    let status_reporter = StatusReporter::new(
+        storage.clone(),
+        fuel_adapter.clone(),
+        ethereum_rpc.commit_interval(),
+    );
+
+    let (wallet_balance_tracker_handle, wallet_balances) = setup::wallet_balance_tracker(
+        &config,
         &internal_config,
         &metrics_registry,
         ethereum_rpc.clone(),
+        &supervisor,
         cancel_token.clone(),
     );
 
-    let committer_handle = setup::block_committer(
-        commit_interval,
-        ethereum_rpc.clone(),
+    let (committer_handle, resubmissions) = setup::block_committer(
+        fenced_l1.clone(),
         storage.clone(),
         fuel_adapter.clone(),
         &config,
         &metrics_registry,
+        &supervisor,
         cancel_token.clone(),
     );
 
     let listener_handle = setup::l1_event_listener(
+        &config,
         &internal_config,
         ethereum_rpc.clone(),
         storage.clone(),
         &metrics_registry,
+        &supervisor,
         cancel_token.clone(),
     );
 
     let mut handles = vec![
+        leader_elector_handle,
         wallet_balance_tracker_handle,
         committer_handle,
         listener_handle,
@@ -79,20 +156,29 @@ async fn main() -> Result<()> {
 
     // If the blob pool wallet key is set, we need to start
     // the state committer and state importer
-    if config.eth.blob_pool_key_arn.is_some() {
+    if config.eth.blob_pool_key.is_some() {
         let state_committer_handle = setup::state_committer(
-            ethereum_rpc.clone(),
+            fenced_l1,
             storage.clone(),
+            &supervisor,
             cancel_token.clone(),
+            &metrics_registry,
             &config,
         );
 
-        let state_importer_handle =
-            setup::state_importer(fuel_adapter, storage.clone(), cancel_token.clone(), &config);
+        let state_importer_handle = setup::state_importer(
+            fuel_adapter,
+            storage.clone(),
+            &supervisor,
+            cancel_token.clone(),
+            &metrics_registry,
+            &config,
+        );
 
         let state_listener_handle = setup::state_listener(
             ethereum_rpc,
             storage.clone(),
+            &supervisor,
             cancel_token.clone(),
             &metrics_registry,
             &config,
@@ -103,17 +189,120 @@ async fn main() -> Result<()> {
         handles.push(state_listener_handle);
     }
 
-    launch_api_server(
+    let health_reporter = HealthReporter::new(fuel_health_check, eth_health_check)
+        .with_wallet_balances(wallet_balances)
+        .with_storage(storage.clone())
+        .with_runners(supervisor.health())
+        .with_leadership(leadership.clone());
+
+    let api_server = launch_api_server(
         &config,
         metrics_registry,
-        storage.clone(),
-        fuel_health_check,
-        eth_health_check,
+        status_reporter,
+        health_reporter,
+        QueryReporter::new(storage.clone()),
+        Admin::new(
+            storage.clone(),
+            supervisor.health(),
+            resubmissions,
+            leadership,
+        ),
     )
-    .await
     .with_context(|| "api server")?;
+    let api_server_handle = api_server.handle();
+    let mut api_server = tokio::spawn(api_server);
+
+    // runs until a shutdown signal arrives, unless the api server goes down first
+    let api_server_result = tokio::select! {
+        () = cancel_token.cancelled() => Ok(()),
+        result = &mut api_server => result
+            .map_err(Error::from)
+            .and_then(|served| served.map_err(Error::from)),
+    };
+
+    shut_down(
+        cancel_token,
+        handles,
+        api_server_handle,
+        storage,
+        leader_election,
+        config.app.shutdown_timeout,
+    )
+    .await?;
+
+    api_server_result.with_context(|| "api server")
+}
+
+/// Runs the block and state committers, and what they depend on, against an in-memory database
+/// with every L1 write logged instead of sent.
+async fn dry_run(config: config::Config) -> Result<()> {
+    config
+        .validate()
+        .with_context(|| "config validation failed")?;
+    warn!("dry run: no L1 txs will be sent and nothing will be written to the database");
+
+    // nothing is sent, so there are no txs to track
+    let storage = storage::InMemory::new().forgetting_sent_fragments();
+
+    let internal_config = config::Internal::default();
+    let cancel_token = CancellationToken::new();
+    setup::cancel_on_shutdown_signal(cancel_token.clone())
+        .with_context(|| "could not listen for shutdown signals")?;
+
+    let metrics_registry = Registry::default();
+    // no leader is elected, as a dry run doesn't write anything another replica could clash with
+    let supervisor = Supervisor::new(RunnerHealth::default()).with_backoff(
+        internal_config.runner_initial_backoff,
+        internal_config.runner_max_backoff,
+    );
+
+    let (fuel_adapter, _) = setup::fuel_adapter(&config, &internal_config, &metrics_registry);
+
+    // nor are there any nonces to reserve
+    let (ethereum_rpc, _) = setup::l1_adapter(&config, &internal_config, None, &metrics_registry)
+        .await
+        .with_context(|| "could not setup l1 adapter")?;
+    let l1 = eth::DryRun::new(ethereum_rpc);
+
+    let (committer_handle, _) = setup::block_committer(
+        l1.clone(),
+        storage.clone(),
+        fuel_adapter.clone(),
+        &config,
+        &metrics_registry,
+        &supervisor,
+        cancel_token.clone(),
+    );
+
+    let mut handles = vec![committer_handle];
+
+    if config.eth.blob_pool_key.is_some() {
+        handles.push(setup::state_committer(
+            l1,
+            storage.clone(),
+            &supervisor,
+            cancel_token.clone(),
+            &metrics_registry,
+            &config,
+        ));
+        handles.push(setup::state_importer(
+            fuel_adapter,
+            storage,
+            &supervisor,
+            cancel_token.clone(),
+            &metrics_registry,
+            &config,
+        ));
+    }
+
+    // nothing in flight is worth waiting for, the runners stop as soon as they are cancelled
+    cancel_token.cancelled().await;
+    for handle in handles {
+        handle.await?;
+    }
 
-    shut_down(cancel_token, handles, storage).await
+    info!("dry run stopped");
+    Ok(())
 }
 
 #[cfg(test)]
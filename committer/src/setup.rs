@@ -1,87 +1,140 @@
-use std::{num::NonZeroU32, time::Duration};
+use std::time::Duration;
 
-use eth::AwsConfig;
+use actix_web::dev::ServerHandle;
+use eth::{AwsConfig, NonceManager, Signer};
+use futures::{FutureExt, StreamExt};
 use metrics::{prometheus::Registry, HealthChecker, RegistersMetrics};
-use ports::storage::Storage;
-use services::{BlockCommitter, CommitListener, Runner, WalletBalanceTracker};
-use tokio::task::JoinHandle;
+use ports::{
+    fuel::Api,
+    l1::Contract,
+    storage::Storage,
+    types::{WalletRole, U256},
+};
+use services::{
+    BlockCommitter, CommitListener, LeaderElector, Leadership, ResubmissionQueue, Runner,
+    SupervisedRunner, Supervisor, WalletBalanceTracker, WalletBalances,
+};
+use storage::AdvisoryLock;
+use tokio::{
+    signal::unix::{signal, SignalKind},
+    task::JoinHandle,
+};
 use tokio_util::sync::CancellationToken;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use validator::BlockValidator;
 
 use crate::{config, errors::Result, AwsClient, Database, FuelApi, L1};
 
 pub fn wallet_balance_tracker(
+    config: &config::Config,
     internal_config: &config::Internal,
     registry: &Registry,
     l1: L1,
+    supervisor: &Supervisor,
     cancel_token: CancellationToken,
-) -> tokio::task::JoinHandle<()> {
-    let wallet_balance_tracker = WalletBalanceTracker::new(l1);
+) -> (tokio::task::JoinHandle<()>, WalletBalances) {
+    let gwei = |amount: u64| U256::from(amount) * U256::from(1_000_000_000);
+
+    let mut wallet_balance_tracker = WalletBalanceTracker::new(l1)
+        .track(WalletRole::Main, gwei(config.eth.main_key_low_balance_gwei))
+        .with_runway_window(internal_config.balance_runway_window);
+    if config.eth.blob_pool_key.is_some() {
+        wallet_balance_tracker = wallet_balance_tracker.track(
+            WalletRole::Blob,
+            gwei(config.eth.blob_pool_key_low_balance_gwei),
+        );
+    }
 
     wallet_balance_tracker.register_metrics(registry);
+    let balances = wallet_balance_tracker.balances();
 
-    schedule_polling(
+    let handle = schedule_polling(
         internal_config.balance_update_interval,
-        wallet_balance_tracker,
-        "Wallet Balance Tracker",
+        supervise(
+            supervisor,
+            registry,
+            "Wallet Balance Tracker",
+            wallet_balance_tracker,
+        ),
         cancel_token,
-    )
+    );
+
+    (handle, balances)
 }
 
 pub fn l1_event_listener(
+    config: &config::Config,
     internal_config: &config::Internal,
     l1: L1,
     storage: Database,
     registry: &Registry,
+    supervisor: &Supervisor,
     cancel_token: CancellationToken,
 ) -> tokio::task::JoinHandle<()> {
-    let commit_listener_service = CommitListener::new(l1, storage, cancel_token.clone());
+    let commit_listener_service = CommitListener::new(l1, storage, cancel_token.clone())
+        .with_confirmations(
+            config.app.num_blocks_to_confirm_commit,
+            config.app.commit_confirmation_check_interval,
+        );
     commit_listener_service.register_metrics(registry);
 
     schedule_polling(
         internal_config.between_eth_event_stream_restablishing_attempts,
-        commit_listener_service,
-        "Commit Listener",
+        // runs for as long as the event stream lasts, so it has no iterations to age by
+        supervise(
+            supervisor,
+            registry,
+            "Commit Listener",
+            commit_listener_service,
+        )
+        .without_max_age(),
         cancel_token,
     )
 }
 
 pub fn block_committer(
-    commit_interval: NonZeroU32,
-    l1: L1,
+    l1: impl Contract + ports::l1::Api + 'static,
     storage: impl Storage + 'static,
     fuel: FuelApi,
     config: &config::Config,
     registry: &Registry,
+    supervisor: &Supervisor,
     cancel_token: CancellationToken,
-) -> tokio::task::JoinHandle<()> {
-    let validator = BlockValidator::new(*config.fuel.block_producer_address);
+) -> (tokio::task::JoinHandle<()>, ResubmissionQueue) {
+    let validator = block_validator(config);
 
-    let block_committer = BlockCommitter::new(l1, storage, fuel, validator, commit_interval);
+    let fuel_stream = fuel.clone();
+    let commit_interval = l1.commit_interval();
+    let resubmissions = ResubmissionQueue::default();
+    let block_committer = BlockCommitter::new(l1, storage, fuel, validator, commit_interval)
+        .with_resubmission_queue(resubmissions.clone());
 
     block_committer.register_metrics(registry);
 
-    schedule_polling(
+    let handle = schedule_on_new_blocks(
+        fuel_stream,
         config.app.block_check_interval,
-        block_committer,
-        "Block Committer",
+        supervise_while_leading(supervisor, registry, "Block Committer", block_committer),
         cancel_token,
-    )
+    );
+
+    (handle, resubmissions)
 }
 
 pub fn state_committer(
-    l1: L1,
+    l1: impl ports::l1::Api + Send + Sync + 'static,
     storage: impl Storage + 'static,
+    supervisor: &Supervisor,
     cancel_token: CancellationToken,
+    registry: &Registry,
     config: &config::Config,
 ) -> tokio::task::JoinHandle<()> {
-    let state_committer = services::StateCommitter::new(l1, storage);
+    let state_committer = services::StateCommitter::new(l1, storage)
+        .with_max_fragments_per_tx(config.app.max_fragments_per_tx);
 
     schedule_polling(
         config.app.block_check_interval,
-        state_committer,
-        "State Committer",
+        supervise_while_leading(supervisor, registry, "State Committer", state_committer),
         cancel_token,
     )
 }
@@ -89,23 +142,27 @@ pub fn state_committer(
 pub fn state_importer(
     fuel: FuelApi,
     storage: impl Storage + 'static,
+    supervisor: &Supervisor,
     cancel_token: CancellationToken,
+    registry: &Registry,
     config: &config::Config,
 ) -> tokio::task::JoinHandle<()> {
-    let validator = BlockValidator::new(*config.fuel.block_producer_address);
+    let validator = block_validator(config);
+    let fuel_stream = fuel.clone();
     let state_importer = services::StateImporter::new(storage, fuel, validator);
 
-    schedule_polling(
+    schedule_on_new_blocks(
+        fuel_stream,
         config.app.block_check_interval,
-        state_importer,
-        "State Importer",
+        supervise_while_leading(supervisor, registry, "State Importer", state_importer),
         cancel_token,
     )
 }
 
 pub fn state_listener(
-    l1: L1,
+    l1: impl ports::l1::Api + Send + Sync + 'static,
     storage: impl Storage + 'static,
+    supervisor: &Supervisor,
     cancel_token: CancellationToken,
     registry: &Registry,
     config: &config::Config,
@@ -117,30 +174,62 @@ pub fn state_listener(
 
     schedule_polling(
         config.app.block_check_interval,
-        state_listener,
-        "State Listener",
+        supervise(supervisor, registry, "State Listener", state_listener),
         cancel_token,
     )
 }
 
+pub fn leader_elector(
+    internal_config: &config::Internal,
+    election: AdvisoryLock,
+    leadership: Leadership,
+    l1: L1,
+    registry: &Registry,
+    supervisor: &Supervisor,
+    cancel_token: CancellationToken,
+) -> tokio::task::JoinHandle<()> {
+    // the nonces the previous leader reserved are only to be touched once it is gone
+    let leader_elector = LeaderElector::new(election, leadership).with_on_lead(move || {
+        let l1 = l1.clone();
+        async move { Ok(l1.reconcile_nonces().await?) }.boxed()
+    });
+    leader_elector.register_metrics(registry);
+
+    schedule_polling(
+        internal_config.leader_election_interval,
+        supervise(supervisor, registry, "Leader Elector", leader_elector),
+        cancel_token,
+    )
+}
+
+pub fn block_validator(config: &config::Config) -> BlockValidator {
+    let validator = BlockValidator::new(*config.fuel.block_producer_address);
+
+    match config.fuel.chain_config_hash {
+        Some(hash) => validator.with_chain_config_hash(*hash),
+        None => validator,
+    }
+}
+
 pub async fn l1_adapter(
     config: &config::Config,
     internal_config: &config::Internal,
+    nonce_manager: Option<NonceManager>,
     registry: &Registry,
 ) -> Result<(L1, HealthChecker)> {
-    let aws_config = AwsConfig::from_env().await;
-
-    let aws_client = AwsClient::new(aws_config).await;
+    let (main_signer, blob_signer) = signers(config).await?;
 
-    let l1 = L1::connect(
-        config.eth.rpc.clone(),
+    let mut l1 = L1::connect(
+        config.eth.rpcs(),
         config.eth.state_contract_address,
-        config.eth.main_key_arn.clone(),
-        config.eth.blob_pool_key_arn.clone(),
+        main_signer,
+        blob_signer,
         internal_config.eth_errors_before_unhealthy,
-        aws_client,
     )
     .await?;
+    if let Some(nonce_manager) = nonce_manager {
+        l1 = l1.with_nonce_manager(nonce_manager);
+    }
 
     l1.register_metrics(registry);
 
@@ -149,23 +238,110 @@ pub async fn l1_adapter(
     Ok((l1, health_check))
 }
 
+pub async fn signers(config: &config::Config) -> Result<(Signer, Option<Signer>)> {
+    // only set up aws when needed as loading its config probes the environment
+    let aws_client = if config.eth.uses_kms() {
+        Some(AwsClient::new(AwsConfig::from_env().await).await)
+    } else {
+        None
+    };
+
+    let main_signer = Signer::from_key_source(&config.eth.main_key, aws_client.as_ref()).await?;
+    let blob_signer = match &config.eth.blob_pool_key {
+        Some(key) => Some(Signer::from_key_source(key, aws_client.as_ref()).await?),
+        None => None,
+    };
+
+    Ok((main_signer, blob_signer))
+}
+
+fn supervise<R: Runner>(
+    supervisor: &Supervisor,
+    registry: &Registry,
+    name: &'static str,
+    runner: R,
+) -> SupervisedRunner<R> {
+    let runner = supervisor.supervise(name, runner);
+    runner.register_metrics(registry);
+    runner
+}
+
+// for runners that write to L1, or whose writes would clash with those of another replica
+fn supervise_while_leading<R: Runner>(
+    supervisor: &Supervisor,
+    registry: &Registry,
+    name: &'static str,
+    runner: R,
+) -> SupervisedRunner<R> {
+    let runner = supervisor.supervise_while_leading(name, runner);
+    runner.register_metrics(registry);
+    runner
+}
+
+/// Runs `runner` every `polling_interval`, waiting longer while it keeps failing.
 fn schedule_polling(
     polling_interval: Duration,
-    mut runner: impl Runner + 'static,
-    name: &'static str,
+    mut runner: SupervisedRunner<impl Runner + 'static>,
     cancel_token: CancellationToken,
 ) -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
         loop {
-            if let Err(e) = runner.run().await {
-                error!("{name} encountered an error: {e}");
+            let backoff = runner.run().await;
+
+            let pause = backoff.map_or(polling_interval, |backoff| backoff.max(polling_interval));
+            tokio::select! {
+                () = tokio::time::sleep(pause) => {}
+                () = cancel_token.cancelled() => break,
+            }
+        }
+
+        info!("{} stopped", runner.name());
+    })
+}
+
+/// Runs `runner` once for every new Fuel block, starting with the current chain tip.
+fn schedule_on_new_blocks(
+    fuel: FuelApi,
+    retry_interval: Duration,
+    mut runner: SupervisedRunner<impl Runner + 'static>,
+    cancel_token: CancellationToken,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let name = runner.name();
+        let from_height = loop {
+            match fuel.latest_block().await {
+                Ok(block) => break block.header.height,
+                Err(e) => {
+                    error!("{name} could not fetch the latest fuel block: {e}");
+                    runner.health().note_failure(e);
+                }
             }
 
             if cancel_token.is_cancelled() {
-                break;
+                info!("{name} stopped");
+                return;
             }
 
-            tokio::time::sleep(polling_interval).await;
+            tokio::time::sleep(retry_interval).await;
+        };
+
+        let mut blocks = std::pin::pin!(fuel
+            .block_stream(from_height)
+            .take_until(cancel_token.cancelled()));
+
+        while let Some(block) = blocks.next().await {
+            if let Err(e) = block {
+                error!("{name} could not receive a new fuel block: {e}");
+                runner.health().note_failure(e);
+                continue;
+            }
+
+            if let Some(backoff) = runner.run().await {
+                tokio::select! {
+                    () = tokio::time::sleep(backoff) => {}
+                    () = cancel_token.cancelled() => {}
+                }
+            }
         }
 
         info!("{name} stopped");
@@ -177,10 +353,12 @@ pub fn fuel_adapter(
     internal_config: &config::Internal,
     registry: &Registry,
 ) -> (FuelApi, HealthChecker) {
-    let fuel_adapter = FuelApi::new(
-        &config.fuel.graphql_endpoint,
+    let fuel_adapter = FuelApi::connect(
+        &config.fuel.graphql_endpoints(),
         internal_config.fuel_errors_before_unhealthy,
-    );
+        config.fuel.min_agreeing_endpoints,
+    )
+    .with_block_poll_interval(config.fuel.block_poll_interval);
     fuel_adapter.register_metrics(registry);
 
     let fuel_connection_health = fuel_adapter.connection_health_checker();
@@ -204,17 +382,57 @@ pub async fn storage(config: &config::Config) -> Result<Database> {
     Ok(postgres)
 }
 
+/// Cancels `cancel_token` once the process receives SIGTERM or SIGINT.
+pub fn cancel_on_shutdown_signal(cancel_token: CancellationToken) -> Result<()> {
+    let mut sigterm = signal(SignalKind::terminate())?;
+
+    tokio::spawn(async move {
+        tokio::select! {
+            _ = sigterm.recv() => info!("received SIGTERM, shutting down"),
+            _ = tokio::signal::ctrl_c() => info!("received SIGINT, shutting down"),
+        }
+
+        cancel_token.cancel();
+    });
+
+    Ok(())
+}
+
+/// Stops the runners from taking on new work and waits up to `timeout` for the work in flight to
+/// finish, before handing over the lead, stopping the api server and closing the database pool.
 pub async fn shut_down(
     cancel_token: CancellationToken,
-    handles: Vec<JoinHandle<()>>,
+    mut handles: Vec<JoinHandle<()>>,
+    api_server: ServerHandle,
     storage: Database,
+    leader_election: AdvisoryLock,
+    timeout: Duration,
 ) -> Result<()> {
     cancel_token.cancel();
 
-    for handle in handles {
-        handle.await?;
-    }
+    // every runner is waited for even if one of them panicked, the first failure is reported
+    let drain = async {
+        let mut drained = Result::Ok(());
+        for handle in &mut handles {
+            drained = drained.and(handle.await.map_err(Into::into));
+        }
+        drained
+    };
+
+    let drained = match tokio::time::timeout(timeout, drain).await {
+        Ok(result) => result,
+        Err(_) => {
+            warn!("runners still busy after {timeout:?}, aborting them");
+            handles.iter().for_each(JoinHandle::abort);
+            Ok(())
+        }
+    };
 
+    // cleaned up regardless, so a failed runner doesn't keep holding the lead
+    leader_election.release().await;
+    api_server.stop(true).await;
     storage.close().await;
-    Ok(())
+
+    info!("shut down");
+    drained
 }
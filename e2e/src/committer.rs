@@ -39,7 +39,10 @@ impl Committer {
             .env("AWS_REGION", "us-east-1")
             .env("AWS_ACCESS_KEY_ID", "test")
             .env("AWS_SECRET_ACCESS_KEY", "test")
-            .env("COMMITTER__ETH__MAIN_KEY_ARN", get_field!(main_key_arn))
+            .env(
+                "COMMITTER__ETH__MAIN_KEY",
+                format!("Kms({})", get_field!(main_key_arn)),
+            )
             .env("COMMITTER__ETH__RPC", get_field!(eth_rpc).as_str())
             .env(
                 "COMMITTER__ETH__STATE_CONTRACT_ADDRESS",
@@ -60,7 +63,10 @@ impl Committer {
             .kill_on_drop(true);
 
         if let Some(blob_wallet_key_arn) = self.blob_key_arn {
-            cmd.env("COMMITTER__ETH__BLOB_POOL_KEY_ARN", blob_wallet_key_arn);
+            cmd.env(
+                "COMMITTER__ETH__BLOB_POOL_KEY",
+                format!("Kms({blob_wallet_key_arn})"),
+            );
         }
 
         let sink = if self.show_logs {
@@ -7,7 +7,7 @@ use alloy::{
     providers::{Provider, ProviderBuilder, WsConnect},
     rpc::types::TransactionRequest,
 };
-use eth::{AwsClient, AwsConfig, WebsocketClient};
+use eth::{AwsClient, AwsConfig, RpcClient};
 use fs_extra::dir::{copy, CopyOptions};
 use ports::types::{Address, ValidatedFuelBlock};
 use serde::Deserialize;
@@ -18,16 +18,17 @@ use crate::kms::KmsKey;
 
 pub struct DeployedContract {
     address: Address,
-    chain_state_contract: WebsocketClient,
+    chain_state_contract: RpcClient,
 }
 
 impl DeployedContract {
     pub async fn connect(url: Url, address: Address, key: KmsKey) -> anyhow::Result<Self> {
         let blob_wallet = None;
         let aws_client = AwsClient::new(AwsConfig::for_testing(key.url).await).await;
+        let main_signer = aws_client.make_signer(key.id).await?.into();
 
         let chain_state_contract =
-            WebsocketClient::connect(url, address, key.id, blob_wallet, 5, aws_client).await?;
+            RpcClient::connect(vec![url], address, main_signer, blob_wallet, 5).await?;
 
         Ok(Self {
             address,
@@ -0,0 +1,134 @@
+use std::{
+    num::NonZeroU32,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use alloy::primitives::{keccak256, B256, U256};
+use async_trait::async_trait;
+use ports::{
+    l1::{Api, Contract, EventStreamer, Result},
+    types::{L1Height, TransactionResponse, ValidatedFuelBlock, WalletRole},
+};
+use tracing::info;
+
+/// Wraps an L1 adapter, logging every write instead of sending it. Reads go through to the
+/// wrapped adapter. Clones share the count of unsent txs.
+#[derive(Clone)]
+pub struct DryRun<L1> {
+    l1: L1,
+    unsent_txs: Arc<AtomicU64>,
+}
+
+impl<L1> DryRun<L1> {
+    pub fn new(l1: L1) -> Self {
+        Self {
+            l1,
+            unsent_txs: Arc::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl<L1> Contract for DryRun<L1>
+where
+    L1: Contract,
+{
+    async fn submit(&self, block: ValidatedFuelBlock) -> Result<()> {
+        info!(
+            "dry run: not committing fuel block {} at height {}",
+            B256::from(block.hash()),
+            block.height()
+        );
+
+        Ok(())
+    }
+
+    fn event_streamer(&self, height: L1Height) -> Box<dyn EventStreamer + Send + Sync> {
+        self.l1.event_streamer(height)
+    }
+
+    fn commit_interval(&self) -> NonZeroU32 {
+        self.l1.commit_interval()
+    }
+}
+
+#[async_trait]
+impl<L1> Api for DryRun<L1>
+where
+    L1: Api + Send + Sync,
+{
+    async fn submit_l2_state(&self, state_data: Vec<u8>) -> Result<[u8; 32]> {
+        // the same state may be sent more than once, the tx hashes of which must still differ
+        let salt = self
+            .unsent_txs
+            .fetch_add(1, Ordering::Relaxed)
+            .to_be_bytes();
+        let tx_hash = keccak256([salt.as_slice(), &state_data].concat()).0;
+
+        info!(
+            "dry run: not sending blob tx {} carrying {} bytes of state",
+            B256::from(tx_hash),
+            state_data.len()
+        );
+
+        Ok(tx_hash)
+    }
+
+    async fn get_block_number(&self) -> Result<L1Height> {
+        self.l1.get_block_number().await
+    }
+
+    async fn block_hash(&self, height: L1Height) -> Result<Option<[u8; 32]>> {
+        self.l1.block_hash(height).await
+    }
+
+    async fn balance(&self, wallet: WalletRole) -> Result<U256> {
+        self.l1.balance(wallet).await
+    }
+
+    async fn get_transaction_response(
+        &self,
+        tx_hash: [u8; 32],
+    ) -> Result<Option<TransactionResponse>> {
+        self.l1.get_transaction_response(tx_hash).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ports::l1::{MockApi, MockContract};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn commits_are_not_submitted() {
+        // given
+        let mut contract = MockContract::new();
+        contract.expect_submit().never();
+        let dry_run = DryRun::new(contract);
+
+        // when
+        let result = dry_run.submit(rand::random()).await;
+
+        // then
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn resent_state_gets_a_new_tx_hash() {
+        // given
+        let mut api = MockApi::new();
+        api.expect_submit_l2_state().never();
+        let dry_run = DryRun::new(api);
+
+        // when
+        let first = dry_run.submit_l2_state(vec![1, 2, 3]).await.unwrap();
+        let resent = dry_run.submit_l2_state(vec![1, 2, 3]).await.unwrap();
+
+        // then
+        assert_ne!(first, resent);
+    }
+}
@@ -44,6 +44,34 @@ impl From<AwsSignerError> for Error {
     }
 }
 
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_status() || err.is_decode() {
+            Self::Other(err.to_string())
+        } else {
+            Self::Network(err.to_string())
+        }
+    }
+}
+
+impl From<url::ParseError> for Error {
+    fn from(err: url::ParseError) -> Self {
+        Self::Other(err.to_string())
+    }
+}
+
+impl From<alloy::hex::FromHexError> for Error {
+    fn from(err: alloy::hex::FromHexError) -> Self {
+        Self::Other(err.to_string())
+    }
+}
+
+impl From<ports::storage::Error> for Error {
+    fn from(err: ports::storage::Error) -> Self {
+        Self::Other(err.to_string())
+    }
+}
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 impl From<Error> for ports::l1::Error {
@@ -7,21 +7,29 @@ use async_trait::async_trait;
 use futures::{stream::TryStreamExt, Stream};
 use ports::{
     l1::{Api, Contract, EventStreamer, Result},
-    types::{FuelBlockCommittedOnL1, L1Height, TransactionResponse, ValidatedFuelBlock},
+    types::{
+        FuelBlockCommittedOnL1, L1Height, TransactionResponse, ValidatedFuelBlock, WalletRole,
+    },
 };
-use websocket::EthEventStreamer;
+use rpc::EthEventStreamer;
 
 mod aws;
+mod dry_run;
 mod error;
 mod metrics;
-mod websocket;
+mod nonce_manager;
+mod rpc;
+mod signer;
 
 pub use alloy::primitives::Address;
 pub use aws::*;
-pub use websocket::WebsocketClient;
+pub use dry_run::DryRun;
+pub use nonce_manager::NonceManager;
+pub use rpc::RpcClient;
+pub use signer::{KeySource, Signer, Web3Signer};
 
 #[async_trait]
-impl Contract for WebsocketClient {
+impl Contract for RpcClient {
     async fn submit(&self, block: ValidatedFuelBlock) -> Result<()> {
         self.submit(block).await
     }
@@ -36,13 +44,13 @@ impl Contract for WebsocketClient {
 }
 
 #[async_trait]
-impl Api for WebsocketClient {
+impl Api for RpcClient {
     async fn submit_l2_state(&self, state_data: Vec<u8>) -> Result<[u8; 32]> {
         Ok(self.submit_l2_state(state_data).await?)
     }
 
-    async fn balance(&self) -> Result<U256> {
-        Ok(self.balance().await?)
+    async fn balance(&self, wallet: WalletRole) -> Result<U256> {
+        self.balance(wallet).await
     }
 
     async fn get_block_number(&self) -> Result<L1Height> {
@@ -52,6 +60,10 @@ impl Api for WebsocketClient {
         Ok(height)
     }
 
+    async fn block_hash(&self, height: L1Height) -> Result<Option<[u8; 32]>> {
+        self.block_hash(height.into()).await
+    }
+
     async fn get_transaction_response(
         &self,
         tx_hash: [u8; 32],
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use ::metrics::{
     prometheus::{core::Collector, IntCounter, Opts},
     RegistersMetrics,
@@ -14,14 +16,24 @@ impl RegistersMetrics for Metrics {
     }
 }
 
-impl Default for Metrics {
-    fn default() -> Self {
-        let eth_network_errors = IntCounter::with_opts(Opts::new(
-            "eth_network_errors",
-            "Number of network errors encountered while running Ethereum RPCs.",
-        ))
+impl Metrics {
+    /// Metrics carrying `const_labels`, so that several instances can be registered side by side.
+    pub(crate) fn with_const_labels(const_labels: HashMap<String, String>) -> Self {
+        let eth_network_errors = IntCounter::with_opts(
+            Opts::new(
+                "eth_network_errors",
+                "Number of network errors encountered while running Ethereum RPCs.",
+            )
+            .const_labels(const_labels),
+        )
         .expect("eth_network_errors metric to be correctly configured");
 
         Self { eth_network_errors }
     }
 }
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::with_const_labels(HashMap::new())
+    }
+}
@@ -0,0 +1,373 @@
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use ::metrics::{
+    prometheus::{core::Collector, IntGaugeVec, Opts},
+    RegistersMetrics,
+};
+use alloy::primitives::Address;
+use ports::storage::Storage;
+
+use crate::error::{Error, Result};
+
+/// Hands out nonces recorded in the database, so that neither restarts nor the commit and blob
+/// wallets sending concurrently lead to a nonce being used twice.
+#[derive(Clone)]
+pub struct NonceManager {
+    storage: Arc<dyn Storage>,
+    unseen_tx_timeout: Duration,
+    // nonces kept reserved after a network error, and since when
+    unconfirmed: Arc<Mutex<BTreeMap<(Address, u64), Instant>>>,
+    metrics: Metrics,
+}
+
+impl NonceManager {
+    pub fn new(storage: impl Storage + 'static) -> Self {
+        Self {
+            storage: Arc::new(storage),
+            unseen_tx_timeout: Duration::from_secs(5 * 60),
+            unconfirmed: Arc::default(),
+            metrics: Metrics::default(),
+        }
+    }
+
+    /// How long L1's pending nonce may stay at a nonce kept reserved after a network error before
+    /// its tx is taken to never have been sent, and the nonce is released.
+    #[must_use]
+    pub fn with_unseen_tx_timeout(mut self, timeout: Duration) -> Self {
+        self.unseen_tx_timeout = timeout;
+        self
+    }
+
+    /// Continues from the `pending_nonce` L1 reports, reporting the reservations it never saw a
+    /// tx for.
+    pub(crate) async fn reconcile(&self, address: Address, pending_nonce: u64) -> Result<()> {
+        let lost = self
+            .storage
+            .reconcile_nonces(address.into(), pending_nonce)
+            .await?;
+
+        if !lost.is_empty() {
+            tracing::warn!(
+                "nonces {lost:?} of {address} were reserved but L1 knows no tx using them, they \
+                 will be handed out again"
+            );
+        }
+
+        self.update_gaps(address).await
+    }
+
+    /// Sends a tx with a freshly reserved nonce, recording the tx hash on success. The nonce is
+    /// released if sending failed before the tx could reach L1. After a network error the tx
+    /// might have been broadcast, so the nonce stays reserved until L1's pending nonce moves past
+    /// it, or, should it stay at the nonce for longer than the unseen tx timeout, until the nonce
+    /// is released by a later send.
+    pub(crate) async fn send<F, Fut>(
+        &self,
+        address: Address,
+        pending_nonce: u64,
+        send: F,
+    ) -> Result<[u8; 32]>
+    where
+        F: FnOnce(u64) -> Fut,
+        Fut: Future<Output = Result<[u8; 32]>>,
+    {
+        self.release_unseen(address, pending_nonce).await?;
+
+        let nonce = self
+            .storage
+            .reserve_nonce(address.into(), pending_nonce)
+            .await?;
+
+        match send(nonce).await {
+            Ok(tx_hash) => {
+                self.storage
+                    .record_nonce_tx(address.into(), nonce, tx_hash)
+                    .await?;
+                self.update_gaps(address).await?;
+                Ok(tx_hash)
+            }
+            Err(err @ Error::Network(_)) => {
+                tracing::warn!(
+                    "nonce {nonce} of {address} stays reserved, its tx might have been sent \
+                     despite: {err}"
+                );
+                self.unconfirmed_nonces()
+                    .insert((address, nonce), Instant::now());
+                Err(err)
+            }
+            Err(err) => {
+                if let Err(release_err) = self.release(address, nonce).await {
+                    tracing::error!("could not release nonce {nonce} of {address}: {release_err}");
+                }
+                Err(err)
+            }
+        }
+    }
+
+    // Forgets the unconfirmed nonces L1 has seen a tx for, and releases the one L1 is still
+    // waiting for once it has waited for too long, so that later txs don't queue up behind it.
+    async fn release_unseen(&self, address: Address, pending_nonce: u64) -> Result<()> {
+        let unseen = {
+            let mut unconfirmed = self.unconfirmed_nonces();
+            unconfirmed.retain(|&(wallet, nonce), _| wallet != address || nonce >= pending_nonce);
+
+            let timed_out = unconfirmed
+                .get(&(address, pending_nonce))
+                .is_some_and(|since| since.elapsed() >= self.unseen_tx_timeout);
+            timed_out.then(|| unconfirmed.remove(&(address, pending_nonce)))
+        };
+
+        if unseen.is_some() {
+            tracing::warn!(
+                "L1 has seen no tx with nonce {pending_nonce} of {address} for {:?}, taking it \
+                 as never sent",
+                self.unseen_tx_timeout
+            );
+            self.release(address, pending_nonce).await?;
+        }
+
+        Ok(())
+    }
+
+    fn unconfirmed_nonces(&self) -> std::sync::MutexGuard<'_, BTreeMap<(Address, u64), Instant>> {
+        self.unconfirmed
+            .lock()
+            .expect("unconfirmed nonces lock not to be poisoned")
+    }
+
+    async fn release(&self, address: Address, nonce: u64) -> Result<()> {
+        self.storage.release_nonce(address.into(), nonce).await?;
+        tracing::warn!(
+            "nonce {nonce} of {address} was left unused, later txs of the wallet wait until it is \
+             reused"
+        );
+
+        self.update_gaps(address).await
+    }
+
+    async fn update_gaps(&self, address: Address) -> Result<()> {
+        let unused = self.storage.unused_nonces(address.into()).await?;
+        self.metrics
+            .nonce_gaps
+            .with_label_values(&[&address.to_string()])
+            .set(i64::try_from(unused.len()).unwrap_or(i64::MAX));
+
+        Ok(())
+    }
+}
+
+impl RegistersMetrics for NonceManager {
+    fn metrics(&self) -> Vec<Box<dyn Collector>> {
+        self.metrics.metrics()
+    }
+}
+
+#[derive(Clone)]
+struct Metrics {
+    nonce_gaps: IntGaugeVec,
+}
+
+impl RegistersMetrics for Metrics {
+    fn metrics(&self) -> Vec<Box<dyn Collector>> {
+        vec![Box::new(self.nonce_gaps.clone())]
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        let nonce_gaps = IntGaugeVec::new(
+            Opts::new(
+                "eth_nonce_gaps",
+                "Number of reserved nonces no tx was sent with, holding back later txs.",
+            ),
+            &["address"],
+        )
+        .expect("eth_nonce_gaps metric to be correctly configured");
+
+        Self { nonce_gaps }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ports::storage::MockStorage;
+
+    use super::*;
+
+    const ADDRESS: Address = Address::repeat_byte(1);
+
+    #[tokio::test]
+    async fn records_tx_sent_with_reserved_nonce() {
+        // given
+        let mut storage = MockStorage::new();
+        storage
+            .expect_reserve_nonce()
+            .withf(|address, pending| *address == <[u8; 20]>::from(ADDRESS) && *pending == 3)
+            .returning(|_, _| Box::pin(async { Ok(5) }));
+        storage
+            .expect_record_nonce_tx()
+            .withf(|_, nonce, tx_hash| *nonce == 5 && *tx_hash == [7; 32])
+            .times(1)
+            .returning(|_, _, _| Box::pin(async { Ok(()) }));
+        storage.expect_release_nonce().never();
+        storage
+            .expect_unused_nonces()
+            .returning(|_| Box::pin(async { Ok(vec![]) }));
+
+        let nonce_manager = NonceManager::new(storage);
+
+        // when
+        let tx_hash = nonce_manager
+            .send(ADDRESS, 3, |nonce| async move {
+                assert_eq!(nonce, 5);
+                Ok([7; 32])
+            })
+            .await
+            .unwrap();
+
+        // then
+        assert_eq!(tx_hash, [7; 32]);
+    }
+
+    #[tokio::test]
+    async fn releases_nonce_when_sending_fails() {
+        // given
+        let mut storage = MockStorage::new();
+        storage
+            .expect_reserve_nonce()
+            .returning(|_, _| Box::pin(async { Ok(5) }));
+        storage.expect_record_nonce_tx().never();
+        storage
+            .expect_release_nonce()
+            .withf(|_, nonce| *nonce == 5)
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+        storage
+            .expect_unused_nonces()
+            .returning(|_| Box::pin(async { Ok(vec![5]) }));
+
+        let nonce_manager = NonceManager::new(storage);
+
+        // when
+        let result = nonce_manager
+            .send(ADDRESS, 3, |_| async {
+                Err(Error::Other("rejected".to_string()))
+            })
+            .await;
+
+        // then
+        assert!(matches!(result, Err(Error::Other(_))));
+        let gaps = nonce_manager
+            .metrics
+            .nonce_gaps
+            .with_label_values(&[&ADDRESS.to_string()])
+            .get();
+        assert_eq!(gaps, 1);
+    }
+
+    #[tokio::test]
+    async fn keeps_nonce_reserved_when_the_tx_might_have_been_sent() {
+        // given
+        let mut storage = MockStorage::new();
+        storage
+            .expect_reserve_nonce()
+            .returning(|_, _| Box::pin(async { Ok(5) }));
+        storage.expect_record_nonce_tx().never();
+        storage.expect_release_nonce().never();
+
+        let nonce_manager = NonceManager::new(storage);
+
+        // when
+        let result = nonce_manager
+            .send(ADDRESS, 3, |_| async {
+                Err(Error::Network("connection reset".to_string()))
+            })
+            .await;
+
+        // then
+        assert!(matches!(result, Err(Error::Network(_))));
+    }
+
+    #[tokio::test]
+    async fn releases_nonce_whose_tx_never_landed() {
+        // given
+        let mut storage = MockStorage::new();
+        storage
+            .expect_reserve_nonce()
+            .returning(|_, _| Box::pin(async { Ok(5) }));
+        storage
+            .expect_release_nonce()
+            .withf(|_, nonce| *nonce == 5)
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+        storage
+            .expect_record_nonce_tx()
+            .withf(|_, nonce, _| *nonce == 5)
+            .times(1)
+            .returning(|_, _, _| Box::pin(async { Ok(()) }));
+        storage
+            .expect_unused_nonces()
+            .returning(|_| Box::pin(async { Ok(vec![]) }));
+
+        let nonce_manager = NonceManager::new(storage).with_unseen_tx_timeout(Duration::ZERO);
+        let lost = nonce_manager
+            .send(ADDRESS, 5, |_| async {
+                Err(Error::Network("connection reset".to_string()))
+            })
+            .await;
+        assert!(lost.is_err());
+
+        // when
+        // L1's pending nonce is still 5, the tx never landed
+        let tx_hash = nonce_manager
+            .send(ADDRESS, 5, |nonce| async move {
+                assert_eq!(nonce, 5);
+                Ok([7; 32])
+            })
+            .await
+            .unwrap();
+
+        // then
+        assert_eq!(tx_hash, [7; 32]);
+    }
+
+    #[tokio::test]
+    async fn keeps_nonce_whose_tx_landed() {
+        // given
+        let mut storage = MockStorage::new();
+        let mut nonces = vec![5, 6].into_iter();
+        storage.expect_reserve_nonce().returning(move |_, _| {
+            let nonce = nonces.next().expect("reservations to be scripted");
+            Box::pin(async move { Ok(nonce) })
+        });
+        storage.expect_release_nonce().never();
+        storage
+            .expect_record_nonce_tx()
+            .returning(|_, _, _| Box::pin(async { Ok(()) }));
+        storage
+            .expect_unused_nonces()
+            .returning(|_| Box::pin(async { Ok(vec![]) }));
+
+        let nonce_manager = NonceManager::new(storage).with_unseen_tx_timeout(Duration::ZERO);
+        let _ = nonce_manager
+            .send(ADDRESS, 5, |_| async {
+                Err(Error::Network("connection reset".to_string()))
+            })
+            .await;
+
+        // when
+        // L1's pending nonce moved past 5, the tx made it after all
+        let result = nonce_manager
+            .send(ADDRESS, 6, |_| async { Ok([7; 32]) })
+            .await;
+
+        // then
+        assert!(result.is_ok());
+        assert!(nonce_manager.unconfirmed_nonces().is_empty());
+    }
+}
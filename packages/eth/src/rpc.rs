@@ -0,0 +1,230 @@
+use std::{future::Future, num::NonZeroU32};
+
+use ::metrics::{prometheus::core::Collector, HealthChecker, RegistersMetrics};
+use alloy::{network::TxSigner, primitives::Address};
+use ports::{
+    l1::Result,
+    types::{TransactionResponse, ValidatedFuelBlock, WalletRole, U256},
+};
+use url::Url;
+
+pub use self::event_streamer::EthEventStreamer;
+use self::{
+    health_tracking_middleware::EthApi,
+    lazy_connection::{ConnectionParams, LazyConnection},
+    provider_pool::ProviderPool,
+};
+use crate::{NonceManager, Signer};
+
+mod connection;
+mod event_streamer;
+mod health_tracking_middleware;
+mod lazy_connection;
+mod provider_pool;
+
+#[derive(Clone)]
+pub struct RpcClient {
+    inner: ProviderPool<LazyConnection>,
+    main_address: Address,
+    blob_address: Option<Address>,
+    nonce_manager: Option<NonceManager>,
+}
+
+impl RpcClient {
+    /// Connects to every url in `urls` over WebSocket or HTTP, depending on its scheme. The first
+    /// url is the primary used for submitting transactions. At least one endpoint has to be
+    /// reachable, the others stay in the pool as unhealthy and are connected to once they are up.
+    pub async fn connect(
+        urls: Vec<Url>,
+        contract_address: Address,
+        main_signer: Signer,
+        blob_signer: Option<Signer>,
+        unhealthy_after_n_errors: usize,
+    ) -> ports::l1::Result<Self> {
+        let main_address = main_signer.address();
+        let blob_address = blob_signer.as_ref().map(TxSigner::address);
+
+        let mut attempts = vec![];
+        for url in urls {
+            let params = ConnectionParams {
+                url,
+                contract_address,
+                main_signer: main_signer.clone(),
+                blob_signer: blob_signer.clone(),
+            };
+            let connection = params.connect().await;
+            if let Err(err) = &connection {
+                tracing::warn!(
+                    "could not connect to eth endpoint `{}`, will keep retrying: {err}",
+                    params.host()
+                );
+            }
+            attempts.push((params, connection));
+        }
+
+        let commit_interval = attempts
+            .iter()
+            .find_map(|(_, connection)| connection.as_ref().ok())
+            .map(EthApi::commit_interval);
+        let Some(commit_interval) = commit_interval else {
+            return Err(attempts
+                .into_iter()
+                .find_map(|(_, connection)| connection.err())
+                .map_or_else(
+                    || ports::l1::Error::Other("no eth endpoints configured".to_string()),
+                    Into::into,
+                ));
+        };
+
+        let mut unreachable = vec![];
+        let endpoints = attempts
+            .into_iter()
+            .enumerate()
+            .map(|(position, (params, connection))| {
+                let url = params.url.clone();
+                let endpoint = match connection {
+                    Ok(connection) => LazyConnection::connected(params, connection),
+                    Err(_) => {
+                        unreachable.push(position);
+                        LazyConnection::pending(params, commit_interval)
+                    }
+                };
+                (url, endpoint)
+            })
+            .collect();
+
+        let inner = ProviderPool::new(endpoints, unhealthy_after_n_errors);
+        for position in unreachable {
+            inner.mark_unhealthy(position);
+        }
+
+        Ok(Self {
+            inner,
+            main_address,
+            blob_address,
+            nonce_manager: None,
+        })
+    }
+
+    /// Takes the nonces of both wallets from `nonce_manager` instead of counting them in memory.
+    /// Nonces are to be reconciled before the first tx is sent.
+    #[must_use]
+    pub fn with_nonce_manager(mut self, nonce_manager: NonceManager) -> Self {
+        self.nonce_manager = Some(nonce_manager);
+        self
+    }
+
+    /// Continues handing out nonces from the pending nonces L1 reports for both wallets. Only to
+    /// be done by the replica that sends, as it forgets reservations L1 has yet to see a tx for.
+    pub async fn reconcile_nonces(&self) -> ports::l1::Result<()> {
+        let Some(nonce_manager) = &self.nonce_manager else {
+            return Ok(());
+        };
+
+        for address in std::iter::once(self.main_address).chain(self.blob_address) {
+            let pending_nonce = self.inner.pending_nonce(address).await?;
+            nonce_manager.reconcile(address, pending_nonce).await?;
+        }
+
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn connection_health_checker(&self) -> HealthChecker {
+        self.inner.connection_health_checker()
+    }
+
+    pub(crate) fn event_streamer(&self, eth_block_height: u64) -> EthEventStreamer {
+        self.inner
+            .event_streamer(eth_block_height)
+            .expect("an eth endpoint to be connected, `connect` fails otherwise")
+    }
+
+    pub(crate) async fn submit(&self, block: ValidatedFuelBlock) -> Result<()> {
+        self.send(self.main_address, |nonce| self.inner.submit(block, nonce))
+            .await?;
+        Ok(())
+    }
+
+    pub(crate) fn commit_interval(&self) -> NonZeroU32 {
+        self.inner.commit_interval()
+    }
+
+    pub(crate) async fn get_block_number(&self) -> Result<u64> {
+        Ok(self.inner.get_block_number().await?)
+    }
+
+    pub(crate) async fn block_hash(&self, number: u64) -> Result<Option<[u8; 32]>> {
+        Ok(self.inner.block_hash(number).await?)
+    }
+
+    /// Chain id reported by the endpoints.
+    pub async fn chain_id(&self) -> Result<u64> {
+        Ok(self.inner.chain_id().await?)
+    }
+
+    pub(crate) async fn get_transaction_response(
+        &self,
+        tx_hash: [u8; 32],
+    ) -> Result<Option<TransactionResponse>> {
+        Ok(self.inner.get_transaction_response(tx_hash).await?)
+    }
+
+    pub(crate) async fn balance(&self, wallet: WalletRole) -> Result<U256> {
+        let address = match wallet {
+            WalletRole::Main => self.main_address,
+            WalletRole::Blob => self.blob_address.ok_or_else(|| {
+                ports::l1::Error::Other("blob pool wallet not configured".to_string())
+            })?,
+        };
+
+        Ok(self.inner.balance(address).await?)
+    }
+
+    pub async fn submit_l2_state(&self, tx: Vec<u8>) -> Result<[u8; 32]> {
+        // without a blob wallet the connection refuses the submission
+        let address = self.blob_address.unwrap_or(self.main_address);
+        Ok(self
+            .send(address, |nonce| self.inner.submit_l2_state(tx, nonce))
+            .await?)
+    }
+
+    async fn send<F, Fut>(&self, address: Address, send: F) -> crate::error::Result<[u8; 32]>
+    where
+        F: FnOnce(Option<u64>) -> Fut,
+        Fut: Future<Output = crate::error::Result<[u8; 32]>>,
+    {
+        let Some(nonce_manager) = &self.nonce_manager else {
+            return send(None).await;
+        };
+
+        let pending_nonce = self.inner.pending_nonce(address).await?;
+        nonce_manager
+            .send(address, pending_nonce, |nonce| send(Some(nonce)))
+            .await
+    }
+
+    #[cfg(feature = "test-helpers")]
+    pub async fn finalized(&self, block: ValidatedFuelBlock) -> Result<bool> {
+        Ok(self.inner.finalized(block).await?)
+    }
+
+    #[cfg(feature = "test-helpers")]
+    pub async fn block_hash_at_commit_height(&self, commit_height: u32) -> Result<[u8; 32]> {
+        Ok(self
+            .inner
+            .block_hash_at_commit_height(commit_height)
+            .await?)
+    }
+}
+
+// User responsible for registering any metrics T might have
+impl RegistersMetrics for RpcClient {
+    fn metrics(&self) -> Vec<Box<dyn Collector>> {
+        let mut metrics = self.inner.metrics();
+        if let Some(nonce_manager) = &self.nonce_manager {
+            metrics.extend(nonce_manager.metrics());
+        }
+        metrics
+    }
+}
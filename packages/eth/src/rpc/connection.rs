@@ -6,41 +6,32 @@ use alloy::{
     primitives::{Address, U256},
     providers::{
         fillers::{ChainIdFiller, FillProvider, GasFiller, JoinFill, NonceFiller, WalletFiller},
-        Identity, Provider, ProviderBuilder, RootProvider, WsConnect,
+        Identity, Provider, ProviderBuilder, RootProvider,
     },
-    pubsub::PubSubFrontend,
     rpc::types::{TransactionReceipt, TransactionRequest},
-    signers::aws::AwsSigner,
     sol,
+    transports::BoxTransport,
 };
 use ports::types::{TransactionResponse, ValidatedFuelBlock};
 use url::Url;
 
 use super::{event_streamer::EthEventStreamer, health_tracking_middleware::EthApi};
-use crate::error::{Error, Result};
+use crate::{
+    error::{Error, Result},
+    Signer,
+};
 
-pub type WsProvider = FillProvider<
+pub type RpcProvider = FillProvider<
     JoinFill<
         JoinFill<JoinFill<JoinFill<Identity, GasFiller>, NonceFiller>, ChainIdFiller>,
         WalletFiller<EthereumWallet>,
     >,
-    RootProvider<PubSubFrontend>,
-    PubSubFrontend,
+    RootProvider<BoxTransport>,
+    BoxTransport,
     Ethereum,
 >;
 
-type FuelStateContract = IFuelStateContract::IFuelStateContractInstance<
-    PubSubFrontend,
-    FillProvider<
-        JoinFill<
-            JoinFill<JoinFill<JoinFill<Identity, GasFiller>, NonceFiller>, ChainIdFiller>,
-            WalletFiller<EthereumWallet>,
-        >,
-        RootProvider<PubSubFrontend>,
-        PubSubFrontend,
-        Ethereum,
-    >,
->;
+type FuelStateContract = IFuelStateContract::IFuelStateContractInstance<BoxTransport, RpcProvider>;
 
 sol!(
     #[sol(rpc)]
@@ -53,25 +44,47 @@ sol!(
     }
 );
 
+/// How an RPC endpoint is reached, as told by the scheme of its url.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+    WebSocket,
+    Http,
+}
+
+impl Transport {
+    pub fn from_url(url: &Url) -> Result<Self> {
+        match url.scheme() {
+            "ws" | "wss" => Ok(Self::WebSocket),
+            "http" | "https" => Ok(Self::Http),
+            scheme => Err(Error::Other(format!(
+                "unsupported eth rpc url scheme `{scheme}`, expected one of ws, wss, http or https"
+            ))),
+        }
+    }
+}
+
 #[derive(Clone)]
-pub struct WsConnection {
-    provider: WsProvider,
-    blob_provider: Option<WsProvider>,
-    address: Address,
+pub struct RpcConnection {
+    provider: RpcProvider,
+    blob_provider: Option<RpcProvider>,
     blob_signer_address: Option<Address>,
     contract: FuelStateContract,
     commit_interval: NonZeroU32,
+    transport: Transport,
 }
 
 #[async_trait::async_trait]
-impl EthApi for WsConnection {
-    async fn submit(&self, block: ValidatedFuelBlock) -> Result<()> {
+impl EthApi for RpcConnection {
+    async fn submit(&self, block: ValidatedFuelBlock, nonce: Option<u64>) -> Result<[u8; 32]> {
         let commit_height = Self::calculate_commit_height(block.height(), self.commit_interval);
-        let contract_call = self.contract.commit(block.hash().into(), commit_height);
+        let mut contract_call = self.contract.commit(block.hash().into(), commit_height);
+        if let Some(nonce) = nonce {
+            contract_call = contract_call.nonce(nonce);
+        }
         let tx = contract_call.send().await?;
         tracing::info!("tx: {} submitted", tx.tx_hash());
 
-        Ok(())
+        Ok(tx.tx_hash().0)
     }
 
     async fn get_block_number(&self) -> Result<u64> {
@@ -79,8 +92,20 @@ impl EthApi for WsConnection {
         Ok(response)
     }
 
-    async fn balance(&self) -> Result<U256> {
-        let address = self.address;
+    async fn block_hash(&self, number: u64) -> Result<Option<[u8; 32]>> {
+        let block = self
+            .provider
+            .get_block_by_number(number.into(), false)
+            .await?;
+
+        Ok(block.and_then(|block| block.header.hash).map(|hash| hash.0))
+    }
+
+    async fn chain_id(&self) -> Result<u64> {
+        Ok(self.provider.get_chain_id().await?)
+    }
+
+    async fn balance(&self, address: Address) -> Result<U256> {
         Ok(self.provider.get_balance(address).await?)
     }
 
@@ -88,13 +113,17 @@ impl EthApi for WsConnection {
         self.commit_interval
     }
 
-    fn event_streamer(&self, eth_block_height: u64) -> EthEventStreamer {
+    fn event_streamer(&self, eth_block_height: u64) -> Option<EthEventStreamer> {
         let filter = self
             .contract
             .CommitSubmitted_filter()
             .from_block(eth_block_height)
             .filter;
-        EthEventStreamer::new(filter, self.contract.provider().clone())
+        Some(EthEventStreamer::new(
+            filter,
+            self.contract.provider().clone(),
+            self.transport,
+        ))
     }
 
     async fn get_transaction_response(
@@ -109,22 +138,33 @@ impl EthApi for WsConnection {
         Self::convert_to_tx_response(tx_receipt)
     }
 
-    async fn submit_l2_state(&self, state_data: Vec<u8>) -> Result<[u8; 32]> {
+    async fn submit_l2_state(&self, state_data: Vec<u8>, nonce: Option<u64>) -> Result<[u8; 32]> {
         let (blob_provider, blob_signer_address) =
             match (&self.blob_provider, &self.blob_signer_address) {
                 (Some(provider), Some(address)) => (provider, address),
                 _ => return Err(Error::Other("blob pool signer not configured".to_string())),
             };
 
-        let blob_tx = self
+        let mut blob_tx = self
             .prepare_blob_tx(&state_data, *blob_signer_address)
             .await?;
+        if let Some(nonce) = nonce {
+            blob_tx.set_nonce(nonce);
+        }
 
         let tx = blob_provider.send_transaction(blob_tx).await?;
 
         Ok(tx.tx_hash().0)
     }
 
+    async fn pending_nonce(&self, address: Address) -> Result<u64> {
+        Ok(self
+            .provider
+            .get_transaction_count(address)
+            .pending()
+            .await?)
+    }
+
     #[cfg(feature = "test-helpers")]
     async fn finalized(&self, block: ValidatedFuelBlock) -> Result<bool> {
         Ok(self
@@ -147,21 +187,20 @@ impl EthApi for WsConnection {
     }
 }
 
-impl WsConnection {
+impl RpcConnection {
     pub async fn connect(
         url: Url,
         contract_address: Address,
-        main_signer: AwsSigner,
-        blob_signer: Option<AwsSigner>,
+        main_signer: Signer,
+        blob_signer: Option<Signer>,
     ) -> Result<Self> {
-        let address = main_signer.address();
+        let transport = Transport::from_url(&url)?;
 
-        let ws = WsConnect::new(url);
-        let provider = Self::provider_with_signer(ws.clone(), main_signer).await?;
+        let provider = Self::provider_with_signer(&url, main_signer).await?;
 
         let (blob_provider, blob_signer_address) = if let Some(signer) = blob_signer {
             let blob_signer_address = signer.address();
-            let blob_provider = Self::provider_with_signer(ws, signer).await?;
+            let blob_provider = Self::provider_with_signer(&url, signer).await?;
             (Some(blob_provider), Some(blob_signer_address))
         } else {
             (None, None)
@@ -183,19 +222,19 @@ impl WsConnection {
         Ok(Self {
             provider,
             blob_provider,
-            address,
             blob_signer_address,
             contract,
             commit_interval,
+            transport,
         })
     }
 
-    async fn provider_with_signer(ws: WsConnect, signer: AwsSigner) -> Result<WsProvider> {
+    async fn provider_with_signer(url: &Url, signer: Signer) -> Result<RpcProvider> {
         let wallet = EthereumWallet::from(signer);
         ProviderBuilder::new()
             .with_recommended_fillers()
             .wallet(wallet)
-            .on_ws(ws)
+            .on_builtin(url.as_str())
             .await
             .map_err(Into::into)
     }
@@ -204,10 +243,6 @@ impl WsConnection {
         U256::from(block_height / commit_interval)
     }
 
-    async fn _balance(&self, address: Address) -> Result<U256> {
-        Ok(self.provider.get_balance(address).await?)
-    }
-
     async fn prepare_blob_tx(&self, data: &[u8], to: Address) -> Result<TransactionRequest> {
         let sidecar = SidecarBuilder::from_coder_and_data(SimpleCoder::default(), data).build()?;
 
@@ -244,10 +279,36 @@ impl WsConnection {
 mod tests {
     use super::*;
 
+    #[test]
+    fn transport_is_picked_by_url_scheme() {
+        let transport_of = |url: &str| Transport::from_url(&Url::parse(url).unwrap());
+
+        assert_eq!(
+            transport_of("ws://localhost:8545").unwrap(),
+            Transport::WebSocket
+        );
+        assert_eq!(
+            transport_of("wss://rpc.example").unwrap(),
+            Transport::WebSocket
+        );
+        assert_eq!(
+            transport_of("http://localhost:8545").unwrap(),
+            Transport::Http
+        );
+        assert_eq!(
+            transport_of("https://rpc.example").unwrap(),
+            Transport::Http
+        );
+        assert!(matches!(
+            transport_of("ipc:///tmp/geth.ipc"),
+            Err(Error::Other(_))
+        ));
+    }
+
     #[test]
     fn calculates_correctly_the_commit_height() {
         assert_eq!(
-            WsConnection::calculate_commit_height(10, 3.try_into().unwrap()),
+            RpcConnection::calculate_commit_height(10, 3.try_into().unwrap()),
             U256::from(3)
         );
     }
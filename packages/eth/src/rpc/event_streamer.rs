@@ -0,0 +1,268 @@
+use std::{collections::HashSet, pin::Pin};
+
+use alloy::{
+    primitives::U256,
+    providers::Provider,
+    rpc::types::{Filter, Log},
+    sol_types::SolEvent,
+};
+use futures::{future, stream, Stream, StreamExt, TryStreamExt};
+use ports::types::{FuelBlockCommittedOnL1, L1Height};
+
+use super::connection::{IFuelStateContract::CommitSubmitted, RpcProvider, Transport};
+use crate::error::{Error, Result};
+
+pub struct EthEventStreamer {
+    filter: Filter,
+    provider: RpcProvider,
+    transport: Transport,
+}
+
+struct PollState {
+    // first block not yet looked at
+    next_block: u64,
+    // logs delivered from blocks that might still be reorged
+    recent_logs: Vec<Log>,
+    wait: bool,
+}
+
+impl EthEventStreamer {
+    // Many providers cap the block range of a single `eth_getLogs` call.
+    const BACKFILL_CHUNK_SIZE: u64 = 2_000;
+    // Blocks further below the tip are taken to be final, two epochs on mainnet.
+    const REORG_WINDOW: u64 = 64;
+
+    pub fn new(filter: Filter, provider: RpcProvider, transport: Transport) -> Self {
+        Self {
+            filter,
+            provider,
+            transport,
+        }
+    }
+
+    pub(crate) async fn establish_stream(
+        &self,
+    ) -> Result<impl Stream<Item = Result<FuelBlockCommittedOnL1>> + Send + '_> {
+        let logs: Pin<Box<dyn Stream<Item = Result<Log>> + Send + '_>> = match self.transport {
+            Transport::WebSocket => Box::pin(self.backfilled_subscription().await?),
+            Transport::Http => Box::pin(self.poll_logs()),
+        };
+
+        let stream = logs.and_then(|log| async move {
+            let l1_height = log
+                .block_number
+                .ok_or_else(|| Error::Other("commit event log without a block number".to_string()))
+                .and_then(|height| {
+                    L1Height::try_from(height).map_err(|e| Error::Other(e.to_string()))
+                })?;
+            let l1_block_hash = log
+                .block_hash
+                .ok_or_else(|| Error::Other("commit event log without a block hash".to_string()))?;
+            let CommitSubmitted {
+                blockHash,
+                commitHeight,
+            } = CommitSubmitted::decode_log_data(log.data(), false)?;
+            Ok(FuelBlockCommittedOnL1 {
+                fuel_block_hash: blockHash.into(),
+                commit_height: U256::from(commitHeight),
+                l1_height,
+                l1_block_hash: l1_block_hash.0,
+                removed: log.removed,
+            })
+        });
+
+        Ok(stream)
+    }
+
+    // Subscriptions rarely deliver logs from before they were opened. The subscription is opened
+    // first so nothing emitted during the backfill is lost, and the logs it has in common with the
+    // backfill are dropped. Removals are always let through as the backfill cannot report them.
+    async fn backfilled_subscription(&self) -> Result<impl Stream<Item = Result<Log>> + Send + '_> {
+        let subscription = self.provider.subscribe_logs(&self.filter).await?;
+        let backfilled_up_to = self.provider.get_block_number().await?;
+        let from_block = self.filter.get_from_block().unwrap_or_default();
+
+        let live = subscription
+            .into_stream()
+            .filter(move |log| {
+                future::ready(
+                    log.removed
+                        || log
+                            .block_number
+                            .map_or(true, |block| block > backfilled_up_to),
+                )
+            })
+            .map(Ok);
+
+        Ok(self.backfill(from_block, backfilled_up_to).chain(live))
+    }
+
+    // Plain HTTP offers no subscriptions, so new logs are fetched with `eth_getLogs` every poll
+    // interval. As `eth_getLogs` never reports removed logs, the last `REORG_WINDOW` blocks are
+    // fetched again on every poll and the logs no longer found there, told apart by the hash of
+    // their block, are yielded as removed. Errors are yielded and polling carries on.
+    fn poll_logs(&self) -> impl Stream<Item = Result<Log>> + Send + '_ {
+        let poll_interval = self.provider.client().poll_interval();
+        let state = PollState {
+            next_block: self.filter.get_from_block().unwrap_or_default(),
+            recent_logs: vec![],
+            wait: false,
+        };
+
+        stream::unfold(state, move |mut state| async move {
+            if state.wait {
+                tokio::time::sleep(poll_interval).await;
+            }
+            state.wait = true;
+
+            let logs = match self.poll(&mut state).await {
+                Ok(logs) => logs.into_iter().map(Ok).collect(),
+                Err(err) => vec![Err(err)],
+            };
+
+            Some((stream::iter(logs), state))
+        })
+        .flatten()
+    }
+
+    // `state` is only advanced once every log could be fetched
+    async fn poll(&self, state: &mut PollState) -> Result<Vec<Log>> {
+        let latest_block = self.provider.get_block_number().await?;
+        let from_block = self.filter.get_from_block().unwrap_or_default();
+        let window_start = latest_block
+            .saturating_sub(Self::REORG_WINDOW)
+            .max(from_block);
+
+        let mut logs = if state.next_block < window_start {
+            self.backfill(state.next_block, window_start - 1)
+                .try_collect()
+                .await?
+        } else {
+            vec![]
+        };
+        let window_logs: Vec<_> = self
+            .backfill(window_start, latest_block)
+            .try_collect()
+            .await?;
+
+        // logs below the window are final, those above the tip are left to be judged once the
+        // queried endpoint has caught up again
+        let (delivered, ahead): (Vec<_>, Vec<_>) = std::mem::take(&mut state.recent_logs)
+            .into_iter()
+            .filter(|log| log.block_number.unwrap_or_default() >= window_start)
+            .partition(|log| log.block_number.unwrap_or_default() <= latest_block);
+
+        logs.extend(reconcile_logs(&delivered, &window_logs));
+        state.recent_logs = window_logs.into_iter().chain(ahead).collect();
+        state.next_block = state.next_block.max(latest_block.saturating_add(1));
+
+        Ok(logs)
+    }
+
+    // logs from `from_block` through `to_block`, fetched in chunks
+    fn backfill(
+        &self,
+        from_block: u64,
+        to_block: u64,
+    ) -> impl Stream<Item = Result<Log>> + Send + '_ {
+        stream::try_unfold(from_block, move |from_block| async move {
+            if from_block > to_block {
+                return Ok(None);
+            }
+
+            let chunk_end = to_block.min(from_block.saturating_add(Self::BACKFILL_CHUNK_SIZE - 1));
+            self.logs_in(from_block, chunk_end).await.map(|logs| {
+                let logs = stream::iter(logs.into_iter().map(Ok));
+                Some((logs, chunk_end + 1))
+            })
+        })
+        .try_flatten()
+    }
+
+    async fn logs_in(&self, from_block: u64, to_block: u64) -> Result<Vec<Log>> {
+        let filter = self
+            .filter
+            .clone()
+            .from_block(from_block)
+            .to_block(to_block);
+
+        Ok(self.provider.get_logs(&filter).await?)
+    }
+}
+
+// The logs in `fetched` missing from `delivered`, preceded by the ones in `delivered` no longer in
+// `fetched` marked as removed.
+fn reconcile_logs(delivered: &[Log], fetched: &[Log]) -> Vec<Log> {
+    let key = |log: &Log| (log.block_hash, log.log_index);
+    let delivered_keys: HashSet<_> = delivered.iter().map(key).collect();
+    let fetched_keys: HashSet<_> = fetched.iter().map(key).collect();
+
+    let removed = delivered
+        .iter()
+        .filter(|log| !fetched_keys.contains(&key(log)))
+        .cloned()
+        .map(|mut log| {
+            log.removed = true;
+            log
+        });
+    let added = fetched
+        .iter()
+        .filter(|log| !delivered_keys.contains(&key(log)))
+        .cloned();
+
+    removed.chain(added).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::B256;
+
+    use super::*;
+
+    #[test]
+    fn reorged_logs_are_removed_and_their_replacements_added() {
+        // given
+        let kept = given_a_log(10, 1, 0);
+        let reorged = given_a_log(11, 1, 0);
+        let replacement = given_a_log(11, 2, 0);
+        let new = given_a_log(12, 1, 0);
+
+        // when
+        let logs = reconcile_logs(
+            &[kept.clone(), reorged.clone()],
+            &[kept, replacement.clone(), new.clone()],
+        );
+
+        // then
+        let removed = Log {
+            removed: true,
+            ..reorged
+        };
+        assert_eq!(logs, vec![removed, replacement, new]);
+    }
+
+    #[test]
+    fn logs_seen_before_are_not_delivered_again() {
+        // given
+        let delivered = vec![given_a_log(10, 1, 0), given_a_log(10, 1, 1)];
+
+        // when
+        let logs = reconcile_logs(&delivered, &delivered);
+
+        // then
+        assert!(logs.is_empty());
+    }
+
+    // `fork` tells apart blocks of the same number on different forks
+    fn given_a_log(block_number: u64, fork: u8, log_index: u64) -> Log {
+        let mut block_hash = [fork; 32];
+        block_hash[..8].copy_from_slice(&block_number.to_be_bytes());
+
+        Log {
+            block_hash: Some(B256::from(block_hash)),
+            block_number: Some(block_number),
+            log_index: Some(log_index),
+            ..Default::default()
+        }
+    }
+}
@@ -3,27 +3,35 @@ use std::num::NonZeroU32;
 use ::metrics::{
     prometheus::core::Collector, ConnectionHealthTracker, HealthChecker, RegistersMetrics,
 };
+use alloy::primitives::Address;
 use ports::types::{TransactionResponse, ValidatedFuelBlock, U256};
 
 use crate::{
     error::{Error, Result},
     metrics::Metrics,
-    websocket::event_streamer::EthEventStreamer,
+    rpc::event_streamer::EthEventStreamer,
 };
 
 #[cfg_attr(test, mockall::automock)]
 #[async_trait::async_trait]
 pub trait EthApi {
-    async fn submit(&self, block: ValidatedFuelBlock) -> Result<()>;
+    /// Leaves picking the nonce to the provider if `nonce` is `None`.
+    async fn submit(&self, block: ValidatedFuelBlock, nonce: Option<u64>) -> Result<[u8; 32]>;
     async fn get_block_number(&self) -> Result<u64>;
-    async fn balance(&self) -> Result<U256>;
+    /// `None` if there is no block at `number` yet.
+    async fn block_hash(&self, number: u64) -> Result<Option<[u8; 32]>>;
+    async fn chain_id(&self) -> Result<u64>;
+    async fn balance(&self, address: Address) -> Result<U256>;
     fn commit_interval(&self) -> NonZeroU32;
-    fn event_streamer(&self, eth_block_height: u64) -> EthEventStreamer;
+    /// `None` if the endpoint has yet to be connected to.
+    fn event_streamer(&self, eth_block_height: u64) -> Option<EthEventStreamer>;
     async fn get_transaction_response(
         &self,
         tx_hash: [u8; 32],
     ) -> Result<Option<TransactionResponse>>;
-    async fn submit_l2_state(&self, state_data: Vec<u8>) -> Result<[u8; 32]>;
+    async fn submit_l2_state(&self, state_data: Vec<u8>, nonce: Option<u64>) -> Result<[u8; 32]>;
+    /// Transaction count of `address` including the ones waiting in the mempool.
+    async fn pending_nonce(&self, address: Address) -> Result<u64>;
     #[cfg(feature = "test-helpers")]
     async fn finalized(&self, block: ValidatedFuelBlock) -> Result<bool>;
     #[cfg(feature = "test-helpers")]
@@ -46,10 +54,20 @@ impl<T> HealthTrackingMiddleware<T> {
         }
     }
 
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
     pub fn connection_health_checker(&self) -> HealthChecker {
         self.health_tracker.tracker()
     }
 
+    /// Deems the endpoint unhealthy until a call to it succeeds.
+    pub fn mark_unhealthy(&self) {
+        self.health_tracker.note_down();
+    }
+
     fn note_network_status<K>(&self, response: &Result<K>) {
         match response {
             Ok(_val) => {
@@ -76,8 +94,8 @@ impl<T> EthApi for HealthTrackingMiddleware<T>
 where
     T: EthApi + Send + Sync,
 {
-    async fn submit(&self, block: ValidatedFuelBlock) -> Result<()> {
-        let response = self.adapter.submit(block).await;
+    async fn submit(&self, block: ValidatedFuelBlock, nonce: Option<u64>) -> Result<[u8; 32]> {
+        let response = self.adapter.submit(block, nonce).await;
         self.note_network_status(&response);
         response
     }
@@ -88,6 +106,18 @@ where
         response
     }
 
+    async fn block_hash(&self, number: u64) -> Result<Option<[u8; 32]>> {
+        let response = self.adapter.block_hash(number).await;
+        self.note_network_status(&response);
+        response
+    }
+
+    async fn chain_id(&self) -> Result<u64> {
+        let response = self.adapter.chain_id().await;
+        self.note_network_status(&response);
+        response
+    }
+
     async fn get_transaction_response(
         &self,
         tx_hash: [u8; 32],
@@ -97,12 +127,12 @@ where
         response
     }
 
-    fn event_streamer(&self, eth_block_height: u64) -> EthEventStreamer {
+    fn event_streamer(&self, eth_block_height: u64) -> Option<EthEventStreamer> {
         self.adapter.event_streamer(eth_block_height)
     }
 
-    async fn balance(&self) -> Result<U256> {
-        let response = self.adapter.balance().await;
+    async fn balance(&self, address: Address) -> Result<U256> {
+        let response = self.adapter.balance(address).await;
         self.note_network_status(&response);
         response
     }
@@ -111,8 +141,14 @@ where
         self.adapter.commit_interval()
     }
 
-    async fn submit_l2_state(&self, tx: Vec<u8>) -> Result<[u8; 32]> {
-        let response = self.adapter.submit_l2_state(tx).await;
+    async fn submit_l2_state(&self, tx: Vec<u8>, nonce: Option<u64>) -> Result<[u8; 32]> {
+        let response = self.adapter.submit_l2_state(tx, nonce).await;
+        self.note_network_status(&response);
+        response
+    }
+
+    async fn pending_nonce(&self, address: Address) -> Result<u64> {
+        let response = self.adapter.pending_nonce(address).await;
         self.note_network_status(&response);
         response
     }
@@ -142,7 +178,7 @@ mod tests {
         let mut eth_adapter = MockEthApi::new();
         eth_adapter
             .expect_submit()
-            .returning(|_| Err(Error::Network("An error".into())));
+            .returning(|_, _| Err(Error::Network("An error".into())));
 
         eth_adapter
             .expect_get_block_number()
@@ -151,7 +187,7 @@ mod tests {
         let adapter = HealthTrackingMiddleware::new(eth_adapter, 1);
         let health_check = adapter.connection_health_checker();
 
-        let _ = adapter.submit(given_a_block(42)).await;
+        let _ = adapter.submit(given_a_block(42), None).await;
 
         // when
         let _ = adapter.get_block_number().await;
@@ -166,7 +202,7 @@ mod tests {
         let mut eth_adapter = MockEthApi::new();
         eth_adapter
             .expect_submit()
-            .returning(|_| Err(Error::Other("An error".into())));
+            .returning(|_, _| Err(Error::Other("An error".into())));
 
         eth_adapter
             .expect_get_block_number()
@@ -175,7 +211,7 @@ mod tests {
         let adapter = HealthTrackingMiddleware::new(eth_adapter, 2);
         let health_check = adapter.connection_health_checker();
 
-        let _ = adapter.submit(given_a_block(42)).await;
+        let _ = adapter.submit(given_a_block(42), None).await;
 
         // when
         let _ = adapter.get_block_number().await;
@@ -189,7 +225,7 @@ mod tests {
         let mut eth_adapter = MockEthApi::new();
         eth_adapter
             .expect_submit()
-            .returning(|_| Err(Error::Network("An error".into())));
+            .returning(|_, _| Err(Error::Network("An error".into())));
 
         eth_adapter
             .expect_get_block_number()
@@ -199,7 +235,7 @@ mod tests {
         let health_check = adapter.connection_health_checker();
         assert!(health_check.healthy());
 
-        let _ = adapter.submit(given_a_block(42)).await;
+        let _ = adapter.submit(given_a_block(42), None).await;
         assert!(health_check.healthy());
 
         let _ = adapter.get_block_number().await;
@@ -214,7 +250,7 @@ mod tests {
         let mut eth_adapter = MockEthApi::new();
         eth_adapter
             .expect_submit()
-            .returning(|_| Err(Error::Network("An error".into())));
+            .returning(|_, _| Err(Error::Network("An error".into())));
 
         eth_adapter
             .expect_get_block_number()
@@ -224,7 +260,7 @@ mod tests {
         let adapter = HealthTrackingMiddleware::new(eth_adapter, 3);
         adapter.register_metrics(&registry);
 
-        let _ = adapter.submit(given_a_block(42)).await;
+        let _ = adapter.submit(given_a_block(42), None).await;
         let _ = adapter.get_block_number().await;
 
         let metrics = registry.gather();
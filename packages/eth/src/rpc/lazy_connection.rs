@@ -0,0 +1,161 @@
+use std::num::NonZeroU32;
+
+use alloy::primitives::Address;
+use ports::types::{TransactionResponse, ValidatedFuelBlock, U256};
+use tokio::sync::OnceCell;
+use url::Url;
+
+use super::{
+    connection::RpcConnection, event_streamer::EthEventStreamer, health_tracking_middleware::EthApi,
+};
+use crate::{
+    error::{Error, Result},
+    Signer,
+};
+
+/// Everything needed to connect to an endpoint.
+#[derive(Clone)]
+pub struct ConnectionParams {
+    pub url: Url,
+    pub contract_address: Address,
+    pub main_signer: Signer,
+    pub blob_signer: Option<Signer>,
+}
+
+impl ConnectionParams {
+    pub async fn connect(&self) -> Result<RpcConnection> {
+        RpcConnection::connect(
+            self.url.clone(),
+            self.contract_address,
+            self.main_signer.clone(),
+            self.blob_signer.clone(),
+        )
+        .await
+    }
+
+    pub fn host(&self) -> &str {
+        self.url.host_str().unwrap_or_default()
+    }
+}
+
+/// An endpoint that might not have been reachable yet. Every call tries to connect until a
+/// connection is made, failing with a network error in the meantime so that the pool treats the
+/// endpoint as unhealthy.
+pub struct LazyConnection {
+    params: ConnectionParams,
+    // as reported by the endpoints that could be reached, all of them must agree
+    commit_interval: NonZeroU32,
+    connection: OnceCell<RpcConnection>,
+}
+
+impl LazyConnection {
+    pub fn connected(params: ConnectionParams, connection: RpcConnection) -> Self {
+        Self {
+            params,
+            commit_interval: connection.commit_interval(),
+            connection: OnceCell::new_with(Some(connection)),
+        }
+    }
+
+    pub fn pending(params: ConnectionParams, commit_interval: NonZeroU32) -> Self {
+        Self {
+            params,
+            commit_interval,
+            connection: OnceCell::new(),
+        }
+    }
+
+    async fn connection(&self) -> Result<&RpcConnection> {
+        self.connection
+            .get_or_try_init(|| async {
+                let connection = self.params.connect().await.map_err(|err| {
+                    Error::Network(format!(
+                        "could not connect to eth endpoint `{}`: {err}",
+                        self.params.host()
+                    ))
+                })?;
+
+                if connection.commit_interval() != self.commit_interval {
+                    return Err(Error::Other(format!(
+                        "eth endpoint `{}` reports a commit interval of {}, the other endpoints \
+                         one of {}",
+                        self.params.host(),
+                        connection.commit_interval(),
+                        self.commit_interval
+                    )));
+                }
+
+                tracing::info!("connected to eth endpoint `{}`", self.params.host());
+
+                Ok(connection)
+            })
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl EthApi for LazyConnection {
+    async fn submit(&self, block: ValidatedFuelBlock, nonce: Option<u64>) -> Result<[u8; 32]> {
+        self.connection().await?.submit(block, nonce).await
+    }
+
+    async fn get_block_number(&self) -> Result<u64> {
+        self.connection().await?.get_block_number().await
+    }
+
+    async fn block_hash(&self, number: u64) -> Result<Option<[u8; 32]>> {
+        self.connection().await?.block_hash(number).await
+    }
+
+    async fn chain_id(&self) -> Result<u64> {
+        self.connection().await?.chain_id().await
+    }
+
+    async fn balance(&self, address: Address) -> Result<U256> {
+        self.connection().await?.balance(address).await
+    }
+
+    fn commit_interval(&self) -> NonZeroU32 {
+        self.commit_interval
+    }
+
+    fn event_streamer(&self, eth_block_height: u64) -> Option<EthEventStreamer> {
+        self.connection
+            .get()
+            .and_then(|connection| connection.event_streamer(eth_block_height))
+    }
+
+    async fn get_transaction_response(
+        &self,
+        tx_hash: [u8; 32],
+    ) -> Result<Option<TransactionResponse>> {
+        self.connection()
+            .await?
+            .get_transaction_response(tx_hash)
+            .await
+    }
+
+    async fn submit_l2_state(&self, state_data: Vec<u8>, nonce: Option<u64>) -> Result<[u8; 32]> {
+        self.connection()
+            .await?
+            .submit_l2_state(state_data, nonce)
+            .await
+    }
+
+    async fn pending_nonce(&self, address: Address) -> Result<u64> {
+        self.connection().await?.pending_nonce(address).await
+    }
+
+    #[cfg(feature = "test-helpers")]
+    async fn finalized(&self, block: ValidatedFuelBlock) -> Result<bool> {
+        self.connection().await?.finalized(block).await
+    }
+
+    #[cfg(feature = "test-helpers")]
+    async fn block_hash_at_commit_height(&self, commit_height: u32) -> Result<[u8; 32]> {
+        self.connection()
+            .await?
+            .block_hash_at_commit_height(commit_height)
+            .await
+    }
+}
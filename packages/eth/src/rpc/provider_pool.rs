@@ -0,0 +1,580 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    num::NonZeroU32,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use ::metrics::{
+    endpoint_labels,
+    prometheus::{core::Collector, HistogramOpts, HistogramVec, IntGauge, Opts},
+    HealthCheck, HealthChecker, RegistersMetrics,
+};
+use alloy::primitives::Address;
+use ports::types::{TransactionResponse, ValidatedFuelBlock, U256};
+use url::Url;
+
+use super::{
+    event_streamer::EthEventStreamer,
+    health_tracking_middleware::{EthApi, HealthTrackingMiddleware},
+};
+use crate::{
+    error::{Error, Result},
+    metrics::Metrics,
+};
+
+struct PooledEndpoint<T> {
+    api: HealthTrackingMiddleware<T>,
+    health: HealthChecker,
+    metrics: EndpointMetrics,
+    last_retried: Mutex<Instant>,
+}
+
+impl<T> PooledEndpoint<T> {
+    // whether an unhealthy endpoint is due to be given another chance, which then counts as given
+    fn take_retry(&self, retry_after: Duration) -> bool {
+        let mut last_retried = self
+            .last_retried
+            .lock()
+            .expect("retry time lock not to be poisoned");
+        let due = last_retried.elapsed() >= retry_after;
+        if due {
+            *last_retried = Instant::now();
+        }
+
+        due
+    }
+}
+
+/// Spreads calls over several Ethereum RPC endpoints. Reads rotate between the healthy endpoints,
+/// writes go to the first (primary) endpoint while it is healthy. Either falls back to the next
+/// endpoint on a network error. Unhealthy endpoints are regularly given a read to recover with,
+/// the primary taking back the writes once it has.
+pub struct ProviderPool<T> {
+    endpoints: Arc<[PooledEndpoint<T>]>,
+    next_reader: Arc<AtomicUsize>,
+    retry_unhealthy_after: Duration,
+}
+
+impl<T> Clone for ProviderPool<T> {
+    fn clone(&self) -> Self {
+        Self {
+            endpoints: Arc::clone(&self.endpoints),
+            next_reader: Arc::clone(&self.next_reader),
+            retry_unhealthy_after: self.retry_unhealthy_after,
+        }
+    }
+}
+
+impl<T> ProviderPool<T> {
+    const DEFAULT_RETRY_UNHEALTHY_AFTER: Duration = Duration::from_secs(30);
+
+    /// # Panics
+    /// If no endpoints are given.
+    pub fn new(endpoints: Vec<(Url, T)>, unhealthy_after_n_errors: usize) -> Self {
+        assert!(
+            !endpoints.is_empty(),
+            "provider pool needs at least one endpoint"
+        );
+
+        let endpoints = endpoints
+            .into_iter()
+            .enumerate()
+            .map(|(position, (url, adapter))| {
+                let labels = endpoint_labels(position, &url);
+                let api = HealthTrackingMiddleware::new(adapter, unhealthy_after_n_errors)
+                    .with_metrics(Metrics::with_const_labels(labels.clone()));
+                let health = api.connection_health_checker();
+
+                PooledEndpoint {
+                    api,
+                    health,
+                    metrics: EndpointMetrics::new(labels),
+                    last_retried: Mutex::new(Instant::now()),
+                }
+            })
+            .collect();
+
+        Self {
+            endpoints,
+            next_reader: Arc::new(AtomicUsize::new(0)),
+            retry_unhealthy_after: Self::DEFAULT_RETRY_UNHEALTHY_AFTER,
+        }
+    }
+
+    /// How often an unhealthy endpoint is given a read to see if it has recovered.
+    #[cfg(test)]
+    pub fn with_retry_unhealthy_after(mut self, retry_unhealthy_after: Duration) -> Self {
+        self.retry_unhealthy_after = retry_unhealthy_after;
+        self
+    }
+
+    /// Deems the endpoint at `position` unhealthy until a call to it succeeds.
+    pub fn mark_unhealthy(&self, position: usize) {
+        let endpoint = &self.endpoints[position];
+        endpoint.api.mark_unhealthy();
+        endpoint.metrics.healthy.set(0);
+    }
+
+    /// Healthy for as long as at least one endpoint is.
+    pub fn connection_health_checker(&self) -> HealthChecker
+    where
+        T: Send + Sync + 'static,
+    {
+        Box::new(AnyEndpointHealthy(Arc::clone(&self.endpoints)))
+    }
+
+    fn primary(&self) -> &PooledEndpoint<T> {
+        &self.endpoints[0]
+    }
+
+    // healthy endpoints first, primary before fallbacks
+    fn writers(&self) -> Vec<&PooledEndpoint<T>> {
+        let mut endpoints: Vec<_> = self.endpoints.iter().collect();
+        endpoints.sort_by_key(|endpoint| !endpoint.health.healthy());
+
+        endpoints
+    }
+
+    // unhealthy endpoints due for a retry first, then the healthy ones, taking turns on which one
+    // is asked first
+    fn readers(&self) -> Vec<&PooledEndpoint<T>> {
+        let start = self.next_reader.fetch_add(1, Ordering::Relaxed) % self.endpoints.len();
+
+        let mut endpoints: Vec<_> = self.endpoints[start..]
+            .iter()
+            .chain(&self.endpoints[..start])
+            .map(|endpoint| {
+                let healthy = endpoint.health.healthy();
+                let retry = !healthy && endpoint.take_retry(self.retry_unhealthy_after);
+                (endpoint, (!retry, !healthy))
+            })
+            .collect();
+        endpoints.sort_by_key(|(_, order)| *order);
+
+        endpoints
+            .into_iter()
+            .map(|(endpoint, _)| endpoint)
+            .collect()
+    }
+}
+
+impl<T: EthApi + Send + Sync> ProviderPool<T> {
+    async fn first_success<'a, R, F, Fut>(
+        endpoints: Vec<&'a PooledEndpoint<T>>,
+        method: &str,
+        call: F,
+    ) -> Result<R>
+    where
+        F: Fn(&'a HealthTrackingMiddleware<T>) -> Fut,
+        Fut: Future<Output = Result<R>>,
+    {
+        let mut last_error = None;
+        for endpoint in endpoints {
+            let response = endpoint.metrics.time(method, call(&endpoint.api)).await;
+            endpoint
+                .metrics
+                .healthy
+                .set(i64::from(endpoint.health.healthy()));
+
+            match response {
+                Err(Error::Network(err)) => {
+                    tracing::warn!("eth endpoint failed `{method}`, trying the next one: {err}");
+                    last_error = Some(Error::Network(err));
+                }
+                response => return response,
+            }
+        }
+
+        Err(last_error.expect("provider pool to have at least one endpoint"))
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: EthApi + Send + Sync> EthApi for ProviderPool<T> {
+    async fn submit(&self, block: ValidatedFuelBlock, nonce: Option<u64>) -> Result<[u8; 32]> {
+        Self::first_success(self.writers(), "submit", |api| api.submit(block, nonce)).await
+    }
+
+    async fn get_block_number(&self) -> Result<u64> {
+        Self::first_success(self.readers(), "get_block_number", |api| {
+            api.get_block_number()
+        })
+        .await
+    }
+
+    async fn block_hash(&self, number: u64) -> Result<Option<[u8; 32]>> {
+        Self::first_success(self.readers(), "block_hash", |api| api.block_hash(number)).await
+    }
+
+    async fn chain_id(&self) -> Result<u64> {
+        Self::first_success(self.readers(), "chain_id", |api| api.chain_id()).await
+    }
+
+    async fn balance(&self, address: Address) -> Result<U256> {
+        Self::first_success(self.readers(), "balance", |api| api.balance(address)).await
+    }
+
+    fn commit_interval(&self) -> NonZeroU32 {
+        self.primary().api.commit_interval()
+    }
+
+    // the endpoint the streamer ends up on is picked anew each time the stream is re-established
+    fn event_streamer(&self, eth_block_height: u64) -> Option<EthEventStreamer> {
+        self.writers()
+            .into_iter()
+            .find_map(|endpoint| endpoint.api.event_streamer(eth_block_height))
+    }
+
+    async fn get_transaction_response(
+        &self,
+        tx_hash: [u8; 32],
+    ) -> Result<Option<TransactionResponse>> {
+        Self::first_success(self.readers(), "get_transaction_response", |api| {
+            api.get_transaction_response(tx_hash)
+        })
+        .await
+    }
+
+    async fn submit_l2_state(&self, state_data: Vec<u8>, nonce: Option<u64>) -> Result<[u8; 32]> {
+        Self::first_success(self.writers(), "submit_l2_state", |api| {
+            api.submit_l2_state(state_data.clone(), nonce)
+        })
+        .await
+    }
+
+    // asked of the writers as those are the ones that saw our transactions
+    async fn pending_nonce(&self, address: Address) -> Result<u64> {
+        Self::first_success(self.writers(), "pending_nonce", |api| {
+            api.pending_nonce(address)
+        })
+        .await
+    }
+
+    #[cfg(feature = "test-helpers")]
+    async fn finalized(&self, block: ValidatedFuelBlock) -> Result<bool> {
+        Self::first_success(self.readers(), "finalized", |api| api.finalized(block)).await
+    }
+
+    #[cfg(feature = "test-helpers")]
+    async fn block_hash_at_commit_height(&self, commit_height: u32) -> Result<[u8; 32]> {
+        Self::first_success(self.readers(), "block_hash_at_commit_height", |api| {
+            api.block_hash_at_commit_height(commit_height)
+        })
+        .await
+    }
+}
+
+// User responsible for registering any metrics T might have
+impl<T> RegistersMetrics for ProviderPool<T> {
+    fn metrics(&self) -> Vec<Box<dyn Collector>> {
+        self.endpoints
+            .iter()
+            .flat_map(|endpoint| {
+                let mut metrics = endpoint.api.metrics();
+                metrics.extend(endpoint.metrics.metrics());
+                metrics
+            })
+            .collect()
+    }
+}
+
+struct AnyEndpointHealthy<T>(Arc<[PooledEndpoint<T>]>);
+
+impl<T: Send + Sync> HealthCheck for AnyEndpointHealthy<T> {
+    fn healthy(&self) -> bool {
+        self.0.iter().any(|endpoint| endpoint.health.healthy())
+    }
+}
+
+#[derive(Clone)]
+struct EndpointMetrics {
+    latency: HistogramVec,
+    healthy: IntGauge,
+}
+
+impl EndpointMetrics {
+    fn new(const_labels: HashMap<String, String>) -> Self {
+        let latency = HistogramVec::new(
+            HistogramOpts::new(
+                "eth_rpc_duration_seconds",
+                "Time taken by calls to an Ethereum RPC endpoint.",
+            )
+            .const_labels(const_labels.clone()),
+            &["method"],
+        )
+        .expect("eth_rpc_duration_seconds metric to be correctly configured");
+
+        let healthy = IntGauge::with_opts(
+            Opts::new(
+                "eth_rpc_endpoint_healthy",
+                "Whether an Ethereum RPC endpoint is deemed healthy (1) or not (0).",
+            )
+            .const_labels(const_labels),
+        )
+        .expect("eth_rpc_endpoint_healthy metric to be correctly configured");
+        healthy.set(1);
+
+        Self { latency, healthy }
+    }
+
+    async fn time<R>(&self, method: &str, call: impl Future<Output = R>) -> R {
+        let timer = self.latency.with_label_values(&[method]).start_timer();
+        let response = call.await;
+        timer.observe_duration();
+
+        response
+    }
+}
+
+impl RegistersMetrics for EndpointMetrics {
+    fn metrics(&self) -> Vec<Box<dyn Collector>> {
+        vec![
+            Box::new(self.latency.clone()),
+            Box::new(self.healthy.clone()),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::metrics::prometheus::Registry;
+
+    use super::*;
+    use crate::rpc::health_tracking_middleware::MockEthApi;
+
+    #[tokio::test]
+    async fn writes_go_to_the_primary() {
+        // given
+        let mut primary = MockEthApi::new();
+        primary
+            .expect_submit()
+            .times(1)
+            .returning(|_, _| Ok([0; 32]));
+        let mut fallback = MockEthApi::new();
+        fallback.expect_submit().never();
+
+        let pool = given_a_pool(vec![primary, fallback], 1);
+
+        // when
+        let result = pool.submit(given_a_block(42), None).await;
+
+        // then
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn writes_fall_back_on_network_errors() {
+        // given
+        let mut primary = MockEthApi::new();
+        primary
+            .expect_submit()
+            .returning(|_, _| Err(Error::Network("An error".into())));
+        let mut fallback = MockEthApi::new();
+        fallback
+            .expect_submit()
+            .times(1)
+            .returning(|_, _| Ok([0; 32]));
+
+        let pool = given_a_pool(vec![primary, fallback], 1);
+
+        // when
+        let result = pool.submit(given_a_block(42), None).await;
+
+        // then
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn other_errors_are_not_retried_elsewhere() {
+        // given
+        let mut primary = MockEthApi::new();
+        primary
+            .expect_submit()
+            .returning(|_, _| Err(Error::Other("An error".into())));
+        let mut fallback = MockEthApi::new();
+        fallback.expect_submit().never();
+
+        let pool = given_a_pool(vec![primary, fallback], 1);
+
+        // when
+        let result = pool.submit(given_a_block(42), None).await;
+
+        // then
+        assert!(matches!(result, Err(Error::Other(_))));
+    }
+
+    #[tokio::test]
+    async fn reads_skip_unhealthy_endpoints() {
+        // given
+        let mut broken = MockEthApi::new();
+        broken
+            .expect_get_block_number()
+            .times(1)
+            .returning(|| Err(Error::Network("An error".into())));
+        let mut working = MockEthApi::new();
+        working.expect_get_block_number().returning(|| Ok(10));
+
+        let pool = given_a_pool(vec![broken, working], 1);
+
+        // when
+        let block_numbers = [
+            pool.get_block_number().await.unwrap(),
+            pool.get_block_number().await.unwrap(),
+            pool.get_block_number().await.unwrap(),
+        ];
+
+        // then
+        assert_eq!(block_numbers, [10, 10, 10]);
+    }
+
+    #[tokio::test]
+    async fn endpoints_marked_unhealthy_do_not_get_the_writes() {
+        // given
+        let mut primary = MockEthApi::new();
+        primary.expect_submit().never();
+        let mut fallback = MockEthApi::new();
+        fallback
+            .expect_submit()
+            .times(1)
+            .returning(|_, _| Ok([0; 32]));
+
+        let pool = given_a_pool(vec![primary, fallback], 3);
+
+        // when
+        pool.mark_unhealthy(0);
+        let result = pool.submit(given_a_block(42), None).await;
+
+        // then
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn unhealthy_primary_takes_back_the_writes_once_a_retried_read_succeeds() {
+        // given
+        let mut primary = MockEthApi::new();
+        primary
+            .expect_get_block_number()
+            .times(1)
+            .returning(|| Ok(10));
+        primary
+            .expect_submit()
+            .times(1)
+            .returning(|_, _| Ok([1; 32]));
+        let mut fallback = MockEthApi::new();
+        fallback.expect_get_block_number().never();
+        fallback.expect_submit().never();
+
+        let pool =
+            given_a_pool(vec![primary, fallback], 1).with_retry_unhealthy_after(Duration::ZERO);
+        pool.mark_unhealthy(0);
+
+        // when
+        pool.get_block_number().await.unwrap();
+        let tx_hash = pool.submit(given_a_block(42), None).await.unwrap();
+
+        // then
+        assert_eq!(tx_hash, [1; 32]);
+    }
+
+    #[tokio::test]
+    async fn unhealthy_endpoints_are_not_retried_before_they_are_due() {
+        // given
+        let mut unhealthy = MockEthApi::new();
+        unhealthy.expect_get_block_number().never();
+        let mut healthy = MockEthApi::new();
+        healthy.expect_get_block_number().returning(|| Ok(10));
+
+        let pool = given_a_pool(vec![unhealthy, healthy], 1)
+            .with_retry_unhealthy_after(Duration::from_secs(3600));
+        pool.mark_unhealthy(0);
+
+        // when
+        let block_numbers = [
+            pool.get_block_number().await.unwrap(),
+            pool.get_block_number().await.unwrap(),
+        ];
+
+        // then
+        assert_eq!(block_numbers, [10, 10]);
+    }
+
+    #[tokio::test]
+    async fn unhealthy_only_once_every_endpoint_is() {
+        // given
+        let mut first = MockEthApi::new();
+        first
+            .expect_get_block_number()
+            .returning(|| Err(Error::Network("An error".into())));
+        let mut second = MockEthApi::new();
+        second
+            .expect_get_block_number()
+            .returning(|| Err(Error::Network("An error".into())));
+
+        let pool = given_a_pool(vec![first, second], 2);
+        let health_check = pool.connection_health_checker();
+
+        // when
+        let _ = pool.get_block_number().await;
+        assert!(health_check.healthy());
+        let _ = pool.get_block_number().await;
+
+        // then
+        assert!(!health_check.healthy());
+    }
+
+    #[tokio::test]
+    async fn exports_metrics_per_endpoint() {
+        // given
+        let mut first = MockEthApi::new();
+        first
+            .expect_get_block_number()
+            .returning(|| Err(Error::Network("An error".into())));
+        let mut second = MockEthApi::new();
+        second.expect_get_block_number().returning(|| Ok(10));
+
+        let pool = given_a_pool(vec![first, second], 1);
+        let registry = Registry::new();
+        pool.register_metrics(&registry);
+
+        // when
+        pool.get_block_number().await.unwrap();
+
+        // then
+        let metrics = registry.gather();
+        let per_endpoint = |name: &str| {
+            metrics
+                .iter()
+                .find(|metric| metric.get_name() == name)
+                .map(|metric| metric.get_metric().len())
+                .unwrap()
+        };
+        assert_eq!(per_endpoint("eth_network_errors"), 2);
+        assert_eq!(per_endpoint("eth_rpc_endpoint_healthy"), 2);
+        assert_eq!(per_endpoint("eth_rpc_duration_seconds"), 2);
+    }
+
+    fn given_a_pool(
+        adapters: Vec<MockEthApi>,
+        unhealthy_after_n_errors: usize,
+    ) -> ProviderPool<MockEthApi> {
+        let endpoints = adapters
+            .into_iter()
+            .enumerate()
+            .map(|(i, adapter)| {
+                let url = Url::parse(&format!("ws://node{i}.example")).unwrap();
+                (url, adapter)
+            })
+            .collect();
+
+        ProviderPool::new(endpoints, unhealthy_after_n_errors)
+    }
+
+    fn given_a_block(block_height: u32) -> ValidatedFuelBlock {
+        ValidatedFuelBlock::new([0; 32], block_height)
+    }
+}
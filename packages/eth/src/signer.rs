@@ -0,0 +1,323 @@
+use std::{
+    env::VarError,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use alloy::{
+    consensus::SignableTransaction,
+    network::TxSigner,
+    primitives::{Address, Signature},
+    signers::{aws::AwsSigner, local::PrivateKeySigner},
+};
+use url::Url;
+
+pub use self::web3signer::Web3Signer;
+use crate::{
+    error::{Error, Result},
+    AwsClient,
+};
+
+mod web3signer;
+
+/// Where the key of a wallet comes from. Parsed from `Kms(<key id>)`, `Env(<variable>)`,
+/// `Keystore(<path>,<password variable>)` or `Web3Signer(<url>,<public key>)`. Anything else is
+/// taken to be a KMS key id.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeySource {
+    /// AWS KMS key id or arn.
+    Kms(String),
+    /// Environment variable holding a hex encoded private key.
+    Env(String),
+    /// Encrypted JSON keystore file and the environment variable holding its password.
+    Keystore { path: PathBuf, password_env: String },
+    /// Web3Signer compatible remote signing service and the public key to sign with.
+    Web3Signer { url: Url, public_key: String },
+}
+
+impl KeySource {
+    pub fn is_kms(&self) -> bool {
+        matches!(self, Self::Kms(_))
+    }
+}
+
+impl FromStr for KeySource {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let Some((kind, arg)) = s.trim().strip_suffix(')').and_then(|s| s.split_once('(')) else {
+            return Ok(Self::Kms(s.trim().to_string()));
+        };
+
+        let arg = arg.trim();
+        if arg.is_empty() {
+            return Err(format!("key source `{s}` is missing its argument"));
+        }
+
+        match kind.trim() {
+            "Kms" => Ok(Self::Kms(arg.to_string())),
+            "Env" => Ok(Self::Env(arg.to_string())),
+            "Keystore" => {
+                let (path, password_env) = arg.rsplit_once(',').ok_or_else(|| {
+                    format!("expected `Keystore(<path>,<password variable>)`, got `{s}`")
+                })?;
+                Ok(Self::Keystore {
+                    path: PathBuf::from(path.trim()),
+                    password_env: password_env.trim().to_string(),
+                })
+            }
+            "Web3Signer" => {
+                let (url, public_key) = arg.rsplit_once(',').ok_or_else(|| {
+                    format!("expected `Web3Signer(<url>,<public key>)`, got `{s}`")
+                })?;
+                Ok(Self::Web3Signer {
+                    url: Url::parse(url.trim())
+                        .map_err(|e| format!("invalid web3signer url `{url}`: {e}"))?,
+                    public_key: public_key.trim().to_string(),
+                })
+            }
+            other => Err(format!(
+                "unknown key source `{other}`, expected one of Kms, Env, Keystore or Web3Signer"
+            )),
+        }
+    }
+}
+
+/// Signs the transactions of a wallet with a key from any of the supported [`KeySource`]s.
+#[derive(Clone)]
+pub enum Signer {
+    Kms(AwsSigner),
+    Local(PrivateKeySigner),
+    Remote(Web3Signer),
+}
+
+impl Signer {
+    /// `aws_client` is only needed for [`KeySource::Kms`].
+    pub async fn from_key_source(
+        key: &KeySource,
+        aws_client: Option<&AwsClient>,
+    ) -> ports::l1::Result<Self> {
+        Self::resolve(key, aws_client, |variable| std::env::var(variable)).await
+    }
+
+    // env variables are read through `env` so that tests need not touch the process environment
+    async fn resolve(
+        key: &KeySource,
+        aws_client: Option<&AwsClient>,
+        env: impl Env,
+    ) -> ports::l1::Result<Self> {
+        let signer = match key {
+            KeySource::Kms(key_id) => {
+                let aws_client = aws_client.ok_or_else(|| {
+                    ports::l1::Error::Other("a KMS key needs an aws client".to_string())
+                })?;
+                Self::Kms(aws_client.make_signer(key_id.clone()).await?)
+            }
+            KeySource::Env(variable) => Self::Local(Self::private_key_from_env(&env, variable)?),
+            KeySource::Keystore { path, password_env } => {
+                Self::Local(Self::decrypt_keystore(path, &env, password_env)?)
+            }
+            KeySource::Web3Signer { url, public_key } => {
+                Self::Remote(Web3Signer::connect(url.clone(), public_key).await?)
+            }
+        };
+
+        Ok(signer)
+    }
+
+    fn private_key_from_env(env: &impl Env, variable: &str) -> Result<PrivateKeySigner> {
+        let key = read_env(env, variable)?;
+        PrivateKeySigner::from_str(key.trim()).map_err(|e| {
+            Error::Other(format!(
+                "env variable `{variable}` does not hold a valid private key: {e}"
+            ))
+        })
+    }
+
+    fn decrypt_keystore(
+        path: &Path,
+        env: &impl Env,
+        password_env: &str,
+    ) -> Result<PrivateKeySigner> {
+        let password = read_env(env, password_env)?;
+        PrivateKeySigner::decrypt_keystore(path, password).map_err(|e| {
+            Error::Other(format!(
+                "could not decrypt keystore `{}`: {e}",
+                path.display()
+            ))
+        })
+    }
+}
+
+// looks up env variables, `std::env::var` outside of tests
+trait Env: Fn(&str) -> std::result::Result<String, VarError> {}
+impl<F> Env for F where F: Fn(&str) -> std::result::Result<String, VarError> {}
+
+fn read_env(env: &impl Env, variable: &str) -> Result<String> {
+    env(variable)
+        .map_err(|e| Error::Other(format!("could not read env variable `{variable}`: {e}")))
+}
+
+impl From<AwsSigner> for Signer {
+    fn from(signer: AwsSigner) -> Self {
+        Self::Kms(signer)
+    }
+}
+
+impl From<PrivateKeySigner> for Signer {
+    fn from(signer: PrivateKeySigner) -> Self {
+        Self::Local(signer)
+    }
+}
+
+impl From<Web3Signer> for Signer {
+    fn from(signer: Web3Signer) -> Self {
+        Self::Remote(signer)
+    }
+}
+
+#[async_trait::async_trait]
+impl TxSigner<Signature> for Signer {
+    fn address(&self) -> Address {
+        match self {
+            Self::Kms(signer) => TxSigner::address(signer),
+            Self::Local(signer) => TxSigner::address(signer),
+            Self::Remote(signer) => signer.address(),
+        }
+    }
+
+    async fn sign_transaction(
+        &self,
+        tx: &mut dyn SignableTransaction<Signature>,
+    ) -> alloy::signers::Result<Signature> {
+        match self {
+            Self::Kms(signer) => signer.sign_transaction(tx).await,
+            Self::Local(signer) => signer.sign_transaction(tx).await,
+            Self::Remote(signer) => signer
+                .sign_transaction(tx)
+                .await
+                .map_err(alloy::signers::Error::other),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::address;
+
+    use super::*;
+
+    // first of the well known anvil/hardhat development accounts
+    const PRIVATE_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const ADDRESS: Address = address!("f39Fd6e51aad88F6F4ce6aB8827279cffFb92266");
+
+    #[test]
+    fn key_sources_are_parsed() {
+        let parse = |s: &str| s.parse::<KeySource>();
+
+        assert_eq!(
+            parse("Kms(some-key)"),
+            Ok(KeySource::Kms("some-key".to_string()))
+        );
+        assert_eq!(
+            parse("arn:aws:kms:key"),
+            Ok(KeySource::Kms("arn:aws:kms:key".to_string()))
+        );
+        assert_eq!(
+            parse("Env(MAIN_KEY)"),
+            Ok(KeySource::Env("MAIN_KEY".to_string()))
+        );
+        assert_eq!(
+            parse("Keystore(/keys/main.json, MAIN_PASSWORD)"),
+            Ok(KeySource::Keystore {
+                path: PathBuf::from("/keys/main.json"),
+                password_env: "MAIN_PASSWORD".to_string(),
+            })
+        );
+        assert_eq!(
+            parse("Web3Signer(http://signer:9000, 0x1234)"),
+            Ok(KeySource::Web3Signer {
+                url: Url::parse("http://signer:9000").unwrap(),
+                public_key: "0x1234".to_string(),
+            })
+        );
+        assert!(parse("Keystore(/keys/main.json)").is_err());
+        assert!(parse("Web3Signer(not a url, 0x1234)").is_err());
+        assert!(parse("Env()").is_err());
+        assert!(parse("Ledger(0)").is_err());
+    }
+
+    #[tokio::test]
+    async fn signer_reads_private_key_from_env() {
+        // given
+        let env = given_env("MAIN_KEY", &format!("0x{PRIVATE_KEY}"));
+        let key = KeySource::Env("MAIN_KEY".to_string());
+
+        // when
+        let signer = Signer::resolve(&key, None, env).await.unwrap();
+
+        // then
+        assert_eq!(TxSigner::address(&signer), ADDRESS);
+    }
+
+    #[tokio::test]
+    async fn signer_decrypts_keystore() {
+        // given
+        let dir = tempfile::tempdir().unwrap();
+        PrivateKeySigner::encrypt_keystore(
+            dir.path(),
+            &mut rand::thread_rng(),
+            alloy::hex::decode(PRIVATE_KEY).unwrap(),
+            "secret",
+            Some("main.json"),
+        )
+        .unwrap();
+        let env = given_env("MAIN_PASSWORD", "secret");
+        let key = KeySource::Keystore {
+            path: dir.path().join("main.json"),
+            password_env: "MAIN_PASSWORD".to_string(),
+        };
+
+        // when
+        let signer = Signer::resolve(&key, None, env).await.unwrap();
+
+        // then
+        assert_eq!(TxSigner::address(&signer), ADDRESS);
+    }
+
+    #[tokio::test]
+    async fn missing_env_variable_is_reported() {
+        // given
+        let env = given_env("OTHER_KEY", PRIVATE_KEY);
+        let key = KeySource::Env("MAIN_KEY".to_string());
+
+        // when
+        let result = Signer::resolve(&key, None, env).await;
+
+        // then
+        let Err(err) = result else {
+            panic!("expected an error");
+        };
+        assert!(err.to_string().contains("`MAIN_KEY`"));
+    }
+
+    #[tokio::test]
+    async fn kms_key_needs_aws_client() {
+        let key = KeySource::Kms("some-key".to_string());
+
+        let result = Signer::from_key_source(&key, None).await;
+
+        assert!(result.is_err());
+    }
+
+    fn given_env(variable: &'static str, value: &str) -> impl Env {
+        let value = value.to_string();
+        move |requested| {
+            if requested == variable {
+                Ok(value.clone())
+            } else {
+                Err(VarError::NotPresent)
+            }
+        }
+    }
+}
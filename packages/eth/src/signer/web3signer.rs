@@ -0,0 +1,268 @@
+use std::{str::FromStr, time::Duration};
+
+use alloy::{
+    consensus::SignableTransaction,
+    hex,
+    primitives::{keccak256, Address, Signature},
+};
+use url::Url;
+
+use crate::error::{Error, Result};
+
+/// Signs through the eth1 endpoints of a Web3Signer compatible service, so the key never leaves
+/// it.
+#[derive(Debug, Clone)]
+pub struct Web3Signer {
+    client: reqwest::Client,
+    sign_url: Url,
+    address: Address,
+}
+
+impl Web3Signer {
+    // a signer that stopped responding would otherwise hold up the tx for good
+    const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// Fails unless the service at `url` holds the key with the hex encoded, uncompressed
+    /// `public_key`.
+    pub async fn connect(mut url: Url, public_key: &str) -> Result<Self> {
+        let public_key = Self::normalize_public_key(public_key)?;
+        let address = Address::from_raw_public_key(&hex::decode(&public_key)?);
+
+        // `Url::join` would otherwise replace the last path segment of the base
+        if !url.path().ends_with('/') {
+            url.set_path(&format!("{}/", url.path()));
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(Self::REQUEST_TIMEOUT)
+            .build()?;
+        let available_keys: Vec<String> = client
+            .get(url.join("api/v1/eth1/publicKeys")?)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        if !available_keys
+            .iter()
+            .any(|key| key.eq_ignore_ascii_case(&public_key))
+        {
+            return Err(Error::Other(format!(
+                "web3signer at `{}` does not hold the key {public_key}",
+                url.host_str().unwrap_or_default()
+            )));
+        }
+
+        Ok(Self {
+            sign_url: url.join(&format!("api/v1/eth1/sign/{public_key}"))?,
+            client,
+            address,
+        })
+    }
+
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    pub async fn sign_transaction(
+        &self,
+        tx: &mut dyn SignableTransaction<Signature>,
+    ) -> Result<Signature> {
+        let signature = self.sign(&tx.encoded_for_signing()).await?;
+
+        match tx.chain_id() {
+            Some(chain_id) if tx.use_eip155() => Ok(signature.with_chain_id(chain_id)),
+            _ => Ok(signature),
+        }
+    }
+
+    // the service signs the keccak256 hash of `data`
+    async fn sign(&self, data: &[u8]) -> Result<Signature> {
+        let response = self
+            .client
+            .post(self.sign_url.clone())
+            .json(&serde_json::json!({ "data": hex::encode_prefixed(data) }))
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        let signature = Signature::from_str(response.trim())
+            .map_err(|e| Error::Other(format!("web3signer returned an invalid signature: {e}")))?;
+
+        let signed_by = signature
+            .recover_address_from_prehash(&keccak256(data))
+            .map_err(|e| Error::Other(e.to_string()))?;
+        if signed_by != self.address {
+            return Err(Error::Other(format!(
+                "web3signer signed with {signed_by} instead of {}",
+                self.address
+            )));
+        }
+
+        Ok(signature)
+    }
+
+    // 0x prefixed, 64 bytes without the leading 0x04 of the SEC1 encoding
+    fn normalize_public_key(public_key: &str) -> Result<String> {
+        let bytes = hex::decode(public_key.trim())?;
+        let raw = match bytes.as_slice() {
+            [0x04, raw @ ..] if raw.len() == 64 => raw,
+            raw if raw.len() == 64 => raw,
+            _ => {
+                return Err(Error::Other(format!(
+                    "`{public_key}` is not an uncompressed secp256k1 public key"
+                )))
+            }
+        };
+
+        Ok(hex::encode_prefixed(raw))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        consensus::TxEip1559,
+        primitives::TxKind,
+        signers::{local::PrivateKeySigner, SignerSync},
+    };
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn signs_transactions_through_the_service() {
+        // given
+        let key = PrivateKeySigner::random();
+        let url = given_web3signer(key.clone()).await;
+        let signer = Web3Signer::connect(url, &public_key_of(&key))
+            .await
+            .unwrap();
+
+        let mut tx = TxEip1559 {
+            chain_id: 1,
+            nonce: 3,
+            to: TxKind::Call(Address::ZERO),
+            ..Default::default()
+        };
+
+        // when
+        let signature = signer.sign_transaction(&mut tx).await.unwrap();
+
+        // then
+        assert_eq!(signer.address(), key.address());
+        let signed_by = signature
+            .recover_address_from_prehash(&tx.signature_hash())
+            .unwrap();
+        assert_eq!(signed_by, key.address());
+    }
+
+    #[tokio::test]
+    async fn refuses_keys_the_service_does_not_hold() {
+        // given
+        let url = given_web3signer(PrivateKeySigner::random()).await;
+        let other_key = PrivateKeySigner::random();
+
+        // when
+        let result = Web3Signer::connect(url, &public_key_of(&other_key)).await;
+
+        // then
+        let Err(Error::Other(msg)) = result else {
+            panic!("expected the key to be refused");
+        };
+        assert!(msg.contains("does not hold the key"));
+    }
+
+    #[test]
+    fn public_keys_are_accepted_with_or_without_sec1_prefix() {
+        let key = PrivateKeySigner::random();
+        let with_prefix = public_key_of(&key);
+        let without_prefix = format!("0x{}", &with_prefix[4..]);
+
+        let normalized = Web3Signer::normalize_public_key(&with_prefix).unwrap();
+
+        assert_eq!(normalized, without_prefix);
+        assert_eq!(
+            Web3Signer::normalize_public_key(&without_prefix).unwrap(),
+            without_prefix
+        );
+        assert!(Web3Signer::normalize_public_key("0x04").is_err());
+    }
+
+    fn public_key_of(key: &PrivateKeySigner) -> String {
+        let point = key.credential().verifying_key().to_encoded_point(false);
+        hex::encode_prefixed(point.as_bytes())
+    }
+
+    // serves the two web3signer endpoints used, signing with `key`
+    async fn given_web3signer(key: PrivateKeySigner) -> Url {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                tokio::spawn(respond(stream, key.clone()));
+            }
+        });
+
+        url
+    }
+
+    async fn respond(mut stream: TcpStream, key: PrivateKeySigner) {
+        let (head, body) = read_request(&mut stream).await;
+        let public_key = public_key_of(&key);
+
+        let (status, response) = if head.starts_with("GET /api/v1/eth1/publicKeys ") {
+            let keys = serde_json::json!([format!("0x{}", &public_key[4..])]);
+            ("200 OK", keys.to_string())
+        } else if head.starts_with(&format!("POST /api/v1/eth1/sign/0x{} ", &public_key[4..])) {
+            let request: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            let data = hex::decode(request["data"].as_str().unwrap()).unwrap();
+            let signature = key.sign_hash_sync(&keccak256(data)).unwrap();
+            ("200 OK", hex::encode_prefixed(signature.as_bytes()))
+        } else {
+            ("404 Not Found", String::new())
+        };
+
+        let response = format!(
+            "HTTP/1.1 {status}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{response}",
+            response.len()
+        );
+        stream.write_all(response.as_bytes()).await.unwrap();
+    }
+
+    async fn read_request(stream: &mut TcpStream) -> (String, Vec<u8>) {
+        let mut request = vec![];
+        let mut buf = [0; 1024];
+        loop {
+            let read = stream.read(&mut buf).await.unwrap();
+            assert_ne!(read, 0, "connection closed before the request was complete");
+            request.extend_from_slice(&buf[..read]);
+
+            let Some(head_end) = request.windows(4).position(|w| w == b"\r\n\r\n") else {
+                continue;
+            };
+            let head = String::from_utf8_lossy(&request[..head_end]).to_string();
+            let content_length = head
+                .lines()
+                .find_map(|line| {
+                    let (name, value) = line.split_once(':')?;
+                    name.eq_ignore_ascii_case("content-length")
+                        .then(|| value.trim().parse::<usize>().unwrap())
+                })
+                .unwrap_or_default();
+
+            let body_start = head_end + 4;
+            if request.len() >= body_start + content_length {
+                return (head, request[body_start..].to_vec());
+            }
+        }
+    }
+}
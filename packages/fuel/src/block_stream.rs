@@ -0,0 +1,39 @@
+use std::time::Duration;
+
+use futures::{stream, Stream};
+use ports::fuel::{Api, FuelBlock};
+
+use crate::Result;
+
+/// Used by the block stream unless configured otherwise.
+pub const DEFAULT_BLOCK_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// The GraphQL schema of fuel-core 0.31 has subscriptions for transaction status only, so the
+// stream polls for the latest block. Blocks produced while the consumer was busy aren't delivered
+// one by one, only the newest of them is. Since the next height is only advanced once a block is
+// delivered, a dropped connection resumes right after the last delivered block.
+pub(crate) fn follow_chain<A: Api + ?Sized>(
+    api: &A,
+    from_height: u32,
+    poll_interval: Duration,
+) -> impl Stream<Item = Result<FuelBlock>> + Send + '_ {
+    stream::unfold(
+        (from_height, false),
+        move |(next_height, retrying)| async move {
+            if retrying {
+                tokio::time::sleep(poll_interval).await;
+            }
+
+            loop {
+                match api.latest_block().await {
+                    Ok(block) if block.header.height >= next_height => {
+                        let after = block.header.height.saturating_add(1);
+                        return Some((Ok(block), (after, false)));
+                    }
+                    Ok(_) => tokio::time::sleep(poll_interval).await,
+                    Err(err) => return Some((Err(err), (next_height, true))),
+                }
+            }
+        },
+    )
+}
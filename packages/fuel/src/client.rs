@@ -1,36 +1,61 @@
+use std::time::Duration;
+
 #[cfg(feature = "test-helpers")]
 use fuel_core_client::client::types::{
     primitives::{Address, AssetId},
     Coin, CoinType,
 };
-use fuel_core_client::client::{types::Block, FuelClient as GqlClient};
+use fuel_core_client::client::{
+    types::{primitives::TransactionId, Block},
+    FuelClient as GqlClient,
+};
 #[cfg(feature = "test-helpers")]
 use fuel_core_types::fuel_tx::Transaction;
+use fuel_core_types::fuel_types::canonical::Serialize;
+use futures::{stream, Stream, StreamExt, TryStreamExt};
 use metrics::{
     prometheus::core::Collector, ConnectionHealthTracker, HealthChecker, RegistersMetrics,
 };
 use url::Url;
 
-use crate::{metrics::Metrics, Error, Result};
+use crate::{block_stream, metrics::Metrics, Error, Result};
 
 #[derive(Clone)]
 pub struct HttpClient {
     client: GqlClient,
     metrics: Metrics,
     health_tracker: ConnectionHealthTracker,
+    block_poll_interval: Duration,
 }
 
 impl HttpClient {
+    const MAX_CONCURRENT_TX_REQUESTS: usize = 16;
+
     #[must_use]
     pub fn new(url: &Url, unhealthy_after_n_errors: usize) -> Self {
+        Self::for_endpoint(0, url, unhealthy_after_n_errors)
+    }
+
+    /// `position` tells apart the metrics of clients connected to the same host.
+    #[must_use]
+    pub fn for_endpoint(position: usize, url: &Url, unhealthy_after_n_errors: usize) -> Self {
         let client = GqlClient::new(url).expect("Url to be well formed");
         Self {
             client,
-            metrics: Metrics::default(),
+            metrics: Metrics::for_endpoint(position, url),
             health_tracker: ConnectionHealthTracker::new(unhealthy_after_n_errors),
+            block_poll_interval: block_stream::DEFAULT_BLOCK_POLL_INTERVAL,
         }
     }
 
+    /// How long the block stream waits before asking for the next block once it has caught up
+    /// with the chain tip, and before retrying after an error.
+    #[must_use]
+    pub fn with_block_poll_interval(mut self, block_poll_interval: Duration) -> Self {
+        self.block_poll_interval = block_poll_interval;
+        self
+    }
+
     #[cfg(feature = "test-helpers")]
     pub async fn produce_blocks(&self, num: u32) -> Result<()> {
         self.client
@@ -85,7 +110,7 @@ impl HttpClient {
         match self.client.block_by_height(height.into()).await {
             Ok(maybe_block) => {
                 self.handle_network_success();
-                Ok(maybe_block.map(Into::into))
+                Ok(maybe_block)
             }
             Err(err) => {
                 self.handle_network_error();
@@ -107,6 +132,45 @@ impl HttpClient {
         }
     }
 
+    // The GraphQL schema of fuel-core 0.31 can't return a block along with its transactions, so
+    // they are fetched by id, a bounded number at a time.
+    pub(crate) async fn _block_transactions(&self, block: &Block) -> Result<Vec<Vec<u8>>> {
+        stream::iter(block.transactions.iter().copied())
+            .map(|tx_id| self.block_transaction(block, tx_id))
+            .buffered(Self::MAX_CONCURRENT_TX_REQUESTS)
+            .try_collect()
+            .await
+    }
+
+    async fn block_transaction(&self, block: &Block, tx_id: TransactionId) -> Result<Vec<u8>> {
+        let response = match self.client.transaction(&tx_id).await {
+            Ok(response) => {
+                self.handle_network_success();
+                response
+            }
+            Err(err) => {
+                self.handle_network_error();
+                return Err(Error::Network(err.to_string()));
+            }
+        };
+
+        let Some(response) = response else {
+            return Err(Error::Other(format!(
+                "Fuel node could not provide transaction `{tx_id:x}` of block `{:x}`",
+                block.id
+            )));
+        };
+
+        Ok(response.transaction.to_bytes())
+    }
+
+    pub(crate) fn _block_stream(
+        &self,
+        from_height: u32,
+    ) -> impl Stream<Item = Result<Block>> + Send + '_ {
+        block_stream::follow_chain(self, from_height, self.block_poll_interval)
+    }
+
     #[must_use]
     pub fn connection_health_checker(&self) -> HealthChecker {
         self.health_tracker.tracker()
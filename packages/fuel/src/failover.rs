@@ -0,0 +1,602 @@
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    num::NonZeroUsize,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use futures::future::join_all;
+use metrics::{prometheus::core::Collector, HealthCheck, HealthChecker, RegistersMetrics};
+use ports::{
+    fuel::{Api, FuelBlock},
+    types::Stream,
+};
+use url::Url;
+
+use crate::{block_stream, Error, HttpClient, Result};
+
+struct Endpoint<A> {
+    api: A,
+    health: HealthChecker,
+    // highest block height the endpoint has reported, used to prefer up-to-date nodes
+    known_height: AtomicU32,
+    // set while the endpoint reports a block the agreeing endpoints don't
+    dissenting: AtomicBool,
+}
+
+impl<A> Endpoint<A> {
+    fn note_height(&self, height: u32) {
+        self.known_height.fetch_max(height, Ordering::Relaxed);
+    }
+
+    fn note_dissent(&self, dissenting: bool) {
+        self.dissenting.store(dissenting, Ordering::Relaxed);
+    }
+
+    fn healthy(&self) -> bool {
+        self.health.healthy() && !self.dissenting.load(Ordering::Relaxed)
+    }
+}
+
+/// Spreads requests over several Fuel nodes. Requests go to the most up-to-date healthy node and
+/// fail over to the next one on error. With `min_agreeing_endpoints` above one, every node is
+/// asked and a block is only handed out once that many nodes report the same block id for its
+/// height. Nodes reporting another block are then treated as unhealthy until they agree again.
+pub struct FailoverClient<A = HttpClient> {
+    endpoints: Arc<[Endpoint<A>]>,
+    min_agreeing_endpoints: usize,
+    block_poll_interval: Duration,
+}
+
+impl<A> Clone for FailoverClient<A> {
+    fn clone(&self) -> Self {
+        Self {
+            endpoints: Arc::clone(&self.endpoints),
+            min_agreeing_endpoints: self.min_agreeing_endpoints,
+            block_poll_interval: self.block_poll_interval,
+        }
+    }
+}
+
+impl FailoverClient<HttpClient> {
+    #[must_use]
+    pub fn connect(
+        urls: &[Url],
+        unhealthy_after_n_errors: usize,
+        min_agreeing_endpoints: NonZeroUsize,
+    ) -> Self {
+        let endpoints = urls
+            .iter()
+            .enumerate()
+            .map(|(position, url)| {
+                let client = HttpClient::for_endpoint(position, url, unhealthy_after_n_errors);
+                let health = client.connection_health_checker();
+                (client, health)
+            })
+            .collect();
+
+        Self::new(endpoints, min_agreeing_endpoints)
+    }
+}
+
+impl<A> FailoverClient<A> {
+    /// # Panics
+    /// If fewer endpoints are given than are required to agree on a block.
+    #[must_use]
+    pub fn new(endpoints: Vec<(A, HealthChecker)>, min_agreeing_endpoints: NonZeroUsize) -> Self {
+        assert!(
+            endpoints.len() >= min_agreeing_endpoints.get(),
+            "{} fuel endpoint(s) cannot satisfy an agreement of {min_agreeing_endpoints}",
+            endpoints.len()
+        );
+
+        let endpoints = endpoints
+            .into_iter()
+            .map(|(api, health)| Endpoint {
+                api,
+                health,
+                known_height: AtomicU32::new(0),
+                dissenting: AtomicBool::new(false),
+            })
+            .collect();
+
+        Self {
+            endpoints,
+            min_agreeing_endpoints: min_agreeing_endpoints.get(),
+            block_poll_interval: block_stream::DEFAULT_BLOCK_POLL_INTERVAL,
+        }
+    }
+
+    /// How long the block stream waits before asking for the next block once it has caught up
+    /// with the chain tip, and before retrying after an error.
+    #[must_use]
+    pub fn with_block_poll_interval(mut self, block_poll_interval: Duration) -> Self {
+        self.block_poll_interval = block_poll_interval;
+        self
+    }
+
+    /// Healthy for as long as at least one endpoint is.
+    #[must_use]
+    pub fn connection_health_checker(&self) -> HealthChecker
+    where
+        A: Send + Sync + 'static,
+    {
+        Box::new(AnyEndpointHealthy(Arc::clone(&self.endpoints)))
+    }
+
+    // healthy endpoints first, each group ordered by the height the endpoint last reported
+    fn by_preference(&self) -> Vec<&Endpoint<A>> {
+        let mut endpoints: Vec<_> = self.endpoints.iter().collect();
+        endpoints.sort_by_key(|endpoint| {
+            (
+                Reverse(endpoint.healthy()),
+                Reverse(endpoint.known_height.load(Ordering::Relaxed)),
+            )
+        });
+
+        endpoints
+    }
+}
+
+impl<A: Api> FailoverClient<A> {
+    async fn agreed_block_at_height(&self, height: u32) -> Result<Option<FuelBlock>> {
+        let responses = join_all(self.endpoints.iter().map(|endpoint| async move {
+            let response = endpoint.api.block_at_height(height).await;
+            if let Ok(Some(_)) = &response {
+                endpoint.note_height(height);
+            }
+            response
+        }))
+        .await;
+
+        let mut votes: HashMap<_, Vec<_>> = HashMap::new();
+        let mut last_error = None;
+        for (endpoint, response) in self.endpoints.iter().zip(responses) {
+            match response {
+                Ok(Some(block)) => votes.entry(block.id).or_default().push((endpoint, block)),
+                Ok(None) => {}
+                Err(err) => last_error = Some(err),
+            }
+        }
+
+        let num_block_ids = votes.len();
+        let mut quorums = votes
+            .values()
+            .filter(|voters| voters.len() >= self.min_agreeing_endpoints);
+        let agreed_id = match (quorums.next(), quorums.next()) {
+            (Some(voters), None) => voters[0].1.id,
+            // not enough endpoints have the block yet, unless it is errors holding them back
+            (None, _) if num_block_ids <= 1 => return last_error.map_or(Ok(None), Err),
+            _ => {
+                return Err(Error::Other(format!(
+                    "fuel endpoints disagree on the block at height {height}: {num_block_ids} \
+                     different block ids reported"
+                )))
+            }
+        };
+
+        let mut agreed_block = None;
+        for (id, voters) in votes {
+            let agrees = id == agreed_id;
+            for (endpoint, block) in voters {
+                endpoint.note_dissent(!agrees);
+                if agrees {
+                    agreed_block = Some(block);
+                }
+            }
+        }
+
+        Ok(agreed_block)
+    }
+}
+
+#[async_trait::async_trait]
+impl<A: Api> Api for FailoverClient<A> {
+    async fn block_at_height(&self, height: u32) -> Result<Option<FuelBlock>> {
+        if self.min_agreeing_endpoints > 1 {
+            return self.agreed_block_at_height(height).await;
+        }
+
+        let mut last_error = None;
+        let mut responded = false;
+        for endpoint in self.by_preference() {
+            match endpoint.api.block_at_height(height).await {
+                Ok(Some(block)) => {
+                    endpoint.note_height(height);
+                    return Ok(Some(block));
+                }
+                // the endpoint might be lagging behind, ask the others
+                Ok(None) => responded = true,
+                Err(err) => last_error = Some(err),
+            }
+        }
+
+        match last_error {
+            Some(err) if !responded => Err(err),
+            _ => Ok(None),
+        }
+    }
+
+    async fn latest_block(&self) -> Result<FuelBlock> {
+        if self.min_agreeing_endpoints == 1 {
+            let mut last_error = None;
+            for endpoint in self.by_preference() {
+                match endpoint.api.latest_block().await {
+                    Ok(block) => {
+                        endpoint.note_height(block.header.height);
+                        return Ok(block);
+                    }
+                    Err(err) => last_error = Some(err),
+                }
+            }
+
+            return Err(last_error.expect("at least one fuel endpoint to be configured"));
+        }
+
+        let responses = join_all(self.endpoints.iter().map(|endpoint| async move {
+            let response = endpoint.api.latest_block().await;
+            if let Ok(block) = &response {
+                endpoint.note_height(block.header.height);
+            }
+            response
+        }))
+        .await;
+
+        let mut latest_blocks = vec![];
+        let mut last_error = None;
+        for response in responses {
+            match response {
+                Ok(block) => latest_blocks.push(block),
+                Err(err) => last_error = Some(err),
+            }
+        }
+        latest_blocks.sort_by_key(|block| Reverse(block.header.height));
+
+        // the highest height reached by enough endpoints to be agreed upon
+        let Some(height) = latest_blocks
+            .get(self.min_agreeing_endpoints - 1)
+            .map(|block| block.header.height)
+        else {
+            return Err(match last_error {
+                Some(err) if latest_blocks.is_empty() => err,
+                _ => Error::Other(format!(
+                    "only {} fuel endpoint(s) reported their latest block, {} required to agree",
+                    latest_blocks.len(),
+                    self.min_agreeing_endpoints
+                )),
+            });
+        };
+
+        self.agreed_block_at_height(height).await?.ok_or_else(|| {
+            Error::Other(format!(
+                "fuel endpoints stopped providing the block at height {height}"
+            ))
+        })
+    }
+
+    async fn block_transactions(&self, block: &FuelBlock) -> Result<Vec<Vec<u8>>> {
+        let mut last_error = None;
+        for endpoint in self.by_preference() {
+            match endpoint.api.block_transactions(block).await {
+                Ok(transactions) => return Ok(transactions),
+                Err(err) => last_error = Some(err),
+            }
+        }
+
+        Err(last_error.expect("at least one fuel endpoint to be configured"))
+    }
+
+    fn block_stream<'a>(
+        &'a self,
+        from_height: u32,
+    ) -> Pin<Box<dyn Stream<Item = Result<FuelBlock>> + Send + 'a>> {
+        Box::pin(block_stream::follow_chain(
+            self,
+            from_height,
+            self.block_poll_interval,
+        ))
+    }
+}
+
+impl<A: RegistersMetrics> RegistersMetrics for FailoverClient<A> {
+    fn metrics(&self) -> Vec<Box<dyn Collector>> {
+        self.endpoints
+            .iter()
+            .flat_map(|endpoint| endpoint.api.metrics())
+            .collect()
+    }
+}
+
+struct AnyEndpointHealthy<A>(Arc<[Endpoint<A>]>);
+
+impl<A: Send + Sync> HealthCheck for AnyEndpointHealthy<A> {
+    fn healthy(&self) -> bool {
+        self.0.iter().any(Endpoint::healthy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fuel_core_types::tai64::Tai64;
+    use futures::StreamExt;
+    use metrics::ConnectionHealthTracker;
+    use ports::fuel::{FuelBlockId, FuelConsensus, FuelHeader, MockApi};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn latest_block_is_only_asked_of_one_endpoint_without_agreement() {
+        // given
+        let preferred = given_endpoint_at(given_a_block(5, 1));
+        let mut other = MockApi::new();
+        other.expect_latest_block().never();
+        let client = FailoverClient::new(vec![preferred, (other, healthy())], NonZeroUsize::MIN);
+
+        // when
+        let latest_block = client.latest_block().await.unwrap();
+
+        // then
+        assert_eq!(latest_block.header.height, 5);
+    }
+
+    #[tokio::test]
+    async fn latest_block_fails_over_to_the_next_endpoint_on_error() {
+        // given
+        let mut failing = MockApi::new();
+        failing
+            .expect_latest_block()
+            .returning(|| Err(Error::Network("down".to_string())));
+        let working = given_endpoint_at(given_a_block(7, 1));
+        let client = FailoverClient::new(vec![(failing, healthy()), working], NonZeroUsize::MIN);
+
+        // when
+        let latest_block = client.latest_block().await.unwrap();
+
+        // then
+        assert_eq!(latest_block.header.height, 7);
+    }
+
+    #[tokio::test]
+    async fn fails_over_to_the_next_endpoint_on_error() {
+        // given
+        let block = given_a_block(3, 1);
+        let mut failing = MockApi::new();
+        failing
+            .expect_block_at_height()
+            .returning(|_| Err(Error::Network("down".to_string())));
+        let mut working = MockApi::new();
+        let response = block.clone();
+        working
+            .expect_block_at_height()
+            .returning(move |_| Ok(Some(response.clone())));
+
+        let client = FailoverClient::new(
+            vec![(failing, healthy()), (working, healthy())],
+            NonZeroUsize::MIN,
+        );
+
+        // when
+        let fetched = client.block_at_height(3).await.unwrap();
+
+        // then
+        assert_eq!(fetched, Some(block));
+    }
+
+    #[tokio::test]
+    async fn unhealthy_endpoints_are_asked_last() {
+        // given
+        let block = given_a_block(3, 1);
+        let mut unhealthy_api = MockApi::new();
+        unhealthy_api.expect_block_at_height().never();
+        let mut healthy_api = MockApi::new();
+        let response = block.clone();
+        healthy_api
+            .expect_block_at_height()
+            .returning(move |_| Ok(Some(response.clone())));
+
+        let client = FailoverClient::new(
+            vec![(unhealthy_api, unhealthy()), (healthy_api, healthy())],
+            NonZeroUsize::MIN,
+        );
+
+        // when
+        let fetched = client.block_at_height(3).await.unwrap();
+
+        // then
+        assert_eq!(fetched, Some(block));
+    }
+
+    #[tokio::test]
+    async fn latest_block_is_the_highest_one_enough_endpoints_agree_on() {
+        // given
+        let agreed_block = given_a_block(5, 1);
+        let endpoints = vec![
+            given_endpoint(given_a_block(7, 1), vec![agreed_block.clone()]),
+            given_endpoint(given_a_block(6, 1), vec![agreed_block.clone()]),
+            given_endpoint(agreed_block.clone(), vec![agreed_block.clone()]),
+        ];
+        let client = FailoverClient::new(endpoints, NonZeroUsize::new(3).unwrap());
+
+        // when
+        let latest_block = client.latest_block().await.unwrap();
+
+        // then
+        assert_eq!(latest_block, agreed_block);
+    }
+
+    #[tokio::test]
+    async fn errors_if_endpoints_disagree_on_a_block() {
+        // given
+        let endpoints = vec![
+            given_endpoint_at(given_a_block(5, 1)),
+            given_endpoint_at(given_a_block(5, 2)),
+        ];
+        let client = FailoverClient::new(endpoints, NonZeroUsize::new(2).unwrap());
+
+        // when
+        let result = client.block_at_height(5).await;
+
+        // then
+        let Err(Error::Other(msg)) = result else {
+            panic!("expected endpoints to disagree, got: {result:?}");
+        };
+        assert!(msg.contains("disagree"));
+    }
+
+    #[tokio::test]
+    async fn agreeing_endpoints_outvote_a_dissenting_one() {
+        // given
+        let agreed_block = given_a_block(5, 1);
+        let endpoints = vec![
+            given_endpoint_at(given_a_block(5, 2)),
+            given_endpoint_at(agreed_block.clone()),
+            given_endpoint_at(agreed_block.clone()),
+        ];
+        let client = FailoverClient::new(endpoints, NonZeroUsize::new(2).unwrap());
+
+        // when
+        let fetched = client.block_at_height(5).await.unwrap();
+
+        // then
+        assert_eq!(fetched, Some(agreed_block));
+    }
+
+    #[tokio::test]
+    async fn dissenting_endpoint_is_asked_last() {
+        // given
+        let agreed_block = given_a_block(5, 1);
+        let (mut dissenting, health) = given_endpoint_at(given_a_block(5, 2));
+        dissenting.expect_block_transactions().never();
+        let (mut agreeing, agreeing_health) = given_endpoint_at(agreed_block.clone());
+        agreeing
+            .expect_block_transactions()
+            .returning(|_| Ok(vec![vec![1]]));
+        let endpoints = vec![
+            (dissenting, health),
+            (agreeing, agreeing_health),
+            given_endpoint_at(agreed_block.clone()),
+        ];
+        let client = FailoverClient::new(endpoints, NonZeroUsize::new(2).unwrap());
+        client.block_at_height(5).await.unwrap();
+
+        // when
+        let transactions = client.block_transactions(&agreed_block).await.unwrap();
+
+        // then
+        assert_eq!(transactions, vec![vec![1]]);
+    }
+
+    #[tokio::test]
+    async fn block_is_not_available_until_enough_endpoints_have_it() {
+        // given
+        let block = given_a_block(5, 1);
+        let endpoints = vec![
+            given_endpoint(block.clone(), vec![block.clone()]),
+            given_endpoint(given_a_block(4, 1), vec![]),
+        ];
+        let client = FailoverClient::new(endpoints, NonZeroUsize::new(2).unwrap());
+
+        // when
+        let fetched = client.block_at_height(5).await.unwrap();
+
+        // then
+        assert!(fetched.is_none());
+    }
+
+    #[tokio::test]
+    async fn healthy_while_any_endpoint_is() {
+        // given
+        let client = FailoverClient::new(
+            vec![(MockApi::new(), unhealthy()), (MockApi::new(), healthy())],
+            NonZeroUsize::MIN,
+        );
+
+        // when
+        let health = client.connection_health_checker();
+
+        // then
+        assert!(health.healthy());
+    }
+
+    #[tokio::test]
+    async fn block_stream_skips_to_the_newest_block() {
+        // given
+        let mut api = MockApi::new();
+        let mut latest_heights = vec![2, 5, 5, 8].into_iter();
+        api.expect_latest_block()
+            .returning(move || Ok(given_a_block(latest_heights.next().unwrap(), 1)));
+        let client = FailoverClient::new(vec![(api, healthy())], NonZeroUsize::MIN)
+            .with_block_poll_interval(Duration::ZERO);
+
+        // when
+        let heights: Vec<_> = client
+            .block_stream(3)
+            .take(2)
+            .map(|block| block.unwrap().header.height)
+            .collect()
+            .await;
+
+        // then
+        assert_eq!(heights, vec![5, 8]);
+    }
+
+    fn given_endpoint_at(block: FuelBlock) -> (MockApi, HealthChecker) {
+        given_endpoint(block.clone(), vec![block])
+    }
+
+    fn given_endpoint(latest: FuelBlock, blocks: Vec<FuelBlock>) -> (MockApi, HealthChecker) {
+        let mut api = MockApi::new();
+        api.expect_latest_block()
+            .returning(move || Ok(latest.clone()));
+        api.expect_block_at_height().returning(move |height| {
+            let block = blocks
+                .iter()
+                .find(|block| block.header.height == height)
+                .cloned();
+            Ok(block)
+        });
+
+        (api, healthy())
+    }
+
+    fn healthy() -> HealthChecker {
+        ConnectionHealthTracker::new(1).tracker()
+    }
+
+    fn unhealthy() -> HealthChecker {
+        ConnectionHealthTracker::new(0).tracker()
+    }
+
+    // `fork` tells apart blocks of equal height reported by nodes on different forks
+    fn given_a_block(height: u32, fork: u8) -> FuelBlock {
+        let mut id = [fork; 32];
+        id[..4].copy_from_slice(&height.to_be_bytes());
+
+        FuelBlock {
+            id: FuelBlockId::from(id),
+            header: FuelHeader {
+                id: Default::default(),
+                da_height: Default::default(),
+                consensus_parameters_version: Default::default(),
+                state_transition_bytecode_version: Default::default(),
+                transactions_count: Default::default(),
+                message_receipt_count: Default::default(),
+                transactions_root: Default::default(),
+                message_outbox_root: Default::default(),
+                event_inbox_root: Default::default(),
+                height,
+                prev_root: Default::default(),
+                time: Tai64(0),
+                application_hash: Default::default(),
+            },
+            consensus: FuelConsensus::Unknown,
+            transactions: vec![],
+            block_producer: None,
+        }
+    }
+}
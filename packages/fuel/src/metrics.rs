@@ -1,7 +1,9 @@
 use metrics::{
+    endpoint_labels,
     prometheus::{core::Collector, IntCounter, Opts},
     RegistersMetrics,
 };
+use url::Url;
 
 #[derive(Clone)]
 pub struct Metrics {
@@ -14,12 +16,17 @@ impl RegistersMetrics for Metrics {
     }
 }
 
-impl Default for Metrics {
-    fn default() -> Self {
-        let fuel_network_errors = IntCounter::with_opts(Opts::new(
-            "fuel_network_errors",
-            "Number of network errors encountered while polling for a new Fuel block.",
-        ))
+impl Metrics {
+    /// Metrics labelled with the endpoint they belong to, so that several clients can be
+    /// registered side by side.
+    pub fn for_endpoint(position: usize, url: &Url) -> Self {
+        let fuel_network_errors = IntCounter::with_opts(
+            Opts::new(
+                "fuel_network_errors",
+                "Number of network errors encountered while polling for a new Fuel block.",
+            )
+            .const_labels(endpoint_labels(position, url)),
+        )
         .expect("fuel_network_errors metric to be correctly configured");
         Self {
             fuel_network_errors,
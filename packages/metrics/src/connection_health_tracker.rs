@@ -26,6 +26,12 @@ impl ConnectionHealthTracker {
         self.consecutive_failures.fetch_add(1, Ordering::SeqCst);
     }
 
+    /// Deems the connection unhealthy until the next success.
+    pub fn note_down(&self) {
+        self.consecutive_failures
+            .fetch_max(self.max_consecutive_failures, Ordering::SeqCst);
+    }
+
     pub fn note_success(&self) {
         self.consecutive_failures.store(0, Ordering::SeqCst);
     }
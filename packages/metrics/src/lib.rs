@@ -1,4 +1,8 @@
 #![deny(unused_crate_dependencies)]
+use std::collections::HashMap;
+
+use url::Url;
+
 mod connection_health_tracker;
 pub use connection_health_tracker::*;
 
@@ -20,3 +24,15 @@ pub trait RegistersMetrics {
 
     fn metrics(&self) -> Vec<Box<dyn crate::prometheus::core::Collector>>;
 }
+
+/// Const labels telling apart the metrics of the clients of several endpoints. The url itself is
+/// left out since it may embed credentials or an api key.
+pub fn endpoint_labels(position: usize, url: &Url) -> HashMap<String, String> {
+    HashMap::from([
+        ("endpoint".to_string(), position.to_string()),
+        (
+            "host".to_string(),
+            url.host_str().unwrap_or_default().to_string(),
+        ),
+    ])
+}
@@ -1,3 +1,5 @@
+use std::pin::Pin;
+
 pub use fuel_core_client::client::types::{
     block::{
         Block as FuelBlock, Consensus as FuelConsensus, Header as FuelHeader,
@@ -6,6 +8,8 @@ pub use fuel_core_client::client::types::{
     primitives::{BlockId as FuelBlockId, Bytes32 as FuelBytes32, PublicKey as FuelPublicKey},
 };
 
+use crate::types::Stream;
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("{0}")]
@@ -21,4 +25,14 @@ pub type Result<T> = std::result::Result<T, Error>;
 pub trait Api: Send + Sync {
     async fn block_at_height(&self, height: u32) -> Result<Option<FuelBlock>>;
     async fn latest_block(&self) -> Result<FuelBlock>;
+    /// Canonically encoded transactions of `block`, in the order their ids appear in the block.
+    async fn block_transactions(&self, block: &FuelBlock) -> Result<Vec<Vec<u8>>>;
+    /// Yields the latest block whenever it is at or above `from_height` and past the last delivered
+    /// block. Blocks produced while the stream wasn't polled are skipped, only the newest of them
+    /// is yielded. Errors are yielded as items; the stream resumes after the last delivered block
+    /// when polled again.
+    fn block_stream<'a>(
+        &'a self,
+        from_height: u32,
+    ) -> Pin<Box<dyn Stream<Item = Result<FuelBlock>> + Send + 'a>>;
 }
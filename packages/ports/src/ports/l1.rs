@@ -2,7 +2,7 @@ use std::pin::Pin;
 
 use crate::types::{
     FuelBlockCommittedOnL1, InvalidL1Height, L1Height, Stream, TransactionResponse,
-    ValidatedFuelBlock, U256,
+    ValidatedFuelBlock, WalletRole, U256,
 };
 
 #[derive(Debug, thiserror::Error)]
@@ -15,6 +15,9 @@ pub enum Error {
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Blobs an L1 block can carry (EIP-4844), and so the most state fragments a single tx can.
+pub const MAX_BLOBS_PER_BLOCK: usize = 6;
+
 impl From<InvalidL1Height> for Error {
     fn from(err: InvalidL1Height) -> Self {
         Self::Other(err.to_string())
@@ -34,7 +37,9 @@ pub trait Contract: Send + Sync {
 pub trait Api {
     async fn submit_l2_state(&self, state_data: Vec<u8>) -> Result<[u8; 32]>;
     async fn get_block_number(&self) -> Result<L1Height>;
-    async fn balance(&self) -> Result<U256>;
+    /// Hash of the canonical block at `height`, `None` if there is none yet.
+    async fn block_hash(&self, height: L1Height) -> Result<Option<[u8; 32]>>;
+    async fn balance(&self, wallet: WalletRole) -> Result<U256>;
     async fn get_transaction_response(
         &self,
         tx_hash: [u8; 32],
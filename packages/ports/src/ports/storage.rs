@@ -1,7 +1,9 @@
 use std::sync::Arc;
 
 use crate::types::{
-    BlockSubmission, StateFragment, StateSubmission, SubmissionTx, TransactionState,
+    AdminAction, BlockSubmission, DateTime, L1Height, Page, StateFragment, StateSubmission,
+    StateSubmissionDetails, SubmissionTx, SubmissionTxDetails, TransactionState,
+    UnsubmittedFragments, Utc,
 };
 
 #[derive(Debug, thiserror::Error)]
@@ -20,21 +22,101 @@ pub type Result<T> = std::result::Result<T, Error>;
 pub trait Storage: Send + Sync {
     async fn insert(&self, submission: BlockSubmission) -> Result<()>;
     async fn submission_w_latest_block(&self) -> Result<Option<BlockSubmission>>;
+    async fn completed_submission_w_latest_block(&self) -> Result<Option<BlockSubmission>>;
     async fn set_submission_completed(&self, fuel_block_hash: [u8; 32]) -> Result<BlockSubmission>;
+    async fn revert_submission_completion(
+        &self,
+        fuel_block_hash: [u8; 32],
+    ) -> Result<BlockSubmission>;
+    /// Inserts the submission, replacing any existing one at the same height.
+    async fn reopen_submission(&self, submission: BlockSubmission) -> Result<()>;
+    /// Records when the commit tx of the block was sent, and when the block was produced if known.
+    async fn record_commit_sent(
+        &self,
+        fuel_block_hash: [u8; 32],
+        fuel_block_time: Option<DateTime<Utc>>,
+        sent_at: DateTime<Utc>,
+    ) -> Result<()>;
+    async fn commit_sent_at(&self, fuel_block_hash: [u8; 32]) -> Result<Option<DateTime<Utc>>>;
+    /// The L1 block up to which commit events have been processed, if any were.
+    async fn last_processed_l1_height(&self) -> Result<Option<L1Height>>;
+    async fn set_last_processed_l1_height(&self, height: L1Height) -> Result<()>;
+
+    /// Hands out the lowest released nonce of `address` if there is one, otherwise the next one.
+    /// Nonces below `pending_nonce` are taken to be used on L1.
+    async fn reserve_nonce(&self, address: [u8; 20], pending_nonce: u64) -> Result<u64>;
+    async fn record_nonce_tx(&self, address: [u8; 20], nonce: u64, tx_hash: [u8; 32])
+        -> Result<()>;
+    /// Makes a nonce no tx was sent with available again.
+    async fn release_nonce(&self, address: [u8; 20], nonce: u64) -> Result<()>;
+    /// Released nonces, i.e. gaps later transactions of `address` wait on.
+    async fn unused_nonces(&self, address: [u8; 20]) -> Result<Vec<u64>>;
+    /// Continues handing out nonces from `pending_nonce`. Returns the nonces that were reserved at
+    /// or above it, as L1 never saw a tx using them.
+    async fn reconcile_nonces(&self, address: [u8; 20], pending_nonce: u64) -> Result<Vec<u64>>;
 
     async fn insert_state_submission(
         &self,
         submission: StateSubmission,
         fragments: Vec<StateFragment>,
     ) -> Result<()>;
-    async fn get_unsubmitted_fragments(&self) -> Result<Vec<StateFragment>>;
+    /// The oldest fragments no pending or finalized tx carries, at most `max_fragments` of them.
+    async fn get_unsubmitted_fragments(&self, max_fragments: usize) -> Result<Vec<StateFragment>>;
+    async fn unsubmitted_fragments_summary(&self) -> Result<UnsubmittedFragments>;
     async fn record_pending_tx(&self, tx_hash: [u8; 32], fragment_ids: Vec<u32>) -> Result<()>;
     async fn get_pending_txs(&self) -> Result<Vec<SubmissionTx>>;
     async fn has_pending_txs(&self) -> Result<bool>;
     async fn state_submission_w_latest_block(&self) -> Result<Option<StateSubmission>>;
+    /// Height of the latest state submission all fragments of which are in finalized txs.
+    async fn latest_finalized_state_height(&self) -> Result<Option<u32>>;
     async fn update_submission_tx_state(
         &self,
         hash: [u8; 32],
         state: TransactionState,
     ) -> Result<()>;
+    /// When each of the fragments the tx carried was imported, along with when the tx was
+    /// finalized. Empty unless the tx is finalized.
+    async fn fragment_finality_times(
+        &self,
+        tx_hash: [u8; 32],
+    ) -> Result<Vec<(DateTime<Utc>, DateTime<Utc>)>>;
+    /// Gives up on a pending tx by marking it as failed, which frees its fragments to be
+    /// submitted again. Returns whether a pending tx with the hash was found.
+    async fn drop_pending_tx(&self, hash: [u8; 32]) -> Result<bool>;
+    /// Detaches the fragments from the txs that carried them, so that they are submitted again.
+    /// Returns the number of fragments that were carried by any tx.
+    async fn requeue_fragments(&self, fragment_ids: Vec<u32>) -> Result<u64>;
+    async fn record_admin_action(&self, action: AdminAction) -> Result<()>;
+
+    /// Ordered by fuel block height, latest first.
+    async fn block_submissions(&self, page: Page) -> Result<Vec<BlockSubmission>>;
+    async fn block_submission_by_height(&self, height: u32) -> Result<Option<BlockSubmission>>;
+    async fn block_submission_by_hash(
+        &self,
+        fuel_block_hash: [u8; 32],
+    ) -> Result<Option<BlockSubmission>>;
+    /// Ordered by fuel block height, latest first.
+    async fn state_submissions(&self, page: Page) -> Result<Vec<StateSubmissionDetails>>;
+    /// Ordered by the time the txs were recorded, latest first.
+    async fn submission_txs(
+        &self,
+        page: Page,
+        state: Option<TransactionState>,
+    ) -> Result<Vec<SubmissionTxDetails>>;
+
+    /// Fails unless the database can be reached.
+    async fn ping(&self) -> Result<()>;
+}
+
+/// Makes sure only one of the replicas sharing the storage leads at a time.
+#[async_trait::async_trait]
+#[cfg_attr(feature = "test-helpers", mockall::automock)]
+pub trait LeaderElection: Send + Sync {
+    /// Takes the lead if no other replica holds it, or confirms it is still held. Returns
+    /// whether this replica leads.
+    async fn try_lead(&self) -> Result<bool>;
+
+    /// Confirms the lead is still held, without trying to take it. Returns whether this replica
+    /// leads.
+    async fn confirm_lead(&self) -> Result<bool>;
 }
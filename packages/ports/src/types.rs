@@ -1,18 +1,24 @@
 #[cfg(feature = "l1")]
 pub use alloy::primitives::{Address, U256};
-#[cfg(feature = "l1")]
+#[cfg(any(feature = "l1", feature = "fuel"))]
 pub use futures::Stream;
 
+mod admin_action;
 mod block_submission;
 #[cfg(feature = "l1")]
 mod fuel_block_committed_on_l1;
 mod l1_height;
+mod page;
 mod state_submission;
+mod wallet_role;
 
+pub use admin_action::*;
 pub use block_submission::*;
 #[cfg(feature = "l1")]
 pub use fuel_block_committed_on_l1::*;
 pub use l1_height::*;
+pub use page::*;
 pub use state_submission::*;
 #[cfg(any(feature = "fuel", feature = "l1"))]
 pub use validator::block::*;
+pub use wallet_role::*;
@@ -0,0 +1,12 @@
+/// An entry of the audit log kept of admin actions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdminAction {
+    /// e.g. `pause_runner`
+    pub action: String,
+    /// Who took the action, i.e. the address the request came from.
+    pub actor: String,
+    /// What the action was applied to.
+    pub details: String,
+    /// `ok`, or what went wrong.
+    pub outcome: String,
+}
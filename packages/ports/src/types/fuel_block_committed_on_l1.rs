@@ -1,9 +1,16 @@
-use crate::types::U256;
+use crate::types::{L1Height, U256};
 
 #[derive(Clone, Copy)]
 pub struct FuelBlockCommittedOnL1 {
     pub fuel_block_hash: [u8; 32],
     pub commit_height: U256,
+    /// The L1 block the event was emitted in.
+    pub l1_height: L1Height,
+    /// Hash of the L1 block the event was emitted in, to tell whether that block is still
+    /// canonical.
+    pub l1_block_hash: [u8; 32],
+    /// Set when a reorg dropped the event from the canonical chain.
+    pub removed: bool,
 }
 
 impl std::fmt::Debug for FuelBlockCommittedOnL1 {
@@ -15,6 +22,15 @@ impl std::fmt::Debug for FuelBlockCommittedOnL1 {
         f.debug_struct("FuelBlockCommittedOnL1")
             .field("hash", &hash)
             .field("commit_height", &self.commit_height)
+            .field("l1_height", &self.l1_height)
+            .field(
+                "l1_block_hash",
+                &self
+                    .l1_block_hash
+                    .map(|byte| format!("{byte:02x?}"))
+                    .join(""),
+            )
+            .field("removed", &self.removed)
             .finish()
     }
 }
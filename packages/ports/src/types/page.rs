@@ -0,0 +1,10 @@
+/// A window into a listing, which is ordered newest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Page {
+    pub offset: u32,
+    pub limit: u32,
+    /// Only rows listed after the one with this key: those below this fuel block height for
+    /// submissions, below this id for txs. Unlike `offset`, it isn't thrown off by rows added
+    /// between fetching one page and the next.
+    pub before: Option<u64>,
+}
@@ -20,6 +20,74 @@ impl StateFragment {
     pub const MAX_FRAGMENT_SIZE: usize = 128 * 1024;
 }
 
+/// Totals over the fragments no pending or finalized tx carries.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UnsubmittedFragments {
+    pub count: u64,
+    pub bytes: u64,
+    pub oldest_created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FragmentStatus {
+    /// Not carried by any pending or finalized tx.
+    Unsubmitted,
+    Pending,
+    Finalized,
+}
+
+// Used for DB storage. Ordered by how far the fragment got, which the DB relies on when a fragment
+// was carried by several txs.
+impl FragmentStatus {
+    pub fn into_i16(&self) -> i16 {
+        match self {
+            FragmentStatus::Unsubmitted => 0,
+            FragmentStatus::Pending => 1,
+            FragmentStatus::Finalized => 2,
+        }
+    }
+
+    pub fn from_i16(value: i16) -> Option<Self> {
+        match value {
+            0 => Some(Self::Unsubmitted),
+            1 => Some(Self::Pending),
+            2 => Some(Self::Finalized),
+            _ => None,
+        }
+    }
+}
+
+/// A fragment without its data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FragmentDetails {
+    pub id: u32,
+    pub fragment_idx: u32,
+    pub size: u64,
+    pub created_at: DateTime<Utc>,
+    pub status: FragmentStatus,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateSubmissionDetails {
+    pub submission: StateSubmission,
+    /// Ordered by `fragment_idx`.
+    pub fragments: Vec<FragmentDetails>,
+}
+
+/// A fragment carried by a tx, along with the state submission it belongs to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxFragment {
+    pub fragment_id: u32,
+    pub fragment_idx: u32,
+    pub submission_block_height: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubmissionTxDetails {
+    pub tx: SubmissionTx,
+    pub fragments: Vec<TxFragment>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SubmissionTx {
     pub id: Option<u32>,
@@ -0,0 +1,26 @@
+use serde::Serialize;
+
+/// The wallets the committer sends transactions from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WalletRole {
+    /// Posts the block commitments.
+    Main,
+    /// Posts the L2 state as blobs.
+    Blob,
+}
+
+impl WalletRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Main => "main",
+            Self::Blob => "blob",
+        }
+    }
+}
+
+impl std::fmt::Display for WalletRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
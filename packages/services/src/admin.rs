@@ -0,0 +1,324 @@
+use std::fmt::Display;
+
+use ports::{storage::Storage, types::AdminAction};
+
+use crate::{block_committer::ResubmissionQueue, Leadership, Result, RunnerHealth};
+
+const NOT_LEADING: &str = "rejected, not the leader";
+// the runners pausing is meant for, the others keep the committer's view of L1 and its wallets
+// current
+const PAUSABLE_RUNNERS: [&str; 3] = ["Block Committer", "State Committer", "State Importer"];
+
+/// Operator interventions. Every one of them, successful or not, is recorded in the audit log.
+///
+/// Pause flags and queued resubmissions are kept in memory by the replica they were sent to, so
+/// only the leading replica, the one running the runners, takes them. They don't survive a restart
+/// or a change of leader.
+pub struct Admin<Db> {
+    storage: Db,
+    runners: RunnerHealth,
+    resubmissions: ResubmissionQueue,
+    leadership: Leadership,
+}
+
+/// The result of an action only the leading replica takes.
+#[derive(Debug, PartialEq, Eq)]
+pub enum LeaderOnly<T> {
+    Done(T),
+    NotLeading,
+}
+
+impl<Db> Admin<Db> {
+    pub fn new(
+        storage: Db,
+        runners: RunnerHealth,
+        resubmissions: ResubmissionQueue,
+        leadership: Leadership,
+    ) -> Self {
+        Self {
+            storage,
+            runners,
+            resubmissions,
+            leadership,
+        }
+    }
+}
+
+impl<Db> Admin<Db>
+where
+    Db: Storage,
+{
+    /// Returns the name of the paused runner, `None` if there is no pausable runner called
+    /// `runner`. Only the block committer, the state committer and the state importer can be
+    /// paused.
+    pub async fn pause_runner(
+        &self,
+        runner: &str,
+        actor: &str,
+    ) -> Result<LeaderOnly<Option<&'static str>>> {
+        self.set_runner_paused(runner, true, actor).await
+    }
+
+    /// Returns the name of the resumed runner, `None` if there is no pausable runner called
+    /// `runner`.
+    pub async fn resume_runner(
+        &self,
+        runner: &str,
+        actor: &str,
+    ) -> Result<LeaderOnly<Option<&'static str>>> {
+        self.set_runner_paused(runner, false, actor).await
+    }
+
+    async fn set_runner_paused(
+        &self,
+        runner: &str,
+        paused: bool,
+        actor: &str,
+    ) -> Result<LeaderOnly<Option<&'static str>>> {
+        let action = if paused {
+            "pause_runner"
+        } else {
+            "resume_runner"
+        };
+        let target = format!("runner {runner:?}");
+
+        if !self.leadership.is_leading() {
+            self.audit(action, target, actor, NOT_LEADING).await?;
+            return Ok(LeaderOnly::NotLeading);
+        }
+
+        let found = self
+            .runners
+            .find(runner)
+            .filter(|(name, _)| PAUSABLE_RUNNERS.contains(name))
+            .map(|(name, health)| {
+                health.set_paused(paused);
+                name
+            });
+
+        let outcome = if found.is_some() { "ok" } else { "not found" };
+        self.audit(action, target, actor, outcome).await?;
+
+        Ok(LeaderOnly::Done(found))
+    }
+
+    /// Has the block committer commit to the block at `commit_height` again.
+    pub async fn force_resubmission(
+        &self,
+        commit_height: u32,
+        actor: &str,
+    ) -> Result<LeaderOnly<()>> {
+        let target = format!("commit height {commit_height}");
+
+        if !self.leadership.is_leading() {
+            self.audit("force_resubmission", target, actor, NOT_LEADING)
+                .await?;
+            return Ok(LeaderOnly::NotLeading);
+        }
+
+        self.resubmissions.push(commit_height);
+        self.audit("force_resubmission", target, actor, "queued")
+            .await?;
+
+        Ok(LeaderOnly::Done(()))
+    }
+
+    /// Marks the pending tx as failed. Returns whether a pending tx with the hash was found.
+    pub async fn drop_tx(&self, hash: [u8; 32], actor: &str) -> Result<bool> {
+        let dropped = self.storage.drop_pending_tx(hash).await;
+
+        let outcome = match &dropped {
+            Ok(true) => "ok".to_string(),
+            Ok(false) => "not found".to_string(),
+            Err(e) => e.to_string(),
+        };
+        self.audit(
+            "drop_tx",
+            format!("tx {}", hex::encode(hash)),
+            actor,
+            outcome,
+        )
+        .await?;
+
+        Ok(dropped?)
+    }
+
+    /// Returns the number of fragments that were detached from txs.
+    pub async fn requeue_fragments(&self, fragment_ids: Vec<u32>, actor: &str) -> Result<u64> {
+        let details = format!("fragments {fragment_ids:?}");
+        let requeued = self.storage.requeue_fragments(fragment_ids).await;
+
+        let outcome = match &requeued {
+            Ok(count) => format!("ok, {count} detached from txs"),
+            Err(e) => e.to_string(),
+        };
+        self.audit("requeue_fragments", details, actor, outcome)
+            .await?;
+
+        Ok(requeued?)
+    }
+
+    async fn audit(
+        &self,
+        action: &str,
+        target: String,
+        actor: &str,
+        outcome: impl Display,
+    ) -> Result<()> {
+        tracing::info!("admin action {action} on {target} by {actor}: {outcome}");
+
+        self.storage
+            .record_admin_action(AdminAction {
+                action: action.to_string(),
+                actor: actor.to_string(),
+                details: target,
+                outcome: outcome.to_string(),
+            })
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockall::predicate::eq;
+    use ports::storage::MockStorage;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn pausing_is_recorded_in_the_audit_log() {
+        // given
+        let runners = RunnerHealth::default();
+        let committer = runners.track("Block Committer");
+
+        let mut storage = MockStorage::new();
+        storage
+            .expect_record_admin_action()
+            .with(eq(AdminAction {
+                action: "pause_runner".to_string(),
+                actor: "127.0.0.1".to_string(),
+                details: "runner \"BlockCommitter\"".to_string(),
+                outcome: "ok".to_string(),
+            }))
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let admin = Admin::new(
+            storage,
+            runners,
+            ResubmissionQueue::default(),
+            given_leadership(true),
+        );
+
+        // when
+        let paused = admin
+            .pause_runner("BlockCommitter", "127.0.0.1")
+            .await
+            .unwrap();
+
+        // then
+        assert_eq!(paused, LeaderOnly::Done(Some("Block Committer")));
+        assert!(committer.is_paused());
+    }
+
+    #[tokio::test]
+    async fn only_the_committers_and_the_importer_can_be_paused() {
+        // given
+        let runners = RunnerHealth::default();
+        let listener = runners.track("Commit Listener");
+
+        let mut storage = MockStorage::new();
+        storage
+            .expect_record_admin_action()
+            .withf(|action| action.outcome == "not found")
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let admin = Admin::new(
+            storage,
+            runners,
+            ResubmissionQueue::default(),
+            given_leadership(true),
+        );
+
+        // when
+        let paused = admin
+            .pause_runner("CommitListener", "127.0.0.1")
+            .await
+            .unwrap();
+
+        // then
+        assert_eq!(paused, LeaderOnly::Done(None));
+        assert!(!listener.is_paused());
+    }
+
+    #[tokio::test]
+    async fn runner_actions_are_rejected_when_not_leading() {
+        // given
+        let runners = RunnerHealth::default();
+        let committer = runners.track("Block Committer");
+
+        let mut storage = MockStorage::new();
+        storage
+            .expect_record_admin_action()
+            .withf(|action| action.outcome == NOT_LEADING)
+            .times(2)
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let resubmissions = ResubmissionQueue::default();
+        let admin = Admin::new(
+            storage,
+            runners,
+            resubmissions.clone(),
+            given_leadership(false),
+        );
+
+        // when
+        let paused = admin
+            .pause_runner("BlockCommitter", "127.0.0.1")
+            .await
+            .unwrap();
+        let resubmitted = admin.force_resubmission(3, "127.0.0.1").await.unwrap();
+
+        // then
+        assert_eq!(paused, LeaderOnly::NotLeading);
+        assert_eq!(resubmitted, LeaderOnly::NotLeading);
+        assert!(!committer.is_paused());
+        assert_eq!(resubmissions.pop(), None);
+    }
+
+    #[tokio::test]
+    async fn failed_actions_are_recorded_too() {
+        // given
+        let mut storage = MockStorage::new();
+        storage.expect_drop_pending_tx().returning(|_| {
+            Box::pin(async { Err(ports::storage::Error::Database("timed out".into())) })
+        });
+        storage
+            .expect_record_admin_action()
+            .withf(|action| action.action == "drop_tx" && action.outcome.contains("timed out"))
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let admin = Admin::new(
+            storage,
+            RunnerHealth::default(),
+            ResubmissionQueue::default(),
+            given_leadership(true),
+        );
+
+        // when
+        let result = admin.drop_tx([1; 32], "127.0.0.1").await;
+
+        // then
+        assert!(result.is_err());
+    }
+
+    fn given_leadership(leading: bool) -> Leadership {
+        let leadership = Leadership::default();
+        leadership.set_leading(leading);
+        leadership
+    }
+}
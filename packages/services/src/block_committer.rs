@@ -1,19 +1,27 @@
-use std::num::NonZeroU32;
+use std::{
+    collections::VecDeque,
+    num::NonZeroU32,
+    sync::{Arc, Mutex},
+};
 
 use async_trait::async_trait;
 use metrics::{
-    prometheus::{core::Collector, IntGauge, Opts},
+    prometheus::{core::Collector, Histogram, IntGauge, Opts},
     RegistersMetrics,
 };
 use ports::{
+    fuel::FuelBlock,
     storage::Storage,
-    types::{BlockSubmission, ValidatedFuelBlock},
+    types::{BlockSubmission, DateTime, Utc, ValidatedFuelBlock},
 };
-use tracing::info;
+use tracing::{info, warn};
 use validator::Validator;
 
 use super::Runner;
-use crate::{Error, Result};
+use crate::{
+    latency::{latency_histogram, seconds_between, unix_time},
+    Error, Result,
+};
 
 pub struct BlockCommitter<L1, Db, Fuel, BlockValidator> {
     l1_adapter: L1,
@@ -21,9 +29,42 @@ pub struct BlockCommitter<L1, Db, Fuel, BlockValidator> {
     storage: Db,
     block_validator: BlockValidator,
     commit_interval: NonZeroU32,
+    resubmissions: ResubmissionQueue,
     metrics: Metrics,
 }
 
+/// A validated block along with when it was produced, if its timestamp could be read.
+struct FetchedBlock {
+    block: ValidatedFuelBlock,
+    produced_at: Option<DateTime<Utc>>,
+}
+
+/// Commit heights an operator asked to be committed again. Clones share the queue.
+#[derive(Debug, Clone, Default)]
+pub struct ResubmissionQueue {
+    commit_heights: Arc<Mutex<VecDeque<u32>>>,
+}
+
+impl ResubmissionQueue {
+    pub fn push(&self, commit_height: u32) {
+        self.lock().push_back(commit_height);
+    }
+
+    pub(crate) fn pop(&self) -> Option<u32> {
+        self.lock().pop_front()
+    }
+
+    fn retry(&self, commit_height: u32) {
+        self.lock().push_front(commit_height);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<u32>> {
+        self.commit_heights
+            .lock()
+            .expect("resubmission queue lock not to be poisoned")
+    }
+}
+
 impl<L1, Db, Fuel, BlockValidator> BlockCommitter<L1, Db, Fuel, BlockValidator> {
     pub fn new(
         l1: L1,
@@ -38,11 +79,30 @@ impl<L1, Db, Fuel, BlockValidator> BlockCommitter<L1, Db, Fuel, BlockValidator>
             fuel_adapter,
             block_validator,
             commit_interval,
+            resubmissions: ResubmissionQueue::default(),
             metrics: Metrics::default(),
         }
     }
 
-    async fn submit_block(&self, fuel_block: ValidatedFuelBlock) -> Result<()> {
+    /// Commit heights pushed to `queue` are committed again ahead of any new block.
+    pub fn with_resubmission_queue(mut self, queue: ResubmissionQueue) -> Self {
+        self.resubmissions = queue;
+        self
+    }
+}
+
+impl<L1, Db, Fuel, BlockValidator> BlockCommitter<L1, Db, Fuel, BlockValidator>
+where
+    L1: ports::l1::Contract + ports::l1::Api,
+    Db: Storage,
+    Fuel: ports::fuel::Api,
+    BlockValidator: Validator,
+{
+    async fn submit_block(
+        &self,
+        fuel_block: ValidatedFuelBlock,
+        produced_at: Option<DateTime<Utc>>,
+    ) -> Result<()> {
         let submittal_height = self.l1_adapter.get_block_number().await?;
 
         let submission = BlockSubmission {
@@ -54,22 +114,78 @@ impl<L1, Db, Fuel, BlockValidator> BlockCommitter<L1, Db, Fuel, BlockValidator>
 
         self.storage.insert(submission).await?;
 
+        self.send_commit(fuel_block, produced_at).await
+    }
+
+    async fn resubmit(&self, commit_height: u32) -> Result<()> {
+        let block_height = commit_height
+            .checked_mul(self.commit_interval.get())
+            .ok_or_else(|| {
+                Error::Other(format!("commit height {commit_height} is out of range"))
+            })?;
+        let FetchedBlock { block, produced_at } = self.fetch_block(block_height).await?;
+        let submittal_height = self.l1_adapter.get_block_number().await?;
+
+        let submission = BlockSubmission {
+            block_hash: block.hash(),
+            block_height: block.height(),
+            submittal_height,
+            completed: false,
+        };
+
+        self.storage.reopen_submission(submission).await?;
+
+        self.send_commit(block, produced_at).await
+    }
+
+    async fn send_commit(
+        &self,
+        fuel_block: ValidatedFuelBlock,
+        produced_at: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        let block_hash = fuel_block.hash();
         self.l1_adapter.submit(fuel_block).await?;
+        let sent_at = Utc::now();
+
+        if let Some(produced_at) = produced_at {
+            self.metrics
+                .fuel_block_to_commit_sent
+                .observe(seconds_between(produced_at, sent_at));
+        }
+
+        // the commit is out, not being able to time it is no reason to fail
+        if let Err(e) = self
+            .storage
+            .record_commit_sent(block_hash, produced_at, sent_at)
+            .await
+        {
+            warn!(
+                "could not record when the commit of block {} was sent: {e}",
+                hex::encode(block_hash)
+            );
+        }
 
         Ok(())
     }
 
-    async fn fetch_latest_block(&self) -> Result<ValidatedFuelBlock> {
+    async fn fetch_latest_block(&self) -> Result<FetchedBlock> {
         let latest_block = self.fuel_adapter.latest_block().await?;
-        let validated_block = self.block_validator.validate(&latest_block)?;
+        let validated_block = self.validate(&latest_block)?;
 
         self.metrics
             .latest_fuel_block
-            .set(i64::from(validated_block.height()));
+            .set(i64::from(validated_block.block.height()));
 
         Ok(validated_block)
     }
 
+    fn validate(&self, fuel_block: &FuelBlock) -> Result<FetchedBlock> {
+        Ok(FetchedBlock {
+            block: self.block_validator.validate(fuel_block)?,
+            produced_at: unix_time(fuel_block.header.time),
+        })
+    }
+
     async fn check_if_stale(&self, block_height: u32) -> Result<bool> {
         match self.last_submitted_block_height().await? {
             Some(submitted_height) => Ok(submitted_height >= block_height),
@@ -82,20 +198,21 @@ impl<L1, Db, Fuel, BlockValidator> BlockCommitter<L1, Db, Fuel, BlockValidator>
     }
 
     async fn last_submitted_block_height(&self) -> Result<Option<u32>> {
-        self.storage
+        Ok(self
+            .storage
             .submission_w_latest_block()
             .await
-            .map(|submission| submission.map(|s| s.block_height))
+            .map(|submission| submission.map(|s| s.block_height))?)
     }
 
-    async fn fetch_block(&self, height: u32) -> Result<ValidatedFuelBlock> {
+    async fn fetch_block(&self, height: u32) -> Result<FetchedBlock> {
         let fuel_block = self
             .fuel_adapter
             .block_at_height(height)
             .await?
             .ok_or_else(|| Error::Other(format!("Fuel node could not provide block at height: {height}")))?;
 
-        Ok(self.block_validator.validate(&fuel_block)?)
+        self.validate(&fuel_block)
     }
 }
 
@@ -108,20 +225,32 @@ where
     BlockValidator: Validator,
 {
     async fn run(&mut self) -> Result<()> {
+        if let Some(commit_height) = self.resubmissions.pop() {
+            if let Err(e) = self.resubmit(commit_height).await {
+                self.resubmissions.retry(commit_height);
+                return Err(e);
+            }
+            info!("resubmitted commit height {commit_height}!");
+
+            return Ok(());
+        }
+
         let current_block = self.fetch_latest_block().await?;
-        let current_epoch_block_height = self.current_epoch_block_height(current_block.height());
+        let current_epoch_block_height =
+            self.current_epoch_block_height(current_block.block.height());
 
         if self.check_if_stale(current_epoch_block_height).await? {
             return Ok(());
         }
 
-        let block = if current_block.height() == current_epoch_block_height {
+        let epoch_block = if current_block.block.height() == current_epoch_block_height {
             current_block
         } else {
             self.fetch_block(current_epoch_block_height).await?
         };
+        let FetchedBlock { block, produced_at } = epoch_block;
 
-        self.submit_block(block).await?;
+        self.submit_block(block, produced_at).await?;
         info!("submitted {block:?}!");
 
         Ok(())
@@ -130,6 +259,7 @@ where
 
 struct Metrics {
     latest_fuel_block: IntGauge,
+    fuel_block_to_commit_sent: Histogram,
 }
 
 impl Default for Metrics {
@@ -140,14 +270,25 @@ impl Default for Metrics {
         ))
         .expect("Failed to configure `latest_fuel_block` metric");
 
-        Self { latest_fuel_block }
+        let fuel_block_to_commit_sent = latency_histogram(
+            "fuel_block_to_commit_sent_seconds",
+            "Time from a fuel block being produced to its commit tx being sent to L1.",
+        );
+
+        Self {
+            latest_fuel_block,
+            fuel_block_to_commit_sent,
+        }
     }
 }
 
 impl<L1, Db, Fuel, BlockValidator> RegistersMetrics for BlockCommitter<L1, Db, Fuel, BlockValidator>
 {
     fn metrics(&self) -> Vec<Box<dyn Collector>> {
-        vec![Box::new(self.metrics.latest_fuel_block.clone())]
+        vec![
+            Box::new(self.metrics.latest_fuel_block.clone()),
+            Box::new(self.metrics.fuel_block_to_commit_sent.clone()),
+        ]
     }
 }
 
@@ -161,7 +302,7 @@ mod tests {
     use ports::{
         fuel::{FuelBlock, FuelBlockId, FuelConsensus, FuelHeader, FuelPoAConsensus},
         l1::{Contract, EventStreamer, MockContract},
-        types::{L1Height, TransactionResponse, U256},
+        types::{L1Height, TransactionResponse, WalletRole, U256},
     };
     use rand::{rngs::StdRng, Rng, SeedableRng};
     use storage::{Postgres, PostgresProcess};
@@ -208,8 +349,12 @@ mod tests {
             self.api.get_block_number().await
         }
 
-        async fn balance(&self) -> ports::l1::Result<U256> {
-            self.api.balance().await
+        async fn block_hash(&self, height: L1Height) -> ports::l1::Result<Option<[u8; 32]>> {
+            self.api.block_hash(height).await
+        }
+
+        async fn balance(&self, wallet: WalletRole) -> ports::l1::Result<U256> {
+            self.api.balance(wallet).await
         }
 
         async fn get_transaction_response(
@@ -308,6 +453,38 @@ mod tests {
         block_committer.run().await.unwrap();
     }
 
+    #[tokio::test]
+    async fn resubmits_queued_commit_height_first() {
+        // given
+        let secret_key = given_secret_key();
+        let block_validator = BlockValidator::new(*secret_key.public_key().hash());
+        let block = given_a_block(2, &secret_key);
+        let fuel_adapter = given_fetcher(vec![block.clone(), given_a_block(4, &secret_key)]);
+
+        let process = PostgresProcess::shared().await.unwrap();
+        let db = db_with_submissions(&process, vec![0, 2, 4]).await;
+        let l1 = given_l1_that_expects_submission(ValidatedFuelBlock::new(*block.id, 2));
+
+        let queue = ResubmissionQueue::default();
+        let mut block_committer = BlockCommitter::new(
+            l1,
+            db.clone(),
+            fuel_adapter,
+            block_validator,
+            2.try_into().unwrap(),
+        )
+        .with_resubmission_queue(queue.clone());
+        queue.push(1);
+
+        // when
+        block_committer.run().await.unwrap();
+
+        // then
+        let resubmitted = db.block_submission_by_height(2).await.unwrap().unwrap();
+        assert_eq!(resubmitted.block_hash, *block.id);
+        assert!(!resubmitted.completed);
+    }
+
     #[tokio::test]
     async fn updates_block_metric_regardless_if_block_is_published() {
         let secret_key = given_secret_key();
@@ -1,42 +1,69 @@
+use std::{collections::VecDeque, pin::pin, time::Duration};
+
 use async_trait::async_trait;
-use futures::{StreamExt, TryStreamExt};
+use futures::StreamExt;
 use metrics::{
-    prometheus::{core::Collector, IntGauge, Opts},
+    prometheus::{core::Collector, Histogram, IntGauge, Opts},
     RegistersMetrics,
 };
 use ports::{
     storage::Storage,
-    types::{FuelBlockCommittedOnL1, L1Height},
+    types::{FuelBlockCommittedOnL1, L1Height, Utc},
 };
 use tokio_util::sync::CancellationToken;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 use super::Runner;
+use crate::latency::{latency_histogram, seconds_between};
 
 pub struct CommitListener<C, Db> {
     contract: C,
     storage: Db,
+    confirmations: u64,
+    confirmation_check_interval: Duration,
+    // events waiting for confirmations, in the order they were emitted
+    unconfirmed: VecDeque<FuelBlockCommittedOnL1>,
     metrics: Metrics,
     cancel_token: CancellationToken,
 }
 
 impl<C, Db> CommitListener<C, Db> {
+    const DEFAULT_CONFIRMATION_CHECK_INTERVAL: Duration = Duration::from_secs(12);
+
     pub fn new(contract: C, storage: Db, cancel_token: CancellationToken) -> Self {
         Self {
             contract,
             storage,
+            confirmations: 0,
+            confirmation_check_interval: Self::DEFAULT_CONFIRMATION_CHECK_INTERVAL,
+            unconfirmed: VecDeque::new(),
             metrics: Metrics::default(),
             cancel_token,
         }
     }
+
+    /// Only mark submissions as completed once `confirmations` L1 blocks were built on top of the
+    /// commit event, checking for new blocks every `check_interval`.
+    #[must_use]
+    pub fn with_confirmations(mut self, confirmations: u64, check_interval: Duration) -> Self {
+        self.confirmations = confirmations;
+        self.confirmation_check_interval = check_interval;
+        self
+    }
 }
 
 impl<C, Db> CommitListener<C, Db>
 where
-    C: ports::l1::Contract,
+    C: ports::l1::Contract + ports::l1::Api,
     Db: Storage,
 {
+    // Resumes from the last block with processed events. That block is processed again as it might
+    // hold more events than were handled before stopping.
     async fn determine_starting_l1_height(&mut self) -> crate::Result<L1Height> {
+        if let Some(height) = self.storage.last_processed_l1_height().await? {
+            return Ok(height);
+        }
+
         self.storage
             .submission_w_latest_block()
             .await?
@@ -58,6 +85,117 @@ where
             .latest_committed_block
             .set(i64::from(submission.block_height));
 
+        self.storage
+            .set_last_processed_l1_height(committed_on_l1.l1_height)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn handle_event(&mut self, event: FuelBlockCommittedOnL1) -> crate::Result<()> {
+        if event.removed {
+            return self.handle_reorged_event(event).await;
+        }
+
+        self.observe_commit_latency(&event).await;
+        self.unconfirmed.push_back(event);
+        self.complete_confirmed().await
+    }
+
+    // events delivered again after a restart are observed again
+    async fn observe_commit_latency(&self, event: &FuelBlockCommittedOnL1) {
+        match self.storage.commit_sent_at(event.fuel_block_hash).await {
+            Ok(Some(sent_at)) => self
+                .metrics
+                .commit_sent_to_event
+                .observe(seconds_between(sent_at, Utc::now())),
+            Ok(None) => {}
+            Err(e) => warn!("could not look up when the commit was sent: {e}"),
+        }
+    }
+
+    async fn handle_reorged_event(&mut self, event: FuelBlockCommittedOnL1) -> crate::Result<()> {
+        let same_event = |pending: &FuelBlockCommittedOnL1| {
+            pending.fuel_block_hash == event.fuel_block_hash && pending.l1_height == event.l1_height
+        };
+        if let Some(idx) = self.unconfirmed.iter().position(same_event) {
+            self.unconfirmed.remove(idx);
+            return Ok(());
+        }
+
+        warn!("Block commit event was reorged out of L1: {:?}", event);
+
+        self.storage
+            .revert_submission_completion(event.fuel_block_hash)
+            .await?;
+
+        // the commit can be included again at or below the reorged block, which a restart must not
+        // skip past
+        let cursor = self.storage.last_processed_l1_height().await?;
+        if cursor.is_some_and(|cursor| cursor > event.l1_height) {
+            self.storage
+                .set_last_processed_l1_height(event.l1_height)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn complete_confirmed(&mut self) -> crate::Result<()> {
+        if self.unconfirmed.is_empty() && self.confirmations == 0 {
+            return Ok(());
+        }
+
+        let current_height: u64 = if self.confirmations == 0 {
+            u64::MAX
+        } else {
+            self.contract.get_block_number().await?.into()
+        };
+
+        while let Some(&event) = self.unconfirmed.front() {
+            if current_height < u64::from(event.l1_height).saturating_add(self.confirmations) {
+                break; // not confirmed, neither are the events after it
+            }
+
+            // not every transport reports removed logs, so the event's block is checked to still
+            // be canonical
+            match self.contract.block_hash(event.l1_height).await? {
+                None => break, // the endpoint lags behind the one that delivered the event
+                Some(hash) if hash != event.l1_block_hash => {
+                    warn!(
+                        "Block commit event was reorged out of L1 before confirmation: {event:?}"
+                    );
+                }
+                Some(_) => Self::log_if_error(self.handle_block_committed(event).await),
+            }
+            self.unconfirmed.pop_front();
+        }
+
+        if self.confirmations > 0 {
+            self.advance_cursor(current_height.saturating_sub(self.confirmations))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    // Blocks up to the confirmed head hold no events left to handle besides those still waiting
+    // for confirmations, so a restart need not look at them again.
+    async fn advance_cursor(&self, confirmed_head: u64) -> crate::Result<()> {
+        let first_waiting = self
+            .unconfirmed
+            .front()
+            .map(|event| u64::from(event.l1_height));
+        let height = first_waiting.map_or(confirmed_head, |height| height.min(confirmed_head));
+        let height = L1Height::try_from(height).map_err(|e| crate::Error::Other(e.to_string()))?;
+
+        let cursor = self.storage.last_processed_l1_height().await?;
+        if cursor.is_some_and(|cursor| cursor >= height) {
+            return Ok(());
+        }
+
+        self.storage.set_last_processed_l1_height(height).await?;
+
         Ok(())
     }
 
@@ -71,21 +209,42 @@ where
 #[async_trait]
 impl<C, Db> Runner for CommitListener<C, Db>
 where
-    C: ports::l1::Contract,
+    C: ports::l1::Contract + ports::l1::Api,
     Db: Storage,
 {
     async fn run(&mut self) -> crate::Result<()> {
         let height = self.determine_starting_l1_height().await?;
+        // unconfirmed events are delivered again since the stream restarts from the cursor
+        self.unconfirmed.clear();
 
-        self.contract
-            .event_streamer(height)
+        let cancel_token = self.cancel_token.clone();
+        let event_streamer = self.contract.event_streamer(height);
+        let mut events = pin!(event_streamer
             .establish_stream()
             .await?
-            .map_err(Into::into)
-            .and_then(|event| self.handle_block_committed(event))
-            .take_until(self.cancel_token.cancelled())
-            .for_each(|response| async { Self::log_if_error(response) })
-            .await;
+            .take_until(cancel_token.cancelled()));
+        let mut confirmation_check = tokio::time::interval(self.confirmation_check_interval);
+
+        loop {
+            tokio::select! {
+                // events ready to be handled go first so the cursor isn't advanced past them
+                biased;
+                event = events.next() => {
+                    let Some(event) = event else {
+                        break;
+                    };
+
+                    let response = match event {
+                        Ok(event) => self.handle_event(event).await,
+                        Err(err) => Err(err.into()),
+                    };
+                    Self::log_if_error(response);
+                }
+                _ = confirmation_check.tick(), if self.confirmations > 0 => {
+                    Self::log_if_error(self.complete_confirmed().await);
+                }
+            }
+        }
 
         Ok(())
     }
@@ -94,11 +253,15 @@ where
 #[derive(Clone)]
 struct Metrics {
     latest_committed_block: IntGauge,
+    commit_sent_to_event: Histogram,
 }
 
 impl<C, Db> RegistersMetrics for CommitListener<C, Db> {
     fn metrics(&self) -> Vec<Box<dyn Collector>> {
-        vec![Box::new(self.metrics.latest_committed_block.clone())]
+        vec![
+            Box::new(self.metrics.latest_committed_block.clone()),
+            Box::new(self.metrics.commit_sent_to_event.clone()),
+        ]
     }
 }
 
@@ -110,14 +273,22 @@ impl Default for Metrics {
         ))
         .expect("Failed to configure `latest_committed_block` metric");
 
+        let commit_sent_to_event = latency_histogram(
+            "commit_sent_to_event_seconds",
+            "Time from a commit tx being sent to its `CommitSubmitted` event being seen.",
+        );
+
         Self {
             latest_committed_block,
+            commit_sent_to_event,
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::{num::NonZeroU32, time::Duration};
+
     use futures::stream;
     use metrics::{
         prometheus::{proto::Metric, Registry},
@@ -125,9 +296,12 @@ mod tests {
     };
     use mockall::predicate;
     use ports::{
-        l1::{MockContract, MockEventStreamer},
+        l1::{Contract, EventStreamer, MockContract, MockEventStreamer},
         storage::Storage,
-        types::{BlockSubmission, FuelBlockCommittedOnL1, L1Height, U256},
+        types::{
+            BlockSubmission, FuelBlockCommittedOnL1, L1Height, TransactionResponse,
+            ValidatedFuelBlock, WalletRole, U256,
+        },
     };
     use rand::Rng;
     use storage::{Postgres, PostgresProcess};
@@ -135,6 +309,9 @@ mod tests {
 
     use crate::{CommitListener, Runner};
 
+    const EVENT_L1_HEIGHT: u32 = 1000;
+    const EVENT_L1_BLOCK_HASH: [u8; 32] = [1; 32];
+
     #[tokio::test]
     async fn listener_will_update_storage_if_event_is_emitted() {
         let mut rng = rand::thread_rng();
@@ -194,6 +371,299 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn listener_remembers_the_l1_height_of_processed_events() {
+        // given
+        let mut rng = rand::thread_rng();
+        let submission = BlockSubmission {
+            completed: false,
+            ..rng.gen()
+        };
+        let block_hash = submission.block_hash;
+
+        let contract = given_contract_with_events(vec![block_hash], submission.submittal_height);
+
+        let process = PostgresProcess::shared().await.unwrap();
+        let db = db_with_submission(&process, submission).await;
+
+        let mut commit_listener =
+            CommitListener::new(contract, db.clone(), CancellationToken::default());
+
+        // when
+        commit_listener.run().await.unwrap();
+
+        // then
+        let last_processed = db.last_processed_l1_height().await.unwrap();
+        assert_eq!(last_processed, Some(EVENT_L1_HEIGHT.into()));
+    }
+
+    #[tokio::test]
+    async fn listener_resumes_from_the_last_processed_l1_height() {
+        // given
+        let mut rng = rand::thread_rng();
+        let submission = BlockSubmission {
+            completed: false,
+            ..rng.gen()
+        };
+        let block_hash = submission.block_hash;
+        let last_processed: L1Height = 42u32.into();
+
+        let contract = given_contract_with_events(vec![block_hash], last_processed);
+
+        let process = PostgresProcess::shared().await.unwrap();
+        let db = db_with_submission(&process, submission).await;
+        db.set_last_processed_l1_height(last_processed)
+            .await
+            .unwrap();
+
+        let mut commit_listener =
+            CommitListener::new(contract, db.clone(), CancellationToken::default());
+
+        // when
+        commit_listener.run().await.unwrap();
+
+        // then
+        let res = db.submission_w_latest_block().await.unwrap().unwrap();
+        assert!(res.completed);
+    }
+
+    #[tokio::test]
+    async fn listener_waits_for_confirmations_before_completing_submission() {
+        // given
+        let mut rng = rand::thread_rng();
+        let submission = BlockSubmission {
+            completed: false,
+            ..rng.gen()
+        };
+        let event = given_event(submission.block_hash);
+
+        let mut l1 = given_l1_with_events(vec![event], submission.submittal_height);
+        l1.api
+            .expect_get_block_number()
+            .returning(|| Ok((EVENT_L1_HEIGHT + 2).into()));
+
+        let process = PostgresProcess::shared().await.unwrap();
+        let db = db_with_submission(&process, submission).await;
+
+        let mut commit_listener = CommitListener::new(l1, db.clone(), CancellationToken::default())
+            .with_confirmations(3, Duration::from_secs(1));
+
+        // when
+        commit_listener.run().await.unwrap();
+
+        // then
+        let res = db.submission_w_latest_block().await.unwrap().unwrap();
+        assert!(!res.completed);
+        assert_eq!(
+            db.last_processed_l1_height().await.unwrap(),
+            Some((EVENT_L1_HEIGHT - 1).into())
+        );
+    }
+
+    #[tokio::test]
+    async fn confirmation_check_advances_the_cursor_to_the_confirmed_head() {
+        // given
+        let mut l1 = MockL1::new();
+        let mut event_streamer = MockEventStreamer::new();
+        event_streamer
+            .expect_establish_stream()
+            .return_once(|| Ok(Box::pin(stream::pending())));
+        l1.contract
+            .expect_event_streamer()
+            .return_once(move |_| Box::new(event_streamer));
+        l1.api
+            .expect_get_block_number()
+            .returning(|| Ok((EVENT_L1_HEIGHT + 3).into()));
+
+        let process = PostgresProcess::shared().await.unwrap();
+        let db = process.create_random_db().await.unwrap();
+
+        let cancel_token = CancellationToken::new();
+        let mut commit_listener = CommitListener::new(l1, db.clone(), cancel_token.clone())
+            .with_confirmations(3, Duration::from_millis(10));
+
+        // when
+        let stop = async {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            cancel_token.cancel();
+        };
+        let (result, ()) = tokio::join!(commit_listener.run(), stop);
+        result.unwrap();
+
+        // then
+        assert_eq!(
+            db.last_processed_l1_height().await.unwrap(),
+            Some(EVENT_L1_HEIGHT.into())
+        );
+    }
+
+    #[tokio::test]
+    async fn listener_completes_submission_once_confirmed() {
+        // given
+        let mut rng = rand::thread_rng();
+        let submission = BlockSubmission {
+            completed: false,
+            ..rng.gen()
+        };
+        let event = given_event(submission.block_hash);
+
+        let mut l1 = given_l1_with_events(vec![event], submission.submittal_height);
+        l1.api
+            .expect_get_block_number()
+            .returning(|| Ok((EVENT_L1_HEIGHT + 3).into()));
+
+        let process = PostgresProcess::shared().await.unwrap();
+        let db = db_with_submission(&process, submission).await;
+
+        let mut commit_listener = CommitListener::new(l1, db.clone(), CancellationToken::default())
+            .with_confirmations(3, Duration::from_secs(1));
+
+        // when
+        commit_listener.run().await.unwrap();
+
+        // then
+        let res = db.submission_w_latest_block().await.unwrap().unwrap();
+        assert!(res.completed);
+    }
+
+    #[tokio::test]
+    async fn reorged_event_reverts_completion() {
+        // given
+        let mut rng = rand::thread_rng();
+        let submission = BlockSubmission {
+            completed: true,
+            ..rng.gen()
+        };
+        let event = FuelBlockCommittedOnL1 {
+            removed: true,
+            ..given_event(submission.block_hash)
+        };
+
+        let l1 = given_l1_with_events(vec![event], submission.submittal_height);
+
+        let process = PostgresProcess::shared().await.unwrap();
+        let db = db_with_submission(&process, submission).await;
+
+        let mut commit_listener = CommitListener::new(l1, db.clone(), CancellationToken::default());
+
+        // when
+        commit_listener.run().await.unwrap();
+
+        // then
+        let res = db.submission_w_latest_block().await.unwrap().unwrap();
+        assert!(!res.completed);
+    }
+
+    #[tokio::test]
+    async fn restart_after_a_reorg_resumes_from_the_reorged_block() {
+        // given
+        let mut rng = rand::thread_rng();
+        let submission = BlockSubmission {
+            completed: true,
+            ..rng.gen()
+        };
+        let event = given_event(submission.block_hash);
+        let removal = FuelBlockCommittedOnL1 {
+            removed: true,
+            ..event
+        };
+        let cursor_past_event: L1Height = (EVENT_L1_HEIGHT + 5).into();
+
+        let mut l1 = given_l1_with_events(vec![removal], cursor_past_event);
+        let reincluded = Box::new(given_event_streamer_w_events(vec![event]));
+        l1.contract
+            .expect_event_streamer()
+            .with(predicate::eq(L1Height::from(EVENT_L1_HEIGHT)))
+            .return_once(move |_| reincluded);
+
+        let process = PostgresProcess::shared().await.unwrap();
+        let db = db_with_submission(&process, submission).await;
+        db.set_last_processed_l1_height(cursor_past_event)
+            .await
+            .unwrap();
+
+        let mut commit_listener = CommitListener::new(l1, db.clone(), CancellationToken::default());
+        commit_listener.run().await.unwrap();
+
+        // when
+        commit_listener.run().await.unwrap();
+
+        // then
+        let res = db.submission_w_latest_block().await.unwrap().unwrap();
+        assert!(res.completed);
+        assert_eq!(
+            db.last_processed_l1_height().await.unwrap(),
+            Some(EVENT_L1_HEIGHT.into())
+        );
+    }
+
+    #[tokio::test]
+    async fn reorged_event_is_dropped_while_unconfirmed() {
+        // given
+        let mut rng = rand::thread_rng();
+        let submission = BlockSubmission {
+            completed: false,
+            ..rng.gen()
+        };
+        let event = given_event(submission.block_hash);
+        let removal = FuelBlockCommittedOnL1 {
+            removed: true,
+            ..event
+        };
+
+        let mut l1 = given_l1_with_events(vec![event, removal], submission.submittal_height);
+        l1.api
+            .expect_get_block_number()
+            .returning(|| Ok(EVENT_L1_HEIGHT.into()));
+
+        let process = PostgresProcess::shared().await.unwrap();
+        let db = db_with_submission(&process, submission).await;
+
+        let mut commit_listener = CommitListener::new(l1, db.clone(), CancellationToken::default())
+            .with_confirmations(1, Duration::from_secs(1));
+
+        // when
+        commit_listener.run().await.unwrap();
+
+        // then
+        let res = db.submission_w_latest_block().await.unwrap().unwrap();
+        assert!(!res.completed);
+        assert!(commit_listener.unconfirmed.is_empty());
+    }
+
+    #[tokio::test]
+    async fn event_from_a_block_no_longer_canonical_is_dropped_on_confirmation() {
+        // given
+        let mut rng = rand::thread_rng();
+        let submission = BlockSubmission {
+            completed: false,
+            ..rng.gen()
+        };
+        let event = FuelBlockCommittedOnL1 {
+            l1_block_hash: [2; 32],
+            ..given_event(submission.block_hash)
+        };
+
+        let mut l1 = given_l1_with_events(vec![event], submission.submittal_height);
+        l1.api
+            .expect_get_block_number()
+            .returning(|| Ok((EVENT_L1_HEIGHT + 1).into()));
+
+        let process = PostgresProcess::shared().await.unwrap();
+        let db = db_with_submission(&process, submission).await;
+
+        let mut commit_listener = CommitListener::new(l1, db.clone(), CancellationToken::default())
+            .with_confirmations(1, Duration::from_secs(1));
+
+        // when
+        commit_listener.run().await.unwrap();
+
+        // then
+        let res = db.submission_w_latest_block().await.unwrap().unwrap();
+        assert!(!res.completed);
+        assert!(commit_listener.unconfirmed.is_empty());
+    }
+
     #[tokio::test]
     async fn error_while_handling_event_will_not_close_stream() {
         let mut rng = rand::thread_rng();
@@ -235,29 +705,98 @@ mod tests {
         db
     }
 
+    struct MockL1 {
+        api: ports::l1::MockApi,
+        contract: MockContract,
+    }
+
+    impl MockL1 {
+        fn new() -> Self {
+            Self {
+                api: ports::l1::MockApi::new(),
+                contract: MockContract::new(),
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Contract for MockL1 {
+        async fn submit(&self, block: ValidatedFuelBlock) -> ports::l1::Result<()> {
+            self.contract.submit(block).await
+        }
+
+        fn event_streamer(&self, height: L1Height) -> Box<dyn EventStreamer + Send + Sync> {
+            self.contract.event_streamer(height)
+        }
+
+        fn commit_interval(&self) -> NonZeroU32 {
+            self.contract.commit_interval()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ports::l1::Api for MockL1 {
+        async fn submit_l2_state(&self, state_data: Vec<u8>) -> ports::l1::Result<[u8; 32]> {
+            self.api.submit_l2_state(state_data).await
+        }
+
+        async fn get_block_number(&self) -> ports::l1::Result<L1Height> {
+            self.api.get_block_number().await
+        }
+
+        async fn block_hash(&self, height: L1Height) -> ports::l1::Result<Option<[u8; 32]>> {
+            self.api.block_hash(height).await
+        }
+
+        async fn balance(&self, wallet: WalletRole) -> ports::l1::Result<U256> {
+            self.api.balance(wallet).await
+        }
+
+        async fn get_transaction_response(
+            &self,
+            _tx_hash: [u8; 32],
+        ) -> ports::l1::Result<Option<TransactionResponse>> {
+            Ok(None)
+        }
+    }
+
+    fn given_event(block_hash: [u8; 32]) -> FuelBlockCommittedOnL1 {
+        FuelBlockCommittedOnL1 {
+            fuel_block_hash: block_hash,
+            commit_height: U256::default(),
+            l1_height: EVENT_L1_HEIGHT.into(),
+            l1_block_hash: EVENT_L1_BLOCK_HASH,
+            removed: false,
+        }
+    }
+
     fn given_contract_with_events(
-        events: Vec<[u8; 32]>,
+        block_hashes: Vec<[u8; 32]>,
+        starting_from_height: L1Height,
+    ) -> MockL1 {
+        let events = block_hashes.into_iter().map(given_event).collect();
+        given_l1_with_events(events, starting_from_height)
+    }
+
+    fn given_l1_with_events(
+        events: Vec<FuelBlockCommittedOnL1>,
         starting_from_height: L1Height,
-    ) -> MockContract {
-        let mut contract = MockContract::new();
+    ) -> MockL1 {
+        let mut l1 = MockL1::new();
         let event_streamer = Box::new(given_event_streamer_w_events(events));
-        contract
+        l1.contract
             .expect_event_streamer()
             .with(predicate::eq(starting_from_height))
             .return_once(move |_| event_streamer);
-        contract
+        l1.api
+            .expect_block_hash()
+            .returning(|_| Ok(Some(EVENT_L1_BLOCK_HASH)));
+        l1
     }
 
-    fn given_event_streamer_w_events(events: Vec<[u8; 32]>) -> MockEventStreamer {
+    fn given_event_streamer_w_events(events: Vec<FuelBlockCommittedOnL1>) -> MockEventStreamer {
         let mut streamer = MockEventStreamer::new();
-        let events = events
-            .into_iter()
-            .map(|block_hash| FuelBlockCommittedOnL1 {
-                fuel_block_hash: block_hash,
-                commit_height: U256::default(),
-            })
-            .map(Ok)
-            .collect::<Vec<_>>();
+        let events = events.into_iter().map(Ok).collect::<Vec<_>>();
         streamer
             .expect_establish_stream()
             .return_once(move || Ok(Box::pin(stream::iter(events))));
@@ -1,21 +1,233 @@
-use metrics::HealthChecker;
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use metrics::{HealthCheck, HealthChecker};
+use ports::storage::Storage;
 use serde::Serialize;
 
+use crate::{Leadership, WalletBalances, WalletReport};
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    Healthy,
+    /// Working, but in need of attention, e.g. a wallet running low.
+    Degraded,
+    Unhealthy,
+}
+
 #[derive(Debug, Serialize)]
 pub struct HealthReport {
+    status: HealthStatus,
     fuel_connection_up: bool,
     eth_connection_healthy: bool,
+    wallet_balances_above_threshold: bool,
+    /// Whether this replica leads and so writes to L1. Followers are just as healthy.
+    leader: bool,
 }
 
 impl HealthReport {
+    /// A degraded committer is still healthy.
     pub fn is_healthy(&self) -> bool {
         self.fuel_connection_up && self.eth_connection_healthy
     }
+
+    pub fn is_degraded(&self) -> bool {
+        self.is_healthy() && !self.wallet_balances_above_threshold
+    }
+}
+
+/// Everything that goes into deciding whether the committer can do its work, broken down per
+/// component.
+#[derive(Debug, Serialize)]
+pub struct ReadinessReport {
+    status: HealthStatus,
+    database: ComponentReport,
+    fuel_connection_up: bool,
+    eth_connection_healthy: bool,
+    wallet_balances_above_threshold: bool,
+    wallets: BTreeMap<&'static str, WalletReport>,
+    leader: bool,
+    runners: BTreeMap<&'static str, ComponentReport>,
+}
+
+impl ReadinessReport {
+    /// Failing and stale runners are reported but don't make the committer unready. Failing ones
+    /// retry on their own, and taking the replica out of service doesn't get a stale one going.
+    pub fn is_ready(&self) -> bool {
+        self.database.healthy && self.fuel_connection_up && self.eth_connection_healthy
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ComponentReport {
+    /// Whether the last attempt succeeded. Components that have yet to try are healthy.
+    pub healthy: bool,
+    /// Unix timestamp [s] of the last success.
+    pub last_success: Option<u64>,
+    pub seconds_since_last_success: Option<u64>,
+    pub last_error: Option<String>,
+    pub consecutive_failures: u32,
+    /// Times the component panicked and was started over.
+    pub restarts: u64,
+    /// Paused by an operator.
+    pub paused: bool,
+    /// Went without succeeding for longer than its age limit, e.g. because it hangs.
+    pub stale: bool,
+}
+
+/// How a component fared the last time it tried to do its work. Clones share the record.
+#[derive(Debug, Clone, Default)]
+pub struct ComponentHealth {
+    state: Arc<Mutex<ComponentState>>,
+}
+
+#[derive(Debug, Default)]
+struct ComponentState {
+    last_success: Option<SystemTime>,
+    last_error: Option<String>,
+    consecutive_failures: u32,
+    restarts: u64,
+    paused: bool,
+    max_age: Option<Duration>,
+    // when the component last had nothing to do, e.g. while paused, or started being aged
+    last_idle: Option<SystemTime>,
+}
+
+impl ComponentHealth {
+    pub fn note_success(&self) {
+        let mut state = self.lock();
+        state.last_success = Some(SystemTime::now());
+        state.consecutive_failures = 0;
+    }
+
+    pub fn note_failure(&self, error: impl Display) {
+        let mut state = self.lock();
+        state.last_error = Some(error.to_string());
+        state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+    }
+
+    /// For iterations skipped as there was nothing to do, they don't age the component.
+    pub fn note_idle(&self) {
+        self.lock().last_idle = Some(SystemTime::now());
+    }
+
+    /// Reports the component as stale once it goes `max_age` without succeeding or being idle.
+    /// Never if `None`.
+    pub fn set_max_age(&self, max_age: Option<Duration>) {
+        let mut state = self.lock();
+        state.max_age = max_age;
+        state.last_idle.get_or_insert_with(SystemTime::now);
+    }
+
+    pub fn note_panic(&self, message: impl Display) {
+        self.note_failure(format!("panicked: {message}"));
+        self.lock().restarts += 1;
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.lock().paused = paused;
+    }
+
+    #[must_use]
+    pub fn is_paused(&self) -> bool {
+        self.lock().paused
+    }
+
+    #[must_use]
+    pub fn report(&self) -> ComponentReport {
+        let state = self.lock();
+        let now = SystemTime::now();
+
+        let last_alive = state.last_success.max(state.last_idle);
+        let stale = state.max_age.is_some_and(|max_age| {
+            last_alive.is_some_and(|at| now.duration_since(at).unwrap_or_default() > max_age)
+        });
+
+        ComponentReport {
+            healthy: state.consecutive_failures == 0,
+            last_success: state.last_success.map(|at| unix_seconds(at, UNIX_EPOCH)),
+            seconds_since_last_success: state.last_success.map(|at| unix_seconds(now, at)),
+            last_error: state.last_error.clone(),
+            consecutive_failures: state.consecutive_failures,
+            restarts: state.restarts,
+            paused: state.paused,
+            stale,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ComponentState> {
+        self.state
+            .lock()
+            .expect("component health lock not to be poisoned")
+    }
+}
+
+// whole seconds from `earlier` to `later`, 0 if the clock went backwards
+fn unix_seconds(later: SystemTime, earlier: SystemTime) -> u64 {
+    later
+        .duration_since(earlier)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+/// The [`ComponentHealth`] of every runner, by name.
+#[derive(Debug, Clone, Default)]
+pub struct RunnerHealth {
+    runners: Arc<Mutex<BTreeMap<&'static str, ComponentHealth>>>,
+}
+
+impl RunnerHealth {
+    /// Starts tracking the runner called `name`, returning the record it should update.
+    pub fn track(&self, name: &'static str) -> ComponentHealth {
+        self.lock().entry(name).or_default().clone()
+    }
+
+    /// Finds a tracked runner by name, ignoring case, spaces and underscores, so that
+    /// `block_committer` finds the "Block Committer".
+    #[must_use]
+    pub fn find(&self, name: &str) -> Option<(&'static str, ComponentHealth)> {
+        let normalize = |name: &str| {
+            name.chars()
+                .filter(|c| !matches!(c, ' ' | '_' | '-'))
+                .map(|c| c.to_ascii_lowercase())
+                .collect::<String>()
+        };
+        let name = normalize(name);
+
+        self.lock()
+            .iter()
+            .find(|(tracked, _)| normalize(tracked) == name)
+            .map(|(tracked, health)| (*tracked, health.clone()))
+    }
+
+    #[must_use]
+    pub fn report(&self) -> BTreeMap<&'static str, ComponentReport> {
+        self.lock()
+            .iter()
+            .map(|(name, health)| (*name, health.report()))
+            .collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<&'static str, ComponentHealth>> {
+        self.runners
+            .lock()
+            .expect("runner health lock not to be poisoned")
+    }
 }
 
 pub struct HealthReporter {
     fuel_connection: HealthChecker,
     eth_connection: HealthChecker,
+    wallet_balances: Option<WalletBalances>,
+    storage: Option<Arc<dyn Storage>>,
+    database: ComponentHealth,
+    runners: RunnerHealth,
+    leadership: Option<Leadership>,
 }
 
 impl HealthReporter {
@@ -24,14 +236,265 @@ impl HealthReporter {
         Self {
             fuel_connection: fuel_health_check,
             eth_connection: eth_health_check,
+            wallet_balances: None,
+            storage: None,
+            database: ComponentHealth::default(),
+            runners: RunnerHealth::default(),
+            leadership: None,
         }
     }
 
+    /// Reports `wallet_balances`, and the committer as degraded while any is below its threshold.
+    #[must_use]
+    pub fn with_wallet_balances(mut self, wallet_balances: WalletBalances) -> Self {
+        self.wallet_balances = Some(wallet_balances);
+        self
+    }
+
+    /// Pings `storage` for every readiness report.
+    #[must_use]
+    pub fn with_storage(mut self, storage: impl Storage + 'static) -> Self {
+        self.storage = Some(Arc::new(storage));
+        self
+    }
+
+    #[must_use]
+    pub fn with_runners(mut self, runners: RunnerHealth) -> Self {
+        self.runners = runners;
+        self
+    }
+
+    /// Reports whether this replica holds `leadership`. Without it, it is taken to lead.
+    #[must_use]
+    pub fn with_leadership(mut self, leadership: Leadership) -> Self {
+        self.leadership = Some(leadership);
+        self
+    }
+
     #[must_use]
     pub fn generate_report(&self) -> HealthReport {
-        HealthReport {
+        let mut report = HealthReport {
+            status: HealthStatus::Healthy,
+            fuel_connection_up: self.fuel_connection.healthy(),
+            eth_connection_healthy: self.eth_connection.healthy(),
+            wallet_balances_above_threshold: self.wallet_balances_above_threshold(),
+            leader: self.is_leading(),
+        };
+
+        report.status = status(report.is_healthy(), report.is_degraded());
+
+        report
+    }
+
+    pub async fn readiness_report(&self) -> ReadinessReport {
+        if let Some(storage) = &self.storage {
+            match storage.ping().await {
+                Ok(()) => self.database.note_success(),
+                Err(err) => self.database.note_failure(err),
+            }
+        }
+
+        let mut report = ReadinessReport {
+            status: HealthStatus::Healthy,
+            database: self.database.report(),
             fuel_connection_up: self.fuel_connection.healthy(),
             eth_connection_healthy: self.eth_connection.healthy(),
+            wallet_balances_above_threshold: self.wallet_balances_above_threshold(),
+            wallets: self
+                .wallet_balances
+                .as_ref()
+                .map(WalletBalances::report)
+                .unwrap_or_default(),
+            leader: self.is_leading(),
+            runners: self.runners.report(),
+        };
+
+        let degraded = !report.wallet_balances_above_threshold
+            || report
+                .runners
+                .values()
+                .any(|runner| !runner.healthy || runner.stale);
+        report.status = status(report.is_ready(), degraded);
+
+        report
+    }
+
+    fn is_leading(&self) -> bool {
+        self.leadership
+            .as_ref()
+            .map_or(true, Leadership::is_leading)
+    }
+
+    fn wallet_balances_above_threshold(&self) -> bool {
+        self.wallet_balances
+            .as_ref()
+            .map_or(true, |check| check.healthy())
+    }
+}
+
+fn status(healthy: bool, degraded: bool) -> HealthStatus {
+    if !healthy {
+        HealthStatus::Unhealthy
+    } else if degraded {
+        HealthStatus::Degraded
+    } else {
+        HealthStatus::Healthy
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockall::predicate::eq;
+    use ports::{
+        storage::{Error, MockStorage},
+        types::{WalletRole, U256},
+    };
+
+    use super::*;
+    use crate::WalletBalanceTracker;
+
+    struct FixedHealth(bool);
+
+    impl HealthCheck for FixedHealth {
+        fn healthy(&self) -> bool {
+            self.0
         }
     }
+
+    #[tokio::test]
+    async fn unreachable_database_makes_committer_unready() {
+        // given
+        let mut storage = MockStorage::new();
+        storage
+            .expect_ping()
+            .returning(|| Box::pin(async { Err(Error::Database("connection refused".into())) }));
+
+        let reporter =
+            HealthReporter::new(Box::new(FixedHealth(true)), Box::new(FixedHealth(true)))
+                .with_storage(storage);
+
+        // when
+        let report = reporter.readiness_report().await;
+
+        // then
+        assert!(!report.is_ready());
+        assert_eq!(report.status, HealthStatus::Unhealthy);
+        assert_eq!(
+            report.database.last_error.as_deref(),
+            Some("db response: connection refused")
+        );
+    }
+
+    #[tokio::test]
+    async fn failing_runner_degrades_readiness() {
+        // given
+        let runners = RunnerHealth::default();
+        let committer = runners.track("Block Committer");
+        let listener = runners.track("Commit Listener");
+        committer.note_success();
+        listener.note_success();
+        listener.note_failure("rpc timed out");
+
+        let reporter =
+            HealthReporter::new(Box::new(FixedHealth(true)), Box::new(FixedHealth(true)))
+                .with_runners(runners);
+
+        // when
+        let report = reporter.readiness_report().await;
+
+        // then
+        assert!(report.is_ready());
+        assert_eq!(report.status, HealthStatus::Degraded);
+
+        let committer = &report.runners["Block Committer"];
+        assert!(committer.healthy);
+        assert!(committer.last_success.is_some());
+
+        let listener = &report.runners["Commit Listener"];
+        assert!(!listener.healthy);
+        assert!(listener.last_success.is_some());
+        assert_eq!(listener.last_error.as_deref(), Some("rpc timed out"));
+    }
+
+    #[tokio::test]
+    async fn readiness_reports_wallet_balances_and_thresholds() {
+        // given
+        let gwei = |amount: u64| U256::from(amount) * U256::from(1_000_000_000);
+        let mut l1 = ports::l1::MockApi::new();
+        l1.expect_balance()
+            .with(eq(WalletRole::Main))
+            .returning(move |_| Ok(gwei(5)));
+        l1.expect_balance()
+            .with(eq(WalletRole::Blob))
+            .returning(move |_| Ok(gwei(20)));
+        let mut tracker = WalletBalanceTracker::new(l1)
+            .track(WalletRole::Main, gwei(10))
+            .track(WalletRole::Blob, gwei(10));
+        tracker.update_balance().await.unwrap();
+
+        let reporter =
+            HealthReporter::new(Box::new(FixedHealth(true)), Box::new(FixedHealth(true)))
+                .with_wallet_balances(tracker.balances());
+
+        // when
+        let report = reporter.readiness_report().await;
+
+        // then
+        assert_eq!(report.status, HealthStatus::Degraded);
+        assert_eq!(
+            report.wallets["main"],
+            WalletReport {
+                balance_gwei: Some(5),
+                low_balance_threshold_gwei: 10,
+                above_threshold: false,
+            }
+        );
+        assert_eq!(
+            report.wallets["blob"],
+            WalletReport {
+                balance_gwei: Some(20),
+                low_balance_threshold_gwei: 10,
+                above_threshold: true,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn stale_runner_degrades_readiness() {
+        // given
+        let runners = RunnerHealth::default();
+        let committer = runners.track("Block Committer");
+        committer.set_max_age(Some(Duration::ZERO));
+        std::thread::sleep(Duration::from_millis(1));
+
+        let reporter =
+            HealthReporter::new(Box::new(FixedHealth(true)), Box::new(FixedHealth(true)))
+                .with_runners(runners);
+
+        // when
+        let report = reporter.readiness_report().await;
+
+        // then
+        assert!(report.is_ready());
+        assert_eq!(report.status, HealthStatus::Degraded);
+        assert!(report.runners["Block Committer"].stale);
+    }
+
+    #[tokio::test]
+    async fn follower_is_reported_as_healthy() {
+        // given
+        let reporter =
+            HealthReporter::new(Box::new(FixedHealth(true)), Box::new(FixedHealth(true)))
+                .with_leadership(Leadership::default());
+
+        // when
+        let health = reporter.generate_report();
+        let readiness = reporter.readiness_report().await;
+
+        // then
+        assert!(!health.leader);
+        assert_eq!(health.status, HealthStatus::Healthy);
+        assert!(!readiness.leader);
+        assert_eq!(readiness.status, HealthStatus::Healthy);
+    }
 }
@@ -0,0 +1,22 @@
+use metrics::prometheus::{exponential_buckets, Histogram, HistogramOpts};
+use ports::types::{DateTime, Utc};
+
+/// A histogram of latencies ranging from a second to a few hours.
+pub(crate) fn latency_histogram(name: &str, help: &str) -> Histogram {
+    let buckets = exponential_buckets(1.0, 2.0, 15).expect("latency buckets to be valid");
+
+    Histogram::with_opts(HistogramOpts::new(name, help).buckets(buckets))
+        .unwrap_or_else(|_| panic!("{name} metric to be correctly configured"))
+}
+
+/// 0 if `later` is in fact earlier, e.g. due to clock skew between the fuel node and us.
+pub(crate) fn seconds_between(earlier: DateTime<Utc>, later: DateTime<Utc>) -> f64 {
+    (later - earlier)
+        .to_std()
+        .map(|elapsed| elapsed.as_secs_f64())
+        .unwrap_or_default()
+}
+
+pub(crate) fn unix_time(time: tai64::Tai64) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(time.to_unix(), 0)
+}
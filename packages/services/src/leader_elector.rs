@@ -0,0 +1,367 @@
+use std::{
+    num::NonZeroU32,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use futures::future::BoxFuture;
+use metrics::{
+    prometheus::{core::Collector, IntCounter, IntGauge},
+    RegistersMetrics,
+};
+use ports::{
+    l1::{Api, Contract, EventStreamer},
+    storage::LeaderElection,
+    types::{L1Height, TransactionResponse, ValidatedFuelBlock, WalletRole, U256},
+};
+use tracing::{info, warn};
+
+use crate::{Result, Runner};
+
+/// Whether this replica leads, i.e. is the one to write to L1. Clones share the flag, which starts
+/// out unset until the first election.
+#[derive(Debug, Clone, Default)]
+pub struct Leadership {
+    leading: Arc<AtomicBool>,
+}
+
+impl Leadership {
+    #[must_use]
+    pub fn is_leading(&self) -> bool {
+        self.leading.load(Ordering::Relaxed)
+    }
+
+    // returns whether this replica led before
+    pub(crate) fn set_leading(&self, leading: bool) -> bool {
+        self.leading.swap(leading, Ordering::Relaxed)
+    }
+}
+
+/// Wraps an L1 adapter, confirming with the election right before every write that this replica
+/// still leads. Runners only start an iteration while leading, but the lead can be lost while one
+/// is underway, e.g. because the connection holding it dropped, upon which another replica takes
+/// over.
+///
+/// The lead can still be lost between the confirmation and the tx reaching L1, so a replica that
+/// loses it at that very moment may send one last tx alongside the new leader's.
+#[derive(Clone)]
+pub struct LeadFenced<L1, E> {
+    l1: L1,
+    leadership: Leadership,
+    election: E,
+}
+
+impl<L1, E> LeadFenced<L1, E> {
+    pub fn new(l1: L1, leadership: Leadership, election: E) -> Self {
+        Self {
+            l1,
+            leadership,
+            election,
+        }
+    }
+}
+
+impl<L1, E> LeadFenced<L1, E>
+where
+    E: LeaderElection,
+{
+    async fn confirm_lead(&self) -> ports::l1::Result<()> {
+        let confirmed = self
+            .election
+            .confirm_lead()
+            .await
+            .map_err(|e| ports::l1::Error::Other(format!("could not confirm the lead: {e}")))?;
+
+        if confirmed && self.leadership.is_leading() {
+            Ok(())
+        } else {
+            Err(ports::l1::Error::Other(
+                "not writing to L1, this replica no longer leads".to_string(),
+            ))
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<L1, E> Contract for LeadFenced<L1, E>
+where
+    L1: Contract,
+    E: LeaderElection,
+{
+    async fn submit(&self, block: ValidatedFuelBlock) -> ports::l1::Result<()> {
+        self.confirm_lead().await?;
+        self.l1.submit(block).await
+    }
+
+    fn event_streamer(&self, height: L1Height) -> Box<dyn EventStreamer + Send + Sync> {
+        self.l1.event_streamer(height)
+    }
+
+    fn commit_interval(&self) -> NonZeroU32 {
+        self.l1.commit_interval()
+    }
+}
+
+#[async_trait::async_trait]
+impl<L1, E> Api for LeadFenced<L1, E>
+where
+    L1: Api + Send + Sync,
+    E: LeaderElection,
+{
+    async fn submit_l2_state(&self, state_data: Vec<u8>) -> ports::l1::Result<[u8; 32]> {
+        self.confirm_lead().await?;
+        self.l1.submit_l2_state(state_data).await
+    }
+
+    async fn get_block_number(&self) -> ports::l1::Result<L1Height> {
+        self.l1.get_block_number().await
+    }
+
+    async fn block_hash(&self, height: L1Height) -> ports::l1::Result<Option<[u8; 32]>> {
+        self.l1.block_hash(height).await
+    }
+
+    async fn balance(&self, wallet: WalletRole) -> ports::l1::Result<U256> {
+        self.l1.balance(wallet).await
+    }
+
+    async fn get_transaction_response(
+        &self,
+        tx_hash: [u8; 32],
+    ) -> ports::l1::Result<Option<TransactionResponse>> {
+        self.l1.get_transaction_response(tx_hash).await
+    }
+}
+
+type OnLead = Box<dyn Fn() -> BoxFuture<'static, Result<()>> + Send + Sync>;
+
+/// Keeps trying to take the lead, and confirming it is still held once it is.
+pub struct LeaderElector<E> {
+    election: E,
+    leadership: Leadership,
+    on_lead: Option<OnLead>,
+    metrics: Metrics,
+}
+
+impl<E> LeaderElector<E> {
+    pub fn new(election: E, leadership: Leadership) -> Self {
+        Self {
+            election,
+            leadership,
+            on_lead: None,
+            metrics: Metrics::default(),
+        }
+    }
+
+    /// Runs `on_lead` every time the lead is taken, before leading. Should it fail, the lead is
+    /// taken on the next run instead.
+    pub fn with_on_lead<F>(mut self, on_lead: F) -> Self
+    where
+        F: Fn() -> BoxFuture<'static, Result<()>> + Send + Sync + 'static,
+    {
+        self.on_lead = Some(Box::new(on_lead));
+        self
+    }
+
+    fn note_leading(&self, leading: bool) {
+        let led = self.leadership.set_leading(leading);
+        self.metrics.leader.set(i64::from(leading));
+
+        match (led, leading) {
+            (false, true) => {
+                info!("took the lead");
+                self.metrics.leadership_changes.inc();
+            }
+            (true, false) => {
+                warn!("lost the lead");
+                self.metrics.leadership_changes.inc();
+            }
+            _ => {}
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<E> Runner for LeaderElector<E>
+where
+    E: LeaderElection,
+{
+    async fn run(&mut self) -> Result<()> {
+        let won = match self.election.try_lead().await {
+            Ok(won) => won,
+            Err(e) => {
+                // the lead can't be vouched for without the election
+                self.note_leading(false);
+                return Err(e.into());
+            }
+        };
+
+        if won && !self.leadership.is_leading() {
+            if let Some(on_lead) = &self.on_lead {
+                on_lead().await?;
+            }
+        }
+
+        self.note_leading(won);
+        Ok(())
+    }
+}
+
+impl<E> RegistersMetrics for LeaderElector<E> {
+    fn metrics(&self) -> Vec<Box<dyn Collector>> {
+        vec![
+            Box::new(self.metrics.leader.clone()),
+            Box::new(self.metrics.leadership_changes.clone()),
+        ]
+    }
+}
+
+#[derive(Clone)]
+struct Metrics {
+    leader: IntGauge,
+    leadership_changes: IntCounter,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        let leader = IntGauge::new(
+            "leader",
+            "1 if this replica leads and writes to L1, 0 otherwise.",
+        )
+        .expect("leader metric to be correctly configured");
+
+        let leadership_changes = IntCounter::new(
+            "leadership_changes",
+            "Number of times this replica took or lost the lead.",
+        )
+        .expect("leadership_changes metric to be correctly configured");
+
+        Self {
+            leader,
+            leadership_changes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ports::storage::{Error, MockLeaderElection};
+
+    use super::*;
+
+    fn given_election(outcomes: Vec<ports::storage::Result<bool>>) -> MockLeaderElection {
+        let mut election = MockLeaderElection::new();
+        let mut outcomes = outcomes.into_iter();
+        election.expect_try_lead().returning(move || {
+            let outcome = outcomes.next().expect("election to be scripted");
+            Box::pin(async move { outcome })
+        });
+
+        election
+    }
+
+    #[tokio::test]
+    async fn leads_while_the_election_is_won() {
+        // given
+        let leadership = Leadership::default();
+        let election = given_election(vec![Ok(false), Ok(true), Ok(true), Ok(false)]);
+        let mut elector = LeaderElector::new(election, leadership.clone());
+
+        // when
+        let mut led = vec![];
+        for _ in 0..4 {
+            elector.run().await.unwrap();
+            led.push(leadership.is_leading());
+        }
+
+        // then
+        assert_eq!(led, vec![false, true, true, false]);
+        assert_eq!(elector.metrics.leadership_changes.get(), 2);
+        assert_eq!(elector.metrics.leader.get(), 0);
+    }
+
+    #[tokio::test]
+    async fn leads_only_once_ready_to() {
+        // given
+        let leadership = Leadership::default();
+        let election = given_election(vec![Ok(true), Ok(true), Ok(true)]);
+        let attempts = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let mut elector = LeaderElector::new(election, leadership.clone()).with_on_lead({
+            let attempts = Arc::clone(&attempts);
+            move || {
+                let first = attempts.fetch_add(1, Ordering::Relaxed) == 0;
+                Box::pin(async move {
+                    if first {
+                        Err(crate::Error::Other("not ready".to_string()))
+                    } else {
+                        Ok(())
+                    }
+                })
+            }
+        });
+
+        // when
+        let first = elector.run().await;
+        let led_after_first = leadership.is_leading();
+        let second = elector.run().await;
+        let third = elector.run().await;
+
+        // then
+        assert!(first.is_err());
+        assert!(!led_after_first);
+        assert!(second.is_ok() && third.is_ok());
+        assert!(leadership.is_leading());
+        // not again while leading
+        assert_eq!(attempts.load(Ordering::Relaxed), 2);
+    }
+
+    #[tokio::test]
+    async fn gives_up_the_lead_when_the_election_fails() {
+        // given
+        let leadership = Leadership::default();
+        let election = given_election(vec![
+            Ok(true),
+            Err(Error::Database("connection reset".to_string())),
+        ]);
+        let mut elector = LeaderElector::new(election, leadership.clone());
+        elector.run().await.unwrap();
+
+        // when
+        let result = elector.run().await;
+
+        // then
+        assert!(result.is_err());
+        assert!(!leadership.is_leading());
+    }
+
+    #[tokio::test]
+    async fn writes_to_l1_only_while_the_lead_is_confirmed() {
+        // given
+        let leadership = Leadership::default();
+        leadership.set_leading(true);
+
+        let mut election = MockLeaderElection::new();
+        let mut confirmations = vec![true, false].into_iter();
+        election.expect_confirm_lead().returning(move || {
+            let confirmed = confirmations.next().expect("confirmations to be scripted");
+            Box::pin(async move { Ok(confirmed) })
+        });
+
+        let mut l1 = ports::l1::MockApi::new();
+        l1.expect_submit_l2_state()
+            .times(1)
+            .returning(|_| Ok([1; 32]));
+
+        let fenced = LeadFenced::new(l1, leadership, election);
+
+        // when
+        let while_leading = fenced.submit_l2_state(vec![1]).await;
+        let after_losing_the_lead = fenced.submit_l2_state(vec![2]).await;
+
+        // then
+        assert!(while_leading.is_ok());
+        assert!(after_losing_the_lead.is_err());
+    }
+}
@@ -1,22 +1,34 @@
 #![deny(unused_crate_dependencies)]
 
+mod admin;
 mod block_committer;
 mod commit_listener;
 mod health_reporter;
+mod latency;
+mod leader_elector;
+mod query_reporter;
 mod state_committer;
 mod state_importer;
 mod state_listener;
 mod status_reporter;
+mod supervisor;
 mod wallet_balance_tracker;
 
-pub use block_committer::BlockCommitter;
+pub use admin::{Admin, LeaderOnly};
+pub use block_committer::{BlockCommitter, ResubmissionQueue};
 pub use commit_listener::CommitListener;
-pub use health_reporter::HealthReporter;
+pub use health_reporter::{ComponentHealth, HealthReporter, RunnerHealth};
+pub use leader_elector::{LeadFenced, LeaderElector, Leadership};
+pub use query_reporter::{
+    BlockLookup, BlockSubmissionView, FragmentView, QueryReporter, StateSubmissionView,
+    SubmissionTxView, TxFragmentView,
+};
 pub use state_committer::StateCommitter;
 pub use state_importer::StateImporter;
 pub use state_listener::StateListener;
 pub use status_reporter::StatusReporter;
-pub use wallet_balance_tracker::WalletBalanceTracker;
+pub use supervisor::{SupervisedRunner, Supervisor};
+pub use wallet_balance_tracker::{WalletBalanceTracker, WalletBalances, WalletReport};
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -31,6 +43,9 @@ pub enum Error {
 
     #[error("Block validation error: {0}")]
     BlockValidation(String),
+
+    #[error("Unsupported consensus: {0}")]
+    UnsupportedConsensus(String),
 }
 
 impl From<ports::l1::Error> for Error {
@@ -55,6 +70,7 @@ impl From<validator::Error> for Error {
     fn from(error: validator::Error) -> Self {
         match error {
             validator::Error::BlockValidation(e) => Self::BlockValidation(e),
+            validator::Error::UnsupportedConsensus(e) => Self::UnsupportedConsensus(e),
         }
     }
 }
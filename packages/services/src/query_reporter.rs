@@ -0,0 +1,282 @@
+use ports::{
+    storage::Storage,
+    types::{
+        BlockSubmission, FragmentStatus, Page, StateSubmissionDetails, SubmissionTxDetails,
+        TransactionState,
+    },
+};
+use serde::Serialize;
+
+use crate::Result;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockLookup {
+    Height(u32),
+    Hash([u8; 32]),
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct BlockSubmissionView {
+    pub fuel_block_hash: String,
+    pub fuel_block_height: u32,
+    pub completed: bool,
+    pub submittal_height: u64,
+}
+
+impl From<BlockSubmission> for BlockSubmissionView {
+    fn from(submission: BlockSubmission) -> Self {
+        Self {
+            fuel_block_hash: hex::encode(submission.block_hash),
+            fuel_block_height: submission.block_height,
+            completed: submission.completed,
+            submittal_height: submission.submittal_height.into(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct StateSubmissionView {
+    pub fuel_block_hash: String,
+    pub fuel_block_height: u32,
+    pub fragments: Vec<FragmentView>,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct FragmentView {
+    pub id: u32,
+    pub fragment_idx: u32,
+    pub size: u64,
+    /// RFC 3339.
+    pub created_at: String,
+    pub status: &'static str,
+}
+
+impl From<StateSubmissionDetails> for StateSubmissionView {
+    fn from(details: StateSubmissionDetails) -> Self {
+        Self {
+            fuel_block_hash: hex::encode(details.submission.block_hash),
+            fuel_block_height: details.submission.block_height,
+            fragments: details
+                .fragments
+                .into_iter()
+                .map(|fragment| FragmentView {
+                    id: fragment.id,
+                    fragment_idx: fragment.fragment_idx,
+                    size: fragment.size,
+                    created_at: fragment.created_at.to_rfc3339(),
+                    status: fragment_status_name(fragment.status),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct SubmissionTxView {
+    pub id: u32,
+    pub hash: String,
+    pub state: &'static str,
+    pub fragments: Vec<TxFragmentView>,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct TxFragmentView {
+    pub fragment_id: u32,
+    pub fragment_idx: u32,
+    /// Height of the fuel block whose state the fragment is part of.
+    pub fuel_block_height: u32,
+}
+
+impl From<SubmissionTxDetails> for SubmissionTxView {
+    fn from(details: SubmissionTxDetails) -> Self {
+        Self {
+            id: details.tx.id.expect("stored txs to have an id"),
+            hash: hex::encode(details.tx.hash),
+            state: tx_state_name(&details.tx.state),
+            fragments: details
+                .fragments
+                .into_iter()
+                .map(|fragment| TxFragmentView {
+                    fragment_id: fragment.fragment_id,
+                    fragment_idx: fragment.fragment_idx,
+                    fuel_block_height: fragment.submission_block_height,
+                })
+                .collect(),
+        }
+    }
+}
+
+fn fragment_status_name(status: FragmentStatus) -> &'static str {
+    match status {
+        FragmentStatus::Unsubmitted => "unsubmitted",
+        FragmentStatus::Pending => "pending",
+        FragmentStatus::Finalized => "finalized",
+    }
+}
+
+fn tx_state_name(state: &TransactionState) -> &'static str {
+    match state {
+        TransactionState::Pending => "pending",
+        TransactionState::Finalized => "finalized",
+        TransactionState::Failed => "failed",
+    }
+}
+
+/// Looks up what the committer has stored, for debugging.
+pub struct QueryReporter<Db> {
+    storage: Db,
+}
+
+impl<Db> QueryReporter<Db> {
+    pub const MAX_PAGE_LIMIT: u32 = 100;
+
+    pub fn new(storage: Db) -> Self {
+        Self { storage }
+    }
+
+    fn clamp(page: Page) -> Page {
+        Page {
+            limit: page.limit.min(Self::MAX_PAGE_LIMIT),
+            ..page
+        }
+    }
+}
+
+impl<Db> QueryReporter<Db>
+where
+    Db: Storage,
+{
+    pub async fn block_submissions(&self, page: Page) -> Result<Vec<BlockSubmissionView>> {
+        let submissions = self.storage.block_submissions(Self::clamp(page)).await?;
+
+        Ok(submissions.into_iter().map(Into::into).collect())
+    }
+
+    pub async fn block_submission(
+        &self,
+        lookup: BlockLookup,
+    ) -> Result<Option<BlockSubmissionView>> {
+        let submission = match lookup {
+            BlockLookup::Height(height) => self.storage.block_submission_by_height(height).await?,
+            BlockLookup::Hash(hash) => self.storage.block_submission_by_hash(hash).await?,
+        };
+
+        Ok(submission.map(Into::into))
+    }
+
+    pub async fn state_submissions(&self, page: Page) -> Result<Vec<StateSubmissionView>> {
+        let submissions = self.storage.state_submissions(Self::clamp(page)).await?;
+
+        Ok(submissions.into_iter().map(Into::into).collect())
+    }
+
+    /// Ordered as they will be submitted in.
+    pub async fn unsubmitted_fragments(&self, page: Page) -> Result<Vec<FragmentView>> {
+        let page = Self::clamp(page);
+        let fragments = self
+            .storage
+            .get_unsubmitted_fragments((page.offset + page.limit) as usize)
+            .await?;
+
+        Ok(fragments
+            .into_iter()
+            .skip(page.offset as usize)
+            .map(|fragment| FragmentView {
+                id: fragment.id.expect("fragments from DB must have `id`"),
+                fragment_idx: fragment.fragment_idx,
+                size: fragment.data.len() as u64,
+                created_at: fragment.created_at.to_rfc3339(),
+                status: fragment_status_name(FragmentStatus::Unsubmitted),
+            })
+            .collect())
+    }
+
+    pub async fn submission_txs(
+        &self,
+        page: Page,
+        state: Option<TransactionState>,
+    ) -> Result<Vec<SubmissionTxView>> {
+        let txs = self
+            .storage
+            .submission_txs(Self::clamp(page), state)
+            .await?;
+
+        Ok(txs.into_iter().map(Into::into).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockall::predicate::eq;
+    use ports::{
+        storage::MockStorage,
+        types::{StateFragment, Utc},
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn page_limit_is_capped() {
+        // given
+        let mut storage = MockStorage::new();
+        storage
+            .expect_block_submissions()
+            .with(eq(Page {
+                offset: 10,
+                limit: QueryReporter::<MockStorage>::MAX_PAGE_LIMIT,
+                before: None,
+            }))
+            .returning(|_| Box::pin(async { Ok(vec![]) }));
+        let reporter = QueryReporter::new(storage);
+
+        // when
+        let submissions = reporter
+            .block_submissions(Page {
+                offset: 10,
+                limit: 10_000,
+                before: None,
+            })
+            .await
+            .unwrap();
+
+        // then
+        assert!(submissions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn unsubmitted_fragments_skip_the_offset() {
+        // given
+        let mut storage = MockStorage::new();
+        storage
+            .expect_get_unsubmitted_fragments()
+            .with(eq(3))
+            .returning(|max_fragments| {
+                let fragments = (0..max_fragments as u32)
+                    .map(|id| StateFragment {
+                        id: Some(id),
+                        submission_id: Some(1),
+                        fragment_idx: id,
+                        data: vec![0; 10],
+                        created_at: Utc::now(),
+                    })
+                    .collect();
+                Box::pin(async move { Ok(fragments) })
+            });
+        let reporter = QueryReporter::new(storage);
+
+        // when
+        let fragments = reporter
+            .unsubmitted_fragments(Page {
+                offset: 2,
+                limit: 1,
+                before: None,
+            })
+            .await
+            .unwrap();
+
+        // then
+        let ids: Vec<_> = fragments.iter().map(|fragment| fragment.id).collect();
+        assert_eq!(ids, vec![2]);
+        assert_eq!(fragments[0].status, "unsubmitted");
+    }
+}
@@ -1,3 +1,5 @@
+use std::num::NonZeroUsize;
+
 use async_trait::async_trait;
 use ports::storage::Storage;
 use tracing::info;
@@ -7,11 +9,23 @@ use crate::{Result, Runner};
 pub struct StateCommitter<L1, Db> {
     l1_adapter: L1,
     storage: Db,
+    max_fragments_per_tx: NonZeroUsize,
 }
 
 impl<L1, Db> StateCommitter<L1, Db> {
     pub fn new(l1_adapter: L1, storage: Db) -> Self {
-        Self { l1_adapter, storage }
+        Self {
+            l1_adapter,
+            storage,
+            max_fragments_per_tx: NonZeroUsize::new(ports::l1::MAX_BLOBS_PER_BLOCK)
+                .expect("an L1 block carries blobs"),
+        }
+    }
+
+    /// How many fragments, at most, are bundled into a single blob transaction.
+    pub fn with_max_fragments_per_tx(mut self, max_fragments_per_tx: NonZeroUsize) -> Self {
+        self.max_fragments_per_tx = max_fragments_per_tx;
+        self
     }
 }
 
@@ -21,7 +35,10 @@ where
     Db: Storage,
 {
     async fn prepare_fragments(&self) -> Result<(Vec<u32>, Vec<u8>)> {
-        let fragments = self.storage.get_unsubmitted_fragments().await?;
+        let fragments = self
+            .storage
+            .get_unsubmitted_fragments(self.max_fragments_per_tx.get())
+            .await?;
 
         let fragment_ids: Vec<u32> = fragments
             .iter()
@@ -48,7 +65,7 @@ where
     }
 
     async fn is_tx_pending(&self) -> Result<bool> {
-        self.storage.has_pending_txs().await
+        Ok(self.storage.has_pending_txs().await?)
     }
 }
 
@@ -72,8 +89,10 @@ where
 #[cfg(test)]
 mod tests {
     use mockall::predicate;
-    use ports::types::{L1Height, StateFragment, StateSubmission, TransactionResponse, U256};
-    use storage::PostgresProcess;
+    use ports::types::{
+        L1Height, StateFragment, StateSubmission, TransactionResponse, WalletRole, U256,
+    };
+    use storage::{InMemory, PostgresProcess};
 
     use super::*;
 
@@ -99,7 +118,11 @@ mod tests {
             Ok(0.into())
         }
 
-        async fn balance(&self) -> ports::l1::Result<U256> {
+        async fn block_hash(&self, _height: L1Height) -> ports::l1::Result<Option<[u8; 32]>> {
+            Ok(None)
+        }
+
+        async fn balance(&self, _wallet: WalletRole) -> ports::l1::Result<U256> {
             Ok(U256::ZERO)
         }
 
@@ -156,6 +179,40 @@ mod tests {
         // then
         assert!(db.has_pending_txs().await?);
 
+        Ok(())
+    }
+    #[tokio::test]
+    async fn bundles_at_most_the_configured_fragments_per_tx() -> Result<()> {
+        // given
+        let (state, fragment) = given_state();
+        let fragments = (0..3)
+            .map(|idx| StateFragment {
+                fragment_idx: idx,
+                data: vec![idx as u8],
+                ..fragment.clone()
+            })
+            .collect();
+        let db = InMemory::new();
+        db.insert_state_submission(state, fragments).await?;
+
+        let mut l1_mock = MockL1::new();
+        l1_mock
+            .api
+            .expect_submit_l2_state()
+            .with(predicate::eq(vec![0, 1]))
+            .return_once(|_| Ok([1u8; 32]));
+
+        let mut committer = StateCommitter::new(l1_mock, db.clone())
+            .with_max_fragments_per_tx(NonZeroUsize::new(2).unwrap());
+
+        // when
+        committer.run().await.unwrap();
+
+        // then
+        let unsubmitted = db.get_unsubmitted_fragments(usize::MAX).await?;
+        assert_eq!(unsubmitted.len(), 1);
+        assert_eq!(unsubmitted[0].data, vec![2]);
+
         Ok(())
     }
 }
@@ -37,6 +37,13 @@ where
         Ok(latest_block)
     }
 
+    async fn fetch_transactions(&self, block: &FuelBlock) -> Result<Vec<Vec<u8>>> {
+        let transactions = self.fuel_adapter.block_transactions(block).await?;
+        self.block_validator
+            .validate_with_transactions(block, &transactions)?;
+        Ok(transactions)
+    }
+
     async fn check_if_stale(&self, block_height: u32) -> Result<bool> {
         if let Some(submitted_height) = self.last_submitted_block_height().await? {
             return Ok(submitted_height >= block_height);
@@ -45,20 +52,21 @@ where
     }
 
     async fn last_submitted_block_height(&self) -> Result<Option<u32>> {
-        self.storage
+        Ok(self
+            .storage
             .state_submission_w_latest_block()
             .await
-            .map(|submission| submission.map(|s| s.block_height))
+            .map(|submission| submission.map(|s| s.block_height))?)
     }
 
     fn block_to_state_submission(
         &self,
         block: FuelBlock,
+        transactions: Vec<Vec<u8>>,
     ) -> Result<(StateSubmission, Vec<StateFragment>)> {
         use itertools::Itertools;
 
-        let fragments = block
-            .transactions
+        let fragments = transactions
             .iter()
             .flat_map(|tx| tx.iter())
             .chunks(StateFragment::MAX_FRAGMENT_SIZE)
@@ -82,8 +90,8 @@ where
         Ok((submission, fragments))
     }
 
-    async fn import_state(&self, block: FuelBlock) -> Result<()> {
-        let (submission, fragments) = self.block_to_state_submission(block)?;
+    async fn import_state(&self, block: FuelBlock, transactions: Vec<Vec<u8>>) -> Result<()> {
+        let (submission, fragments) = self.block_to_state_submission(block, transactions)?;
         self.storage
             .insert_state_submission(submission, fragments)
             .await?;
@@ -105,9 +113,11 @@ where
             return Ok(());
         }
 
+        let transactions = self.fetch_transactions(&block).await?;
+
         let block_id = block.id;
         let block_height = block.header.height;
-        self.import_state(block).await?;
+        self.import_state(block, transactions).await?;
         info!(
             "Imported state from Fuel block: height: {}, id: {}",
             block_height, block_id
@@ -120,7 +130,9 @@ where
 #[cfg(test)]
 mod tests {
     use fuel_crypto::{Message, SecretKey, Signature};
-    use ports::fuel::{FuelBlock, FuelBlockId, FuelConsensus, FuelHeader, FuelPoAConsensus};
+    use ports::fuel::{
+        FuelBlock, FuelBlockId, FuelBytes32, FuelConsensus, FuelHeader, FuelPoAConsensus,
+    };
     use rand::{rngs::StdRng, SeedableRng};
     use storage::PostgresProcess;
     use validator::BlockValidator;
@@ -132,7 +144,7 @@ mod tests {
     }
 
     fn given_a_block(height: u32, secret_key: &SecretKey) -> FuelBlock {
-        let header = given_header(height);
+        let header = given_header(height, &[given_transaction()]);
 
         let mut hasher = fuel_crypto::Hasher::default();
         hasher.input(header.prev_root.as_ref());
@@ -148,40 +160,70 @@ mod tests {
             id,
             header,
             consensus: FuelConsensus::PoAConsensus(FuelPoAConsensus { signature }),
-            transactions: vec![[2u8; 32].into()],
+            transactions: vec![Default::default()],
             block_producer: Some(secret_key.public_key()),
         }
     }
 
-    fn given_header(height: u32) -> FuelHeader {
-        let application_hash = "0x8b96f712e293e801d53da77113fec3676c01669c6ea05c6c92a5889fce5f649d"
-            .parse()
-            .unwrap();
+    fn given_transaction() -> Vec<u8> {
+        vec![2u8; 32]
+    }
 
-        FuelHeader {
+    fn given_header(height: u32, transactions: &[Vec<u8>]) -> FuelHeader {
+        let mut header = FuelHeader {
             id: Default::default(),
             da_height: Default::default(),
             consensus_parameters_version: Default::default(),
             state_transition_bytecode_version: Default::default(),
-            transactions_count: 1,
+            transactions_count: transactions.len() as u16,
             message_receipt_count: Default::default(),
-            transactions_root: Default::default(),
+            transactions_root: transactions_root(transactions),
             message_outbox_root: Default::default(),
             event_inbox_root: Default::default(),
             height,
             prev_root: Default::default(),
             time: tai64::Tai64(0),
-            application_hash,
+            application_hash: Default::default(),
+        };
+        header.application_hash = application_hash(&header);
+
+        header
+    }
+
+    fn transactions_root(transactions: &[Vec<u8>]) -> FuelBytes32 {
+        let mut calculator = fuel_merkle::binary::root_calculator::MerkleRootCalculator::new();
+        for tx in transactions {
+            calculator.push(tx);
         }
+
+        calculator.root().into()
+    }
+
+    fn application_hash(header: &FuelHeader) -> FuelBytes32 {
+        let mut hasher = fuel_crypto::Hasher::default();
+        hasher.input(header.da_height.to_be_bytes());
+        hasher.input(header.consensus_parameters_version.to_be_bytes());
+        hasher.input(header.state_transition_bytecode_version.to_be_bytes());
+        hasher.input(header.transactions_count.to_be_bytes());
+        hasher.input(header.message_receipt_count.to_be_bytes());
+        hasher.input(header.transactions_root.as_ref());
+        hasher.input(header.message_outbox_root.as_ref());
+        hasher.input(header.event_inbox_root.as_ref());
+
+        hasher.digest()
     }
 
-    fn given_fetcher(block: FuelBlock) -> ports::fuel::MockApi {
+    fn given_fetcher(block: FuelBlock, transactions: Vec<Vec<u8>>) -> ports::fuel::MockApi {
         let mut fetcher = ports::fuel::MockApi::new();
 
         fetcher
             .expect_latest_block()
             .returning(move || Ok(block.clone()));
 
+        fetcher
+            .expect_block_transactions()
+            .returning(move |_| Ok(transactions.clone()));
+
         fetcher
     }
 
@@ -190,7 +232,7 @@ mod tests {
         // given
         let secret_key = given_secret_key();
         let block = given_a_block(1, &secret_key);
-        let fuel_mock = given_fetcher(block);
+        let fuel_mock = given_fetcher(block, vec![given_transaction()]);
         let block_validator = BlockValidator::new(*secret_key.public_key().hash());
 
         let process = PostgresProcess::shared().await.unwrap();
@@ -201,10 +243,33 @@ mod tests {
         importer.run().await.unwrap();
 
         // then
-        let fragments = db.get_unsubmitted_fragments().await?;
+        let fragments = db.get_unsubmitted_fragments(usize::MAX).await?;
         let latest_submission = db.state_submission_w_latest_block().await?.unwrap();
         assert_eq!(fragments.len(), 1);
         assert_eq!(fragments[0].submission_id, latest_submission.id);
+        assert_eq!(fragments[0].data, given_transaction());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn does_not_import_state_if_transactions_do_not_match_header() -> Result<()> {
+        // given
+        let secret_key = given_secret_key();
+        let block = given_a_block(1, &secret_key);
+        let fuel_mock = given_fetcher(block, vec![vec![3u8; 32]]);
+        let block_validator = BlockValidator::new(*secret_key.public_key().hash());
+
+        let process = PostgresProcess::shared().await.unwrap();
+        let db = process.create_random_db().await?;
+        let mut importer = StateImporter::new(db.clone(), fuel_mock, block_validator);
+
+        // when
+        let result = importer.run().await;
+
+        // then
+        assert!(matches!(result, Err(crate::Error::BlockValidation(_))));
+        assert!(db.state_submission_w_latest_block().await?.is_none());
 
         Ok(())
     }
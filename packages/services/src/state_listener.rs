@@ -1,15 +1,16 @@
 use async_trait::async_trait;
 use metrics::{
-    prometheus::{core::Collector, IntGauge, Opts},
+    prometheus::{core::Collector, Histogram, IntGauge, Opts},
     RegistersMetrics,
 };
 use ports::{
     storage::Storage,
     types::{SubmissionTx, TransactionState},
 };
-use tracing::info;
+use tracing::{info, warn};
 
 use super::Runner;
+use crate::latency::{latency_histogram, seconds_between};
 
 pub struct StateListener<L1, Db> {
     l1_adapter: L1,
@@ -61,6 +62,7 @@ where
                 .await?;
 
             info!("Finalized transaction: {}", hex::encode(tx_hash));
+            self.observe_finality_latency(tx_hash).await;
 
             self.metrics
                 .last_eth_block_w_blob
@@ -69,6 +71,22 @@ where
 
         Ok(())
     }
+
+    async fn observe_finality_latency(&self, tx_hash: [u8; 32]) {
+        match self.storage.fragment_finality_times(tx_hash).await {
+            Ok(times) => {
+                for (imported_at, finalized_at) in times {
+                    self.metrics
+                        .fragment_import_to_finalized
+                        .observe(seconds_between(imported_at, finalized_at));
+                }
+            }
+            Err(e) => warn!(
+                "could not look up the fragments of tx {}: {e}",
+                hex::encode(tx_hash)
+            ),
+        }
+    }
 }
 
 #[async_trait]
@@ -93,11 +111,15 @@ where
 #[derive(Clone)]
 struct Metrics {
     last_eth_block_w_blob: IntGauge,
+    fragment_import_to_finalized: Histogram,
 }
 
 impl<L1, Db> RegistersMetrics for StateListener<L1, Db> {
     fn metrics(&self) -> Vec<Box<dyn Collector>> {
-        vec![Box::new(self.metrics.last_eth_block_w_blob.clone())]
+        vec![
+            Box::new(self.metrics.last_eth_block_w_blob.clone()),
+            Box::new(self.metrics.fragment_import_to_finalized.clone()),
+        ]
     }
 }
 
@@ -109,8 +131,14 @@ impl Default for Metrics {
         ))
         .expect("Metric configuration failed");
 
+        let fragment_import_to_finalized = latency_histogram(
+            "fragment_import_to_finalized_seconds",
+            "Time from a state fragment being imported to the blob tx carrying it being finalized.",
+        );
+
         Self {
             last_eth_block_w_blob,
+            fragment_import_to_finalized,
         }
     }
 }
@@ -118,7 +146,9 @@ impl Default for Metrics {
 #[cfg(test)]
 mod tests {
     use mockall::predicate;
-    use ports::types::{L1Height, StateFragment, StateSubmission, TransactionResponse, U256};
+    use ports::types::{
+        L1Height, StateFragment, StateSubmission, TransactionResponse, WalletRole, U256,
+    };
     use storage::PostgresProcess;
 
     use super::*;
@@ -145,7 +175,11 @@ mod tests {
             self.api.get_block_number().await
         }
 
-        async fn balance(&self) -> ports::l1::Result<U256> {
+        async fn block_hash(&self, height: L1Height) -> ports::l1::Result<Option<[u8; 32]>> {
+            self.api.block_hash(height).await
+        }
+
+        async fn balance(&self, _wallet: WalletRole) -> ports::l1::Result<U256> {
             Ok(U256::ZERO)
         }
 
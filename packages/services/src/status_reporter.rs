@@ -1,11 +1,28 @@
-use ports::storage::Storage;
+use std::num::NonZeroU32;
+
+use ports::{
+    fuel::FuelBlock,
+    storage::Storage,
+    types::{BlockSubmission, Utc},
+};
 use serde::Serialize;
 
 use crate::Result;
 
-#[derive(Debug, Serialize, Default, PartialEq, Eq)]
+#[derive(Debug, Serialize, PartialEq, Eq)]
 pub struct StatusReport {
     pub status: Status,
+    /// `None` while the fuel node cannot be reached, same for the lag.
+    pub latest_fuel_height: Option<u32>,
+    pub last_committed: Option<CommitHeights>,
+    pub last_completed: Option<CommitHeights>,
+    pub pending_blob_txs: usize,
+    pub unsubmitted_fragments: FragmentsStatus,
+    pub latest_finalized_state_height: Option<u32>,
+    /// How far the last completed commit trails the fuel chain.
+    pub commit_lag: Lag,
+    /// How far the latest finalized state trails the fuel chain.
+    pub state_lag: Lag,
 }
 
 #[derive(Serialize, Debug, Default, PartialEq, Eq)]
@@ -15,33 +32,135 @@ pub enum Status {
     Committing,
 }
 
-pub struct StatusReporter<Db> {
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct CommitHeights {
+    pub fuel_block_height: u32,
+    /// Height under which the block is committed to in the L1 contract.
+    pub commit_height: u32,
+}
+
+#[derive(Debug, Serialize, Default, PartialEq, Eq)]
+pub struct FragmentsStatus {
+    pub count: u64,
+    pub bytes: u64,
+    pub oldest_age_seconds: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Default, PartialEq, Eq)]
+pub struct Lag {
+    pub blocks: Option<u32>,
+    /// Difference between the fuel block timestamps.
+    pub seconds: Option<u64>,
+}
+
+pub struct StatusReporter<Db, Fuel> {
     storage: Db,
+    fuel: Fuel,
+    commit_interval: NonZeroU32,
 }
 
-impl<Db> StatusReporter<Db> {
-    pub fn new(storage: Db) -> Self {
-        Self { storage }
+impl<Db, Fuel> StatusReporter<Db, Fuel> {
+    pub fn new(storage: Db, fuel: Fuel, commit_interval: NonZeroU32) -> Self {
+        Self {
+            storage,
+            fuel,
+            commit_interval,
+        }
     }
 }
-impl<Db> StatusReporter<Db>
+
+impl<Db, Fuel> StatusReporter<Db, Fuel>
 where
     Db: Storage,
+    Fuel: ports::fuel::Api,
 {
     pub async fn current_status(&self) -> Result<StatusReport> {
-        let last_submission_completed = self
-            .storage
-            .submission_w_latest_block()
-            .await?
-            .map(|submission| submission.completed);
+        let last_committed = self.storage.submission_w_latest_block().await?;
+        let last_completed = self.storage.completed_submission_w_latest_block().await?;
+        let pending_blob_txs = self.storage.get_pending_txs().await?.len();
+        let fragments = self.storage.unsubmitted_fragments_summary().await?;
+        let latest_finalized_state_height = self.storage.latest_finalized_state_height().await?;
 
-        let status = if last_submission_completed == Some(false) {
+        let status = if last_committed
+            .as_ref()
+            .map(|submission| submission.completed)
+            == Some(false)
+        {
             Status::Committing
         } else {
             Status::Idle
         };
 
-        Ok(StatusReport { status })
+        let latest_fuel_block = match self.fuel.latest_block().await {
+            Ok(block) => Some(block),
+            Err(e) => {
+                tracing::warn!("status report is missing the latest fuel block: {e}");
+                None
+            }
+        };
+
+        let commit_lag = self
+            .lag(
+                latest_fuel_block.as_ref(),
+                last_completed
+                    .as_ref()
+                    .map(|submission| submission.block_height),
+            )
+            .await;
+        let state_lag = self
+            .lag(latest_fuel_block.as_ref(), latest_finalized_state_height)
+            .await;
+
+        Ok(StatusReport {
+            status,
+            latest_fuel_height: latest_fuel_block.map(|block| block.header.height),
+            last_committed: last_committed.map(|submission| self.commit_heights(&submission)),
+            last_completed: last_completed.map(|submission| self.commit_heights(&submission)),
+            pending_blob_txs,
+            unsubmitted_fragments: FragmentsStatus {
+                count: fragments.count,
+                bytes: fragments.bytes,
+                oldest_age_seconds: fragments.oldest_created_at.map(|created_at| {
+                    u64::try_from((Utc::now() - created_at).num_seconds()).unwrap_or_default()
+                }),
+            },
+            latest_finalized_state_height,
+            commit_lag,
+            state_lag,
+        })
+    }
+
+    fn commit_heights(&self, submission: &BlockSubmission) -> CommitHeights {
+        CommitHeights {
+            fuel_block_height: submission.block_height,
+            commit_height: submission.block_height / self.commit_interval,
+        }
+    }
+
+    async fn lag(&self, latest_block: Option<&FuelBlock>, height: Option<u32>) -> Lag {
+        let (Some(latest_block), Some(height)) = (latest_block, height) else {
+            return Lag::default();
+        };
+
+        let seconds = match self.fuel.block_at_height(height).await {
+            Ok(Some(block)) => Some(
+                latest_block
+                    .header
+                    .time
+                    .0
+                    .saturating_sub(block.header.time.0),
+            ),
+            Ok(None) => None,
+            Err(e) => {
+                tracing::warn!("status report is missing the fuel block at {height}: {e}");
+                None
+            }
+        };
+
+        Lag {
+            blocks: Some(latest_block.header.height.saturating_sub(height)),
+            seconds,
+        }
     }
 }
 
@@ -49,7 +168,12 @@ where
 mod tests {
     use std::sync::Arc;
 
-    use ports::types::BlockSubmission;
+    use mockall::predicate::eq;
+    use ports::{
+        fuel::{FuelConsensus, FuelHeader},
+        storage::MockStorage,
+        types::UnsubmittedFragments,
+    };
     use rand::Rng;
     use storage::PostgresProcess;
 
@@ -73,18 +197,16 @@ mod tests {
                     db.insert(latest_submission).await.unwrap();
                 }
 
-                let status_reporter = StatusReporter::new(db);
+                let mut fuel = given_fuel_at(100, 1_000);
+                fuel.expect_block_at_height().returning(|_| Ok(None));
+
+                let status_reporter = StatusReporter::new(db, fuel, NonZeroU32::MIN);
 
                 // when
                 let status = status_reporter.current_status().await.unwrap();
 
                 // then
-                assert_eq!(
-                    status,
-                    StatusReport {
-                        status: expected_app_status
-                    }
-                );
+                assert_eq!(status.status, expected_app_status);
             }
         };
 
@@ -95,4 +217,112 @@ mod tests {
         // has no entry
         test(None, Status::Idle).await;
     }
+
+    #[tokio::test]
+    async fn reports_heights_and_lag() {
+        // given
+        let mut storage = MockStorage::new();
+        storage
+            .expect_submission_w_latest_block()
+            .returning(|| Box::pin(async { Ok(Some(given_submission(95, false))) }));
+        storage
+            .expect_completed_submission_w_latest_block()
+            .returning(|| Box::pin(async { Ok(Some(given_submission(90, true))) }));
+        storage
+            .expect_get_pending_txs()
+            .returning(|| Box::pin(async { Ok(vec![]) }));
+        storage
+            .expect_unsubmitted_fragments_summary()
+            .returning(|| {
+                Box::pin(async {
+                    Ok(UnsubmittedFragments {
+                        count: 3,
+                        bytes: 300,
+                        oldest_created_at: None,
+                    })
+                })
+            });
+        storage
+            .expect_latest_finalized_state_height()
+            .returning(|| Box::pin(async { Ok(None) }));
+
+        let mut fuel = given_fuel_at(100, 1_000);
+        fuel.expect_block_at_height()
+            .with(eq(90))
+            .returning(|_| Ok(Some(given_block(90, 940))));
+
+        let status_reporter = StatusReporter::new(storage, fuel, NonZeroU32::new(10).unwrap());
+
+        // when
+        let status = status_reporter.current_status().await.unwrap();
+
+        // then
+        assert_eq!(
+            status,
+            StatusReport {
+                status: Status::Committing,
+                latest_fuel_height: Some(100),
+                last_committed: Some(CommitHeights {
+                    fuel_block_height: 95,
+                    commit_height: 9,
+                }),
+                last_completed: Some(CommitHeights {
+                    fuel_block_height: 90,
+                    commit_height: 9,
+                }),
+                pending_blob_txs: 0,
+                unsubmitted_fragments: FragmentsStatus {
+                    count: 3,
+                    bytes: 300,
+                    oldest_age_seconds: None,
+                },
+                latest_finalized_state_height: None,
+                commit_lag: Lag {
+                    blocks: Some(10),
+                    seconds: Some(60),
+                },
+                state_lag: Lag::default(),
+            }
+        );
+    }
+
+    fn given_submission(block_height: u32, completed: bool) -> BlockSubmission {
+        BlockSubmission {
+            block_height,
+            completed,
+            ..rand::thread_rng().gen()
+        }
+    }
+
+    fn given_fuel_at(height: u32, time: u64) -> ports::fuel::MockApi {
+        let mut fuel = ports::fuel::MockApi::new();
+        fuel.expect_latest_block()
+            .returning(move || Ok(given_block(height, time)));
+
+        fuel
+    }
+
+    fn given_block(height: u32, time: u64) -> FuelBlock {
+        FuelBlock {
+            id: Default::default(),
+            header: FuelHeader {
+                id: Default::default(),
+                da_height: Default::default(),
+                consensus_parameters_version: Default::default(),
+                state_transition_bytecode_version: Default::default(),
+                transactions_count: Default::default(),
+                message_receipt_count: Default::default(),
+                transactions_root: Default::default(),
+                message_outbox_root: Default::default(),
+                event_inbox_root: Default::default(),
+                height,
+                prev_root: Default::default(),
+                time: tai64::Tai64(time),
+                application_hash: Default::default(),
+            },
+            consensus: FuelConsensus::Unknown,
+            transactions: vec![],
+            block_producer: None,
+        }
+    }
 }
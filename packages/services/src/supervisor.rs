@@ -0,0 +1,417 @@
+use std::{collections::HashMap, panic::AssertUnwindSafe, time::Duration};
+
+use futures::FutureExt;
+use metrics::{
+    prometheus::{core::Collector, Histogram, HistogramOpts, IntCounter, Opts},
+    RegistersMetrics,
+};
+use rand::Rng;
+
+use crate::{ComponentHealth, Leadership, Runner, RunnerHealth};
+
+/// Hands out [`SupervisedRunner`]s that share the same backoff settings and report to the same
+/// [`RunnerHealth`].
+#[derive(Debug, Clone)]
+pub struct Supervisor {
+    health: RunnerHealth,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    max_age: Option<Duration>,
+    leadership: Option<Leadership>,
+}
+
+impl Supervisor {
+    pub fn new(health: RunnerHealth) -> Self {
+        Self {
+            health,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(5 * 60),
+            max_age: None,
+            leadership: None,
+        }
+    }
+
+    /// The first error is followed by a pause of about `initial`, doubling with every further
+    /// consecutive error up to `max`.
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Reports runners as stale once they go `max_age` without a successful iteration. Paused
+    /// runners and those waiting for the lead don't age.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Runners supervised with [`Self::supervise_while_leading`] only run while this replica
+    /// holds `leadership`. Without it they always do.
+    pub fn with_leadership(mut self, leadership: Leadership) -> Self {
+        self.leadership = Some(leadership);
+        self
+    }
+
+    #[must_use]
+    pub fn health(&self) -> RunnerHealth {
+        self.health.clone()
+    }
+
+    pub fn supervise<R>(&self, name: &'static str, runner: R) -> SupervisedRunner<R> {
+        let health = self.health.track(name);
+        health.set_max_age(self.max_age);
+
+        SupervisedRunner {
+            name,
+            runner,
+            health,
+            initial_backoff: self.initial_backoff,
+            max_backoff: self.max_backoff,
+            consecutive_errors: 0,
+            metrics: Metrics::new(name),
+            leadership: None,
+        }
+    }
+
+    /// For runners that must not run on more than one replica at a time.
+    pub fn supervise_while_leading<R>(&self, name: &'static str, runner: R) -> SupervisedRunner<R> {
+        SupervisedRunner {
+            leadership: self.leadership.clone(),
+            ..self.supervise(name, runner)
+        }
+    }
+}
+
+/// Runs a [`Runner`], recovering from its panics and counting its errors.
+pub struct SupervisedRunner<R> {
+    name: &'static str,
+    runner: R,
+    health: ComponentHealth,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    consecutive_errors: u32,
+    metrics: Metrics,
+    leadership: Option<Leadership>,
+}
+
+impl<R> SupervisedRunner<R> {
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// For failures that happen around the runner, e.g. while waiting for its next trigger.
+    pub fn health(&self) -> &ComponentHealth {
+        &self.health
+    }
+
+    /// For runners whose iterations last indefinitely, e.g. ones following a stream.
+    #[must_use]
+    pub fn without_max_age(self) -> Self {
+        self.health.set_max_age(None);
+        self
+    }
+}
+
+impl<R> SupervisedRunner<R>
+where
+    R: Runner,
+{
+    /// Runs a single iteration, unless the runner is paused or waits for the lead. Returns how
+    /// long to back off for if it failed or panicked, the runner is carried on with either way.
+    pub async fn run(&mut self) -> Option<Duration> {
+        if self.health.is_paused() || !self.is_leading() {
+            self.health.note_idle();
+            return None;
+        }
+
+        self.metrics.iterations.inc();
+        let timer = self.metrics.duration.start_timer();
+        let outcome = AssertUnwindSafe(self.runner.run()).catch_unwind().await;
+        timer.observe_duration();
+
+        match outcome {
+            Ok(Ok(())) => {
+                self.consecutive_errors = 0;
+                self.health.note_success();
+                return None;
+            }
+            Ok(Err(e)) => {
+                tracing::error!("{} encountered an error: {e}", self.name);
+                self.metrics.errors.inc();
+                self.health.note_failure(e);
+            }
+            Err(panic) => {
+                let message = panic_message(&*panic);
+                tracing::error!("{} panicked, restarting it: {message}", self.name);
+                self.metrics.panics.inc();
+                self.health.note_panic(message);
+            }
+        }
+
+        self.consecutive_errors = self.consecutive_errors.saturating_add(1);
+        Some(self.backoff())
+    }
+
+    fn is_leading(&self) -> bool {
+        self.leadership
+            .as_ref()
+            .map_or(true, Leadership::is_leading)
+    }
+
+    // exponential in the number of consecutive errors, jittered down by up to half so runners
+    // failing on the same cause don't retry in lockstep
+    fn backoff(&self) -> Duration {
+        let doublings = self.consecutive_errors.saturating_sub(1).min(31);
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(1 << doublings)
+            .min(self.max_backoff);
+
+        backoff.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+    }
+}
+
+fn panic_message(panic: &(dyn std::any::Any + Send)) -> String {
+    panic
+        .downcast_ref::<&str>()
+        .map(ToString::to_string)
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic payload".to_string())
+}
+
+impl<R> RegistersMetrics for SupervisedRunner<R> {
+    fn metrics(&self) -> Vec<Box<dyn Collector>> {
+        self.metrics.metrics()
+    }
+}
+
+#[derive(Clone)]
+struct Metrics {
+    iterations: IntCounter,
+    errors: IntCounter,
+    panics: IntCounter,
+    duration: Histogram,
+}
+
+impl Metrics {
+    fn new(runner: &str) -> Self {
+        let labels = HashMap::from([("runner".to_string(), runner.to_string())]);
+
+        let iterations = IntCounter::with_opts(
+            Opts::new("runner_iterations", "Number of times a runner was run.")
+                .const_labels(labels.clone()),
+        )
+        .expect("runner_iterations metric to be correctly configured");
+
+        let errors = IntCounter::with_opts(
+            Opts::new("runner_errors", "Number of runner iterations that failed.")
+                .const_labels(labels.clone()),
+        )
+        .expect("runner_errors metric to be correctly configured");
+
+        let panics = IntCounter::with_opts(
+            Opts::new(
+                "runner_panics",
+                "Number of runner iterations that panicked.",
+            )
+            .const_labels(labels.clone()),
+        )
+        .expect("runner_panics metric to be correctly configured");
+
+        let duration = Histogram::with_opts(
+            HistogramOpts::new(
+                "runner_iteration_duration_seconds",
+                "Time taken by a single runner iteration.",
+            )
+            .const_labels(labels),
+        )
+        .expect("runner_iteration_duration_seconds metric to be correctly configured");
+
+        Self {
+            iterations,
+            errors,
+            panics,
+            duration,
+        }
+    }
+}
+
+impl RegistersMetrics for Metrics {
+    fn metrics(&self) -> Vec<Box<dyn Collector>> {
+        vec![
+            Box::new(self.iterations.clone()),
+            Box::new(self.errors.clone()),
+            Box::new(self.panics.clone()),
+            Box::new(self.duration.clone()),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use super::*;
+    use crate::{Error, Result};
+
+    enum Outcome {
+        Success,
+        Failure,
+        Panic,
+    }
+
+    struct ScriptedRunner(VecDeque<Outcome>);
+
+    #[async_trait::async_trait]
+    impl Runner for ScriptedRunner {
+        async fn run(&mut self) -> Result<()> {
+            match self.0.pop_front().expect("runner to be scripted") {
+                Outcome::Success => Ok(()),
+                Outcome::Failure => Err(Error::Other("failed".to_string())),
+                Outcome::Panic => panic!("runner blew up"),
+            }
+        }
+    }
+
+    fn given_supervised(outcomes: Vec<Outcome>) -> SupervisedRunner<ScriptedRunner> {
+        Supervisor::new(RunnerHealth::default())
+            .with_backoff(Duration::from_secs(1), Duration::from_secs(5))
+            .supervise("Scripted", ScriptedRunner(outcomes.into()))
+    }
+
+    fn given_aged(outcomes: Vec<Outcome>, max_age: Duration) -> SupervisedRunner<ScriptedRunner> {
+        Supervisor::new(RunnerHealth::default())
+            .with_max_age(max_age)
+            .supervise("Aged", ScriptedRunner(outcomes.into()))
+    }
+
+    #[tokio::test]
+    async fn recovers_from_panics() {
+        // given
+        let mut sut = given_supervised(vec![Outcome::Panic, Outcome::Success]);
+
+        // when
+        let after_panic = sut.run().await;
+        let after_success = sut.run().await;
+
+        // then
+        assert!(after_panic.is_some());
+        assert!(after_success.is_none());
+        assert_eq!(sut.metrics.panics.get(), 1);
+        assert_eq!(sut.metrics.iterations.get(), 2);
+
+        let report = sut.health.report();
+        assert!(report.healthy);
+        assert_eq!(report.restarts, 1);
+    }
+
+    #[tokio::test]
+    async fn paused_runner_is_not_run() {
+        // given
+        let mut sut = given_supervised(vec![Outcome::Success]);
+        sut.health.set_paused(true);
+
+        // when
+        let while_paused = sut.run().await;
+        sut.health.set_paused(false);
+        let after_resuming = sut.run().await;
+
+        // then
+        assert!(while_paused.is_none());
+        assert!(after_resuming.is_none());
+        assert_eq!(sut.metrics.iterations.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn runs_only_while_leading_if_asked_to() {
+        // given
+        let leadership = Leadership::default();
+        let supervisor =
+            Supervisor::new(RunnerHealth::default()).with_leadership(leadership.clone());
+        let scripted = || ScriptedRunner(VecDeque::from([Outcome::Success]));
+        let mut leader_only = supervisor.supervise_while_leading("Leader Only", scripted());
+        let mut everywhere = supervisor.supervise("Everywhere", scripted());
+
+        // when
+        leader_only.run().await;
+        everywhere.run().await;
+        leadership.set_leading(true);
+        leader_only.run().await;
+
+        // then
+        assert_eq!(everywhere.metrics.iterations.get(), 1);
+        assert_eq!(leader_only.metrics.iterations.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn runner_without_recent_success_is_stale() {
+        // given
+        let mut sut = given_aged(
+            vec![Outcome::Success, Outcome::Failure],
+            Duration::from_millis(50),
+        );
+
+        // when
+        sut.run().await;
+        let fresh = sut.health.report();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        sut.run().await;
+        let after_failing = sut.health.report();
+
+        // then
+        assert!(!fresh.stale);
+        assert!(after_failing.stale);
+    }
+
+    #[tokio::test]
+    async fn paused_runner_does_not_go_stale() {
+        // given
+        let mut sut = given_aged(vec![], Duration::from_millis(50));
+        sut.health.set_paused(true);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // when
+        sut.run().await;
+
+        // then
+        assert!(!sut.health.report().stale);
+    }
+
+    #[tokio::test]
+    async fn backs_off_exponentially_on_consecutive_errors() {
+        // given
+        let mut sut = given_supervised(vec![
+            Outcome::Failure,
+            Outcome::Failure,
+            Outcome::Failure,
+            Outcome::Failure,
+            Outcome::Success,
+            Outcome::Failure,
+        ]);
+
+        // when
+        let mut backoffs = vec![];
+        for _ in 0..6 {
+            backoffs.push(sut.run().await);
+        }
+
+        // then
+        let within = |backoff: Option<Duration>, max_secs: u64| {
+            let backoff = backoff.expect("a backoff");
+            let max = Duration::from_secs(max_secs);
+            backoff >= max / 2 && backoff <= max
+        };
+        assert!(within(backoffs[0], 1));
+        assert!(within(backoffs[1], 2));
+        assert!(within(backoffs[2], 4));
+        // capped
+        assert!(within(backoffs[3], 5));
+        assert!(backoffs[4].is_none());
+        // reset by the success
+        assert!(within(backoffs[5], 1));
+
+        assert_eq!(sut.metrics.errors.get(), 5);
+        assert_eq!(sut.health.report().consecutive_failures, 1);
+    }
+}
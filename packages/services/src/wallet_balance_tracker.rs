@@ -1,40 +1,262 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
 use metrics::{
-    prometheus::{core::Collector, IntGauge, Opts},
-    RegistersMetrics,
+    prometheus::{core::Collector, GaugeVec, IntGaugeVec, Opts},
+    HealthCheck, HealthChecker, RegistersMetrics,
 };
-use ports::types::U256;
+use ports::types::{WalletRole, U256};
+use serde::Serialize;
 
 use super::Runner;
 use crate::Result;
 
 pub struct WalletBalanceTracker<Api> {
     api: Api,
+    wallets: Vec<TrackedWallet>,
+    runway_window: Duration,
+    balances: WalletBalances,
     metrics: Metrics,
 }
 
-impl<Api> WalletBalanceTracker<Api>
-where
-    Api: ports::l1::Api,
-{
+struct TrackedWallet {
+    role: WalletRole,
+    low_balance_threshold: U256,
+    // balances seen within the runway window, oldest first
+    samples: VecDeque<(Instant, U256)>,
+}
+
+impl<Api> WalletBalanceTracker<Api> {
+    const DEFAULT_RUNWAY_WINDOW: Duration = Duration::from_secs(6 * 60 * 60);
+
+    /// Tracks the main wallet without a low balance threshold.
     pub fn new(api: Api) -> Self {
         Self {
             api,
+            wallets: vec![],
+            runway_window: Self::DEFAULT_RUNWAY_WINDOW,
+            balances: WalletBalances::default(),
             metrics: Metrics::default(),
         }
+        .track(WalletRole::Main, U256::ZERO)
+    }
+
+    /// Tracks the balance of the `role` wallet, which is deemed low once it falls below
+    /// `low_balance_threshold` [wei].
+    pub fn track(mut self, role: WalletRole, low_balance_threshold: U256) -> Self {
+        self.wallets.retain(|wallet| wallet.role != role);
+        self.balances.track(role, low_balance_threshold);
+        self.wallets.push(TrackedWallet {
+            role,
+            low_balance_threshold,
+            samples: VecDeque::new(),
+        });
+        self
+    }
+
+    /// How far back the spend used to estimate the runway of a wallet is looked at.
+    pub fn with_runway_window(mut self, runway_window: Duration) -> Self {
+        self.runway_window = runway_window;
+        self
+    }
+
+    /// Unhealthy while any tracked wallet is below its low balance threshold.
+    #[must_use]
+    pub fn low_balance_checker(&self) -> HealthChecker {
+        Box::new(self.balances.clone())
+    }
+
+    /// The last balance seen of every tracked wallet, next to its threshold.
+    #[must_use]
+    pub fn balances(&self) -> WalletBalances {
+        self.balances.clone()
+    }
+}
+
+impl<Api> WalletBalanceTracker<Api>
+where
+    Api: ports::l1::Api,
+{
+    pub async fn update_balance(&mut self) -> Result<()> {
+        let now = Instant::now();
+
+        // a wallet whose balance can't be fetched doesn't keep the others from being updated
+        let mut failures = vec![];
+        for wallet in &mut self.wallets {
+            let balance = match self.api.balance(wallet.role).await {
+                Ok(balance) => balance,
+                Err(e) => {
+                    failures.push((wallet.role, crate::Error::from(e)));
+                    continue;
+                }
+            };
+            let label = [wallet.role.as_str()];
+
+            let balance_gwei = balance / U256::from(1_000_000_000);
+            self.metrics
+                .eth_wallet_balance
+                .with_label_values(&label)
+                .set(balance_gwei.saturating_to::<i64>());
+
+            let is_low = balance < wallet.low_balance_threshold;
+            if is_low {
+                tracing::warn!(
+                    "{} wallet balance of {balance} wei is below the threshold of {} wei",
+                    wallet.role,
+                    wallet.low_balance_threshold
+                );
+            }
+            self.balances.update(wallet.role, balance);
+            self.metrics
+                .eth_wallet_low_balance
+                .with_label_values(&label)
+                .set(i64::from(is_low));
+
+            wallet.samples.push_back((now, balance));
+            while wallet
+                .samples
+                .front()
+                .is_some_and(|(seen_at, _)| now.duration_since(*seen_at) > self.runway_window)
+            {
+                wallet.samples.pop_front();
+            }
+
+            let runway = estimate_runway(&wallet.samples)
+                .map_or(f64::INFINITY, |runway| runway.as_secs_f64());
+            self.metrics
+                .eth_wallet_runway
+                .with_label_values(&label)
+                .set(runway);
+        }
+
+        balance_errors(failures)
+    }
+}
+
+// One error naming every wallet whose balance couldn't be fetched, a network error if each of them
+// was.
+fn balance_errors(failures: Vec<(WalletRole, crate::Error)>) -> Result<()> {
+    if failures.is_empty() {
+        return Ok(());
+    }
+
+    let all_network = failures
+        .iter()
+        .all(|(_, error)| matches!(error, crate::Error::Network(_)));
+    let details: Vec<_> = failures
+        .iter()
+        .map(|(role, error)| format!("{role} wallet: {error}"))
+        .collect();
+    let message = format!("could not fetch balances, {}", details.join("; "));
+
+    if all_network {
+        Err(crate::Error::Network(message))
+    } else {
+        Err(crate::Error::Other(message))
+    }
+}
+
+/// Time until the last balance in `samples` is used up when spending continues at the pace seen
+/// across them. Top ups are not counted as negative spend. `None` if nothing was spent.
+fn estimate_runway(samples: &VecDeque<(Instant, U256)>) -> Option<Duration> {
+    let ((first_seen, _), (last_seen, balance)) = (samples.front()?, samples.back()?);
+    let elapsed = last_seen.duration_since(*first_seen);
+
+    let spent = samples
+        .iter()
+        .zip(samples.iter().skip(1))
+        .fold(U256::ZERO, |spent, ((_, before), (_, after))| {
+            spent + before.saturating_sub(*after)
+        });
+
+    if spent.is_zero() || elapsed.is_zero() {
+        return None;
+    }
+
+    let runway_millis = balance.saturating_mul(U256::from(elapsed.as_millis())) / spent;
+    Some(Duration::from_millis(runway_millis.saturating_to()))
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct WalletReport {
+    /// `None` until the balance was first looked up.
+    pub balance_gwei: Option<u64>,
+    pub low_balance_threshold_gwei: u64,
+    pub above_threshold: bool,
+}
+
+/// Balances of the tracked wallets as last seen. Clones share the record. Unhealthy while any
+/// wallet is below its low balance threshold.
+#[derive(Debug, Clone, Default)]
+pub struct WalletBalances {
+    wallets: Arc<Mutex<BTreeMap<&'static str, WalletBalance>>>,
+}
+
+#[derive(Debug)]
+struct WalletBalance {
+    // [wei], `None` until first seen
+    balance: Option<U256>,
+    // [wei]
+    low_balance_threshold: U256,
+}
+
+impl WalletBalance {
+    fn above_threshold(&self) -> bool {
+        self.balance
+            .map_or(true, |balance| balance >= self.low_balance_threshold)
+    }
+}
+
+impl WalletBalances {
+    fn track(&self, role: WalletRole, low_balance_threshold: U256) {
+        let wallet = WalletBalance {
+            balance: None,
+            low_balance_threshold,
+        };
+        self.lock().insert(role.as_str(), wallet);
+    }
+
+    fn update(&self, role: WalletRole, balance: U256) {
+        if let Some(wallet) = self.lock().get_mut(role.as_str()) {
+            wallet.balance = Some(balance);
+        }
     }
 
-    pub async fn update_balance(&self) -> Result<()> {
-        let balance = self.api.balance().await?;
+    #[must_use]
+    pub fn report(&self) -> BTreeMap<&'static str, WalletReport> {
+        self.lock()
+            .iter()
+            .map(|(role, wallet)| {
+                let report = WalletReport {
+                    balance_gwei: wallet.balance.map(to_gwei),
+                    low_balance_threshold_gwei: to_gwei(wallet.low_balance_threshold),
+                    above_threshold: wallet.above_threshold(),
+                };
+                (*role, report)
+            })
+            .collect()
+    }
 
-        let balance_gwei = balance / U256::from(1_000_000_000);
-        self.metrics
-            .eth_wallet_balance
-            .set(balance_gwei.to::<i64>());
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<&'static str, WalletBalance>> {
+        self.wallets
+            .lock()
+            .expect("wallet balances lock not to be poisoned")
+    }
+}
 
-        Ok(())
+impl HealthCheck for WalletBalances {
+    fn healthy(&self) -> bool {
+        self.lock().values().all(WalletBalance::above_threshold)
     }
 }
 
+fn to_gwei(wei: U256) -> u64 {
+    (wei / U256::from(1_000_000_000)).saturating_to()
+}
+
 impl<Api> RegistersMetrics for WalletBalanceTracker<Api> {
     fn metrics(&self) -> Vec<Box<dyn Collector>> {
         self.metrics.metrics()
@@ -43,24 +265,53 @@ impl<Api> RegistersMetrics for WalletBalanceTracker<Api> {
 
 #[derive(Clone)]
 struct Metrics {
-    eth_wallet_balance: IntGauge,
+    eth_wallet_balance: IntGaugeVec,
+    eth_wallet_low_balance: IntGaugeVec,
+    eth_wallet_runway: GaugeVec,
 }
 
 impl RegistersMetrics for Metrics {
     fn metrics(&self) -> Vec<Box<dyn Collector>> {
-        vec![Box::new(self.eth_wallet_balance.clone())]
+        vec![
+            Box::new(self.eth_wallet_balance.clone()),
+            Box::new(self.eth_wallet_low_balance.clone()),
+            Box::new(self.eth_wallet_runway.clone()),
+        ]
     }
 }
 
 impl Default for Metrics {
     fn default() -> Self {
-        let eth_wallet_balance = IntGauge::with_opts(Opts::new(
-            "eth_wallet_balance",
-            "Ethereum wallet balance [gwei].",
-        ))
+        let eth_wallet_balance = IntGaugeVec::new(
+            Opts::new("eth_wallet_balance", "Ethereum wallet balance [gwei]."),
+            &["wallet"],
+        )
         .expect("eth_wallet_balance metric to be correctly configured");
 
-        Self { eth_wallet_balance }
+        let eth_wallet_low_balance = IntGaugeVec::new(
+            Opts::new(
+                "eth_wallet_low_balance",
+                "1 if the wallet balance is below its low balance threshold, 0 otherwise.",
+            ),
+            &["wallet"],
+        )
+        .expect("eth_wallet_low_balance metric to be correctly configured");
+
+        let eth_wallet_runway = GaugeVec::new(
+            Opts::new(
+                "eth_wallet_runway_seconds",
+                "Estimated time until the wallet balance runs out at the recent rate of spend. \
+                 +Inf if nothing was spent recently.",
+            ),
+            &["wallet"],
+        )
+        .expect("eth_wallet_runway_seconds metric to be correctly configured");
+
+        Self {
+            eth_wallet_balance,
+            eth_wallet_low_balance,
+            eth_wallet_runway,
+        }
     }
 }
 
@@ -80,6 +331,7 @@ mod tests {
     use std::str::FromStr;
 
     use metrics::prometheus::{proto::Metric, Registry};
+    use mockall::predicate::eq;
     use ports::l1;
 
     use super::*;
@@ -87,10 +339,13 @@ mod tests {
     #[tokio::test]
     async fn updates_metrics() {
         // given
-        let eth_adapter = given_l1_api("500000000000000000000");
+        let eth_adapter = given_l1_api(&[
+            (WalletRole::Main, "500000000000000000000"),
+            (WalletRole::Blob, "2000000000000000000"),
+        ]);
         let registry = Registry::new();
 
-        let sut = WalletBalanceTracker::new(eth_adapter);
+        let mut sut = WalletBalanceTracker::new(eth_adapter).track(WalletRole::Blob, U256::ZERO);
         sut.register_metrics(&registry);
 
         // when
@@ -98,23 +353,113 @@ mod tests {
 
         // then
         let metrics = registry.gather();
-        let eth_balance_metric = metrics
-            .iter()
-            .find(|metric| metric.get_name() == "eth_wallet_balance")
-            .and_then(|metric| metric.get_metric().first())
-            .map(Metric::get_gauge)
-            .unwrap();
+        let balance_of = |role: WalletRole| {
+            metrics
+                .iter()
+                .find(|metric| metric.get_name() == "eth_wallet_balance")
+                .and_then(|metric| {
+                    metric.get_metric().iter().find(|metric| {
+                        metric
+                            .get_label()
+                            .iter()
+                            .any(|label| label.get_value() == role.as_str())
+                    })
+                })
+                .map(Metric::get_gauge)
+                .unwrap()
+                .get_value()
+        };
 
-        assert_eq!(eth_balance_metric.get_value(), 500_000_000_000_f64);
+        assert_eq!(balance_of(WalletRole::Main), 500_000_000_000_f64);
+        assert_eq!(balance_of(WalletRole::Blob), 2_000_000_000_f64);
     }
 
-    fn given_l1_api(wei_balance: &str) -> l1::MockApi {
-        let balance = U256::from_str(wei_balance).unwrap();
-
-        let mut eth_adapter = l1::MockApi::new();
+    #[tokio::test]
+    async fn other_wallets_are_updated_when_one_balance_cannot_be_fetched() {
+        // given
+        let mut eth_adapter = given_l1_api(&[(WalletRole::Blob, "100000000000000000")]);
         eth_adapter
             .expect_balance()
-            .return_once(move || Ok(balance));
+            .with(eq(WalletRole::Main))
+            .return_once(|_| Err(l1::Error::Network("connection refused".to_string())));
+        let one_eth = U256::from_str("1000000000000000000").unwrap();
+
+        let mut sut = WalletBalanceTracker::new(eth_adapter).track(WalletRole::Blob, one_eth);
+        let health_check = sut.low_balance_checker();
+
+        // when
+        let result = sut.update_balance().await;
+
+        // then
+        let Err(crate::Error::Network(message)) = result else {
+            panic!("expected a network error, got {result:?}");
+        };
+        assert!(message.contains("connection refused"));
+        assert!(!health_check.healthy());
+    }
+
+    #[tokio::test]
+    async fn unhealthy_while_a_wallet_is_below_its_threshold() {
+        // given
+        let eth_adapter = given_l1_api(&[
+            (WalletRole::Main, "5000000000000000000"),
+            (WalletRole::Blob, "100000000000000000"),
+        ]);
+        let one_eth = U256::from_str("1000000000000000000").unwrap();
+
+        let mut sut = WalletBalanceTracker::new(eth_adapter)
+            .track(WalletRole::Main, one_eth)
+            .track(WalletRole::Blob, one_eth);
+        let health_check = sut.low_balance_checker();
+        assert!(health_check.healthy());
+
+        // when
+        sut.update_balance().await.unwrap();
+
+        // then
+        assert!(!health_check.healthy());
+    }
+
+    #[test]
+    fn runway_is_estimated_from_recent_spend() {
+        // given
+        let start = Instant::now();
+        let samples = VecDeque::from([
+            (start, U256::from(1_000)),
+            (start + Duration::from_secs(10), U256::from(900)),
+            // top ups don't count
+            (start + Duration::from_secs(20), U256::from(1_900)),
+            (start + Duration::from_secs(40), U256::from(1_800)),
+        ]);
+
+        // when
+        let runway = estimate_runway(&samples);
+
+        // then
+        // 200 spent over 40s
+        assert_eq!(runway, Some(Duration::from_secs(360)));
+    }
+
+    #[test]
+    fn no_runway_estimate_without_spend() {
+        let start = Instant::now();
+        let samples = VecDeque::from([
+            (start, U256::from(1_000)),
+            (start + Duration::from_secs(10), U256::from(1_000)),
+        ]);
+
+        assert_eq!(estimate_runway(&samples), None);
+    }
+
+    fn given_l1_api(wei_balances: &[(WalletRole, &str)]) -> l1::MockApi {
+        let mut eth_adapter = l1::MockApi::new();
+        for (role, wei_balance) in wei_balances {
+            let balance = U256::from_str(wei_balance).unwrap();
+            eth_adapter
+                .expect_balance()
+                .with(eq(*role))
+                .return_once(move |_| Ok(balance));
+        }
 
         eth_adapter
     }
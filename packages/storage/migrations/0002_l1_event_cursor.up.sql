@@ -0,0 +1,9 @@
+BEGIN;
+
+-- Single row tracking the last L1 block whose commit events were processed.
+CREATE TABLE IF NOT EXISTS l1_event_cursor (
+    id                    BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    last_processed_height BIGINT NOT NULL CHECK (last_processed_height >= 0)
+);
+
+COMMIT;
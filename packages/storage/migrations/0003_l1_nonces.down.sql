@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS l1_nonce_reservations;
+DROP TABLE IF EXISTS l1_nonces;
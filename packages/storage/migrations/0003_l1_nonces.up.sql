@@ -0,0 +1,20 @@
+BEGIN;
+
+-- Next nonce to hand out per wallet.
+CREATE TABLE IF NOT EXISTS l1_nonces (
+    address    BYTEA PRIMARY KEY CHECK (octet_length(address) = 20),
+    next_nonce BIGINT NOT NULL CHECK (next_nonce >= 0)
+);
+
+-- Nonces handed out and not yet known to be used on L1. `tx_hash` is set once a tx was sent with
+-- the nonce, `released` once sending failed so that the nonce can be handed out again.
+CREATE TABLE IF NOT EXISTS l1_nonce_reservations (
+    address     BYTEA NOT NULL REFERENCES l1_nonces(address) ON DELETE CASCADE,
+    nonce       BIGINT NOT NULL CHECK (nonce >= 0),
+    tx_hash     BYTEA CHECK (octet_length(tx_hash) = 32),
+    released    BOOLEAN NOT NULL DEFAULT false,
+    reserved_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (address, nonce)
+);
+
+COMMIT;
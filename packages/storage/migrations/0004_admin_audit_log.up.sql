@@ -0,0 +1,13 @@
+BEGIN;
+
+-- Every action taken through the admin api, whether it succeeded or not.
+CREATE TABLE IF NOT EXISTS admin_audit_log (
+    id         SERIAL PRIMARY KEY,
+    action     TEXT NOT NULL,
+    actor      TEXT NOT NULL,
+    details    TEXT NOT NULL,
+    outcome    TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+COMMIT;
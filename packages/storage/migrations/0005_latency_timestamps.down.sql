@@ -0,0 +1,4 @@
+ALTER TABLE l1_transactions DROP COLUMN IF EXISTS finalized_at;
+ALTER TABLE l1_fuel_block_submission
+    DROP COLUMN IF EXISTS commit_sent_at,
+    DROP COLUMN IF EXISTS fuel_block_time;
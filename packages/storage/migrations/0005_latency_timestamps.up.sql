@@ -0,0 +1,11 @@
+BEGIN;
+
+-- When the fuel block was produced and when its commit tx was sent, for latency metrics.
+ALTER TABLE l1_fuel_block_submission
+    ADD COLUMN IF NOT EXISTS fuel_block_time TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS commit_sent_at  TIMESTAMPTZ;
+
+ALTER TABLE l1_transactions
+    ADD COLUMN IF NOT EXISTS finalized_at TIMESTAMPTZ;
+
+COMMIT;
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS l1_transaction_fragments_fragment_id_idx;
+DROP INDEX IF EXISTS l1_fragments_unsubmitted_idx;
+ALTER TABLE l1_fragments DROP COLUMN IF EXISTS status;
@@ -0,0 +1,19 @@
+BEGIN;
+
+-- How far a fragment got: 0 unsubmitted, 1 in a pending tx, 2 in a finalized tx. Kept up to date as
+-- txs change state, so that finding the fragments to submit next doesn't have to go through every
+-- tx that ever carried one.
+ALTER TABLE l1_fragments
+    ADD COLUMN IF NOT EXISTS status SMALLINT NOT NULL DEFAULT 0 CHECK (status IN (0, 1, 2));
+
+UPDATE l1_fragments SET status = COALESCE((
+    SELECT MAX(CASE l1_transactions.state WHEN 1 THEN 2 WHEN 0 THEN 1 ELSE 0 END)
+    FROM l1_transaction_fragments
+    JOIN l1_transactions ON l1_transaction_fragments.transaction_id = l1_transactions.id
+    WHERE l1_transaction_fragments.fragment_id = l1_fragments.id
+), 0);
+
+CREATE INDEX IF NOT EXISTS l1_fragments_unsubmitted_idx ON l1_fragments (created_at) WHERE status = 0;
+CREATE INDEX IF NOT EXISTS l1_transaction_fragments_fragment_id_idx ON l1_transaction_fragments (fragment_id);
+
+COMMIT;
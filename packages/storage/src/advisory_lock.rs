@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use ports::storage::LeaderElection;
+use sqlx::{Connection, PgConnection};
+use tokio::sync::Mutex;
+
+use crate::error::{Error, Result};
+
+/// Leader election over a session-level Postgres advisory lock. The lock is held by a connection
+/// of its own, outside of the pool, and is released by Postgres as soon as that connection goes
+/// away, be it on purpose or because this replica died. Clones share the lock.
+#[derive(Clone)]
+pub struct AdvisoryLock {
+    pool: sqlx::Pool<sqlx::Postgres>,
+    key: i64,
+    // locked across the round trips, so that a confirmation doesn't see the connection missing
+    // while an election pings it
+    connection: Arc<Mutex<Option<PgConnection>>>,
+}
+
+impl AdvisoryLock {
+    pub(crate) fn new(pool: sqlx::Pool<sqlx::Postgres>, key: i64) -> Self {
+        Self {
+            pool,
+            key,
+            connection: Arc::default(),
+        }
+    }
+
+    pub(crate) async fn try_acquire(&self) -> Result<bool> {
+        let mut held = self.connection.lock().await;
+        if held.is_some() {
+            return Self::ping(&mut held).await;
+        }
+
+        let mut connection = self.pool.acquire().await?.detach();
+        let acquired = sqlx::query_scalar!(
+            r#"SELECT pg_try_advisory_lock($1) AS "acquired!""#,
+            self.key
+        )
+        .fetch_one(&mut connection)
+        .await?;
+
+        if acquired {
+            *held = Some(connection);
+        }
+
+        Ok(acquired)
+    }
+
+    pub(crate) async fn is_held(&self) -> Result<bool> {
+        Self::ping(&mut *self.connection.lock().await).await
+    }
+
+    /// Gives up the lock, should this replica hold it, so that another can take over right away.
+    pub async fn release(&self) {
+        if let Some(connection) = self.connection.lock().await.take() {
+            let _ = connection.close().await;
+        }
+    }
+
+    // whether the lock is still held, forgetting the connection if it isn't
+    async fn ping(held: &mut Option<PgConnection>) -> Result<bool> {
+        let Some(connection) = held.as_mut() else {
+            return Ok(false);
+        };
+
+        match connection.ping().await {
+            Ok(()) => Ok(true),
+            Err(e) => {
+                // the session, and with it the lock, is gone
+                *held = None;
+                Err(Error::from(e))
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl LeaderElection for AdvisoryLock {
+    async fn try_lead(&self) -> ports::storage::Result<bool> {
+        Ok(self.try_acquire().await?)
+    }
+
+    async fn confirm_lead(&self) -> ports::storage::Result<bool> {
+        Ok(self.is_held().await?)
+    }
+}
@@ -0,0 +1,733 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use ports::{
+    storage::{Error, Result, Storage},
+    types::{
+        AdminAction, BlockSubmission, DateTime, FragmentDetails, FragmentStatus, L1Height, Page,
+        StateFragment, StateSubmission, StateSubmissionDetails, SubmissionTx, SubmissionTxDetails,
+        TransactionState, TxFragment, UnsubmittedFragments, Utc,
+    },
+};
+
+/// Keeps everything in memory, for tests and for running the committer without a database.
+/// Behaves like [`crate::Postgres`] down to ordering, uniqueness and id assignment. Clones share
+/// their data.
+#[derive(Clone, Default)]
+pub struct InMemory {
+    tables: Arc<Mutex<Tables>>,
+    forget_sent_fragments: bool,
+}
+
+#[derive(Default)]
+struct Tables {
+    block_submissions: BTreeMap<u32, BlockSubmissionRow>,
+    last_processed_l1_height: Option<L1Height>,
+    next_nonces: HashMap<[u8; 20], u64>,
+    nonce_reservations: BTreeMap<([u8; 20], u64), NonceReservation>,
+    state_submissions: BTreeMap<u32, StateSubmission>,
+    fragments: BTreeMap<u32, StateFragment>,
+    txs: BTreeMap<u32, TxRow>,
+    // (tx id, fragment id)
+    tx_fragments: BTreeSet<(u32, u32)>,
+    admin_actions: Vec<(AdminAction, DateTime<Utc>)>,
+    last_ids: LastIds,
+}
+
+struct BlockSubmissionRow {
+    submission: BlockSubmission,
+    fuel_block_time: Option<DateTime<Utc>>,
+    commit_sent_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Default)]
+struct NonceReservation {
+    tx_hash: Option<[u8; 32]>,
+    released: bool,
+}
+
+struct TxRow {
+    hash: [u8; 32],
+    state: TransactionState,
+    finalized_at: Option<DateTime<Utc>>,
+}
+
+// Like Postgres sequences, ids start at 1 and a failed insert still uses up its id.
+#[derive(Default)]
+struct LastIds {
+    state_submission: u32,
+    fragment: u32,
+    tx: u32,
+}
+
+fn next_id(last_id: &mut u32) -> u32 {
+    *last_id += 1;
+    *last_id
+}
+
+// Postgres keeps timestamps to the microsecond.
+fn db_time(time: DateTime<Utc>) -> DateTime<Utc> {
+    DateTime::from_timestamp_micros(time.timestamp_micros())
+        .expect("a timestamp to stay in range when truncated")
+}
+
+// `key` is what `Page::before` compares against
+fn paginate<T>(
+    rows: impl Iterator<Item = T>,
+    page: Page,
+    key: impl Fn(&T) -> u64,
+) -> impl Iterator<Item = T> {
+    rows.filter(move |row| page.before.map_or(true, |before| key(row) < before))
+        .skip(page.offset as usize)
+        .take(page.limit as usize)
+}
+
+impl InMemory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// For when txs are never actually sent, as in a dry run: rather than being recorded as
+    /// pending, a tx is forgotten along with the fragments it carries, so that the next fragments
+    /// are picked up and memory use stays flat.
+    #[must_use]
+    pub fn forgetting_sent_fragments(mut self) -> Self {
+        self.forget_sent_fragments = true;
+        self
+    }
+
+    fn tables(&self) -> MutexGuard<'_, Tables> {
+        self.tables
+            .lock()
+            .expect("in-memory storage lock not to be poisoned")
+    }
+}
+
+impl Tables {
+    fn submission_by_hash(&mut self, fuel_block_hash: [u8; 32]) -> Option<&mut BlockSubmissionRow> {
+        self.block_submissions
+            .values_mut()
+            .find(|row| row.submission.block_hash == fuel_block_hash)
+    }
+
+    fn tx_by_hash(&mut self, hash: [u8; 32]) -> Option<&mut TxRow> {
+        self.txs.values_mut().find(|tx| tx.hash == hash)
+    }
+
+    fn fragments_carried_by(&self, states: &[TransactionState]) -> HashSet<u32> {
+        self.tx_fragments
+            .iter()
+            .filter(|(tx_id, _)| states.contains(&self.txs[tx_id].state))
+            .map(|(_, fragment_id)| *fragment_id)
+            .collect()
+    }
+
+    fn unsubmitted_fragments(&self) -> Vec<&StateFragment> {
+        let submitted =
+            self.fragments_carried_by(&[TransactionState::Finalized, TransactionState::Pending]);
+
+        let mut fragments: Vec<_> = self
+            .fragments
+            .iter()
+            .filter(|(id, _)| !submitted.contains(id))
+            .map(|(_, fragment)| fragment)
+            .collect();
+        fragments.sort_by_key(|fragment| fragment.created_at);
+
+        fragments
+    }
+
+    fn submission_tx(&self, id: u32, tx: &TxRow) -> SubmissionTx {
+        SubmissionTx {
+            id: Some(id),
+            hash: tx.hash,
+            state: tx.state.clone(),
+        }
+    }
+
+    fn ensure_hash_is_free(&self, fuel_block_hash: [u8; 32], except_height: u32) -> Result<()> {
+        let taken = self.block_submissions.values().any(|row| {
+            row.submission.block_hash == fuel_block_hash
+                && row.submission.block_height != except_height
+        });
+
+        if taken {
+            return Err(Error::Database(format!(
+                "a submission of block `{}` already exists",
+                hex::encode(fuel_block_hash)
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Storage for InMemory {
+    async fn insert(&self, submission: BlockSubmission) -> Result<()> {
+        let mut tables = self.tables();
+        if tables
+            .block_submissions
+            .contains_key(&submission.block_height)
+        {
+            return Err(Error::Database(format!(
+                "a submission at height {} already exists",
+                submission.block_height
+            )));
+        }
+        tables.ensure_hash_is_free(submission.block_hash, submission.block_height)?;
+
+        tables.block_submissions.insert(
+            submission.block_height,
+            BlockSubmissionRow {
+                submission,
+                fuel_block_time: None,
+                commit_sent_at: None,
+            },
+        );
+
+        Ok(())
+    }
+
+    async fn submission_w_latest_block(&self) -> Result<Option<BlockSubmission>> {
+        Ok(self
+            .tables()
+            .block_submissions
+            .values()
+            .next_back()
+            .map(|row| row.submission.clone()))
+    }
+
+    async fn completed_submission_w_latest_block(&self) -> Result<Option<BlockSubmission>> {
+        Ok(self
+            .tables()
+            .block_submissions
+            .values()
+            .rev()
+            .find(|row| row.submission.completed)
+            .map(|row| row.submission.clone()))
+    }
+
+    async fn set_submission_completed(&self, fuel_block_hash: [u8; 32]) -> Result<BlockSubmission> {
+        let mut tables = self.tables();
+        let row = tables.submission_by_hash(fuel_block_hash).ok_or_else(|| {
+            let hash = hex::encode(fuel_block_hash);
+            Error::Database(format!(
+                "Cannot set submission to completed! Submission of block: `{hash}` not found in DB."
+            ))
+        })?;
+        row.submission.completed = true;
+
+        Ok(row.submission.clone())
+    }
+
+    async fn revert_submission_completion(
+        &self,
+        fuel_block_hash: [u8; 32],
+    ) -> Result<BlockSubmission> {
+        let mut tables = self.tables();
+        let row = tables.submission_by_hash(fuel_block_hash).ok_or_else(|| {
+            let hash = hex::encode(fuel_block_hash);
+            Error::Database(format!(
+                "Cannot revert submission completion! Submission of block `{hash}` not found in DB."
+            ))
+        })?;
+        row.submission.completed = false;
+
+        Ok(row.submission.clone())
+    }
+
+    async fn reopen_submission(&self, submission: BlockSubmission) -> Result<()> {
+        let mut tables = self.tables();
+        tables.ensure_hash_is_free(submission.block_hash, submission.block_height)?;
+
+        match tables.block_submissions.get_mut(&submission.block_height) {
+            Some(row) => row.submission = submission,
+            None => {
+                tables.block_submissions.insert(
+                    submission.block_height,
+                    BlockSubmissionRow {
+                        submission,
+                        fuel_block_time: None,
+                        commit_sent_at: None,
+                    },
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn record_commit_sent(
+        &self,
+        fuel_block_hash: [u8; 32],
+        fuel_block_time: Option<DateTime<Utc>>,
+        sent_at: DateTime<Utc>,
+    ) -> Result<()> {
+        if let Some(row) = self.tables().submission_by_hash(fuel_block_hash) {
+            row.fuel_block_time = fuel_block_time.map(db_time);
+            row.commit_sent_at = Some(db_time(sent_at));
+        }
+
+        Ok(())
+    }
+
+    async fn commit_sent_at(&self, fuel_block_hash: [u8; 32]) -> Result<Option<DateTime<Utc>>> {
+        Ok(self
+            .tables()
+            .submission_by_hash(fuel_block_hash)
+            .and_then(|row| row.commit_sent_at))
+    }
+
+    async fn last_processed_l1_height(&self) -> Result<Option<L1Height>> {
+        Ok(self.tables().last_processed_l1_height)
+    }
+
+    async fn set_last_processed_l1_height(&self, height: L1Height) -> Result<()> {
+        self.tables().last_processed_l1_height = Some(height);
+        Ok(())
+    }
+
+    async fn reserve_nonce(&self, address: [u8; 20], pending_nonce: u64) -> Result<u64> {
+        let mut tables = self.tables();
+        let tables = &mut *tables;
+
+        let next_nonce = tables.next_nonces.entry(address).or_insert(pending_nonce);
+        *next_nonce = (*next_nonce).max(pending_nonce);
+
+        // L1 has seen txs with these, whether or not they were released or sent by us
+        tables
+            .nonce_reservations
+            .retain(|(reserved_by, nonce), _| !(*reserved_by == address && *nonce < pending_nonce));
+
+        let released =
+            tables
+                .nonce_reservations
+                .iter_mut()
+                .find(|((reserved_by, _), reservation)| {
+                    *reserved_by == address && reservation.released
+                });
+        if let Some(((_, nonce), reservation)) = released {
+            reservation.released = false;
+            return Ok(*nonce);
+        }
+
+        let nonce = *next_nonce;
+        *next_nonce += 1;
+        tables
+            .nonce_reservations
+            .insert((address, nonce), NonceReservation::default());
+
+        Ok(nonce)
+    }
+
+    async fn record_nonce_tx(
+        &self,
+        address: [u8; 20],
+        nonce: u64,
+        tx_hash: [u8; 32],
+    ) -> Result<()> {
+        match self.tables().nonce_reservations.get_mut(&(address, nonce)) {
+            Some(reservation) => {
+                reservation.tx_hash = Some(tx_hash);
+                Ok(())
+            }
+            None => Err(Error::Database(format!(
+                "Cannot record tx of nonce {nonce}! It was not reserved."
+            ))),
+        }
+    }
+
+    async fn release_nonce(&self, address: [u8; 20], nonce: u64) -> Result<()> {
+        if let Some(reservation) = self.tables().nonce_reservations.get_mut(&(address, nonce)) {
+            if reservation.tx_hash.is_none() {
+                reservation.released = true;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn unused_nonces(&self, address: [u8; 20]) -> Result<Vec<u64>> {
+        Ok(self
+            .tables()
+            .nonce_reservations
+            .iter()
+            .filter(|((reserved_by, _), reservation)| {
+                *reserved_by == address && reservation.released
+            })
+            .map(|((_, nonce), _)| *nonce)
+            .collect())
+    }
+
+    async fn reconcile_nonces(&self, address: [u8; 20], pending_nonce: u64) -> Result<Vec<u64>> {
+        let mut tables = self.tables();
+        tables.next_nonces.insert(address, pending_nonce);
+
+        let mut lost = vec![];
+        tables.nonce_reservations.retain(|(reserved_by, nonce), _| {
+            if *reserved_by == address && *nonce >= pending_nonce {
+                lost.push(*nonce);
+            }
+            *reserved_by != address
+        });
+
+        Ok(lost)
+    }
+
+    async fn insert_state_submission(
+        &self,
+        submission: StateSubmission,
+        fragments: Vec<StateFragment>,
+    ) -> Result<()> {
+        if fragments.is_empty() {
+            return Err(Error::Database(
+                "Cannot insert state with no fragments".to_string(),
+            ));
+        }
+
+        let mut tables = self.tables();
+        let submission_id = next_id(&mut tables.last_ids.state_submission);
+        if tables
+            .state_submissions
+            .values()
+            .any(|existing| existing.block_height == submission.block_height)
+        {
+            return Err(Error::Database(format!(
+                "a state submission at height {} already exists",
+                submission.block_height
+            )));
+        }
+
+        tables.state_submissions.insert(
+            submission_id,
+            StateSubmission {
+                id: Some(submission_id),
+                ..submission
+            },
+        );
+
+        for fragment in fragments {
+            let id = next_id(&mut tables.last_ids.fragment);
+            tables.fragments.insert(
+                id,
+                StateFragment {
+                    id: Some(id),
+                    submission_id: Some(submission_id),
+                    created_at: db_time(fragment.created_at),
+                    ..fragment
+                },
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn get_unsubmitted_fragments(&self, max_fragments: usize) -> Result<Vec<StateFragment>> {
+        Ok(self
+            .tables()
+            .unsubmitted_fragments()
+            .into_iter()
+            .take(max_fragments)
+            .cloned()
+            .collect())
+    }
+
+    async fn unsubmitted_fragments_summary(&self) -> Result<UnsubmittedFragments> {
+        let tables = self.tables();
+        let fragments = tables.unsubmitted_fragments();
+
+        Ok(UnsubmittedFragments {
+            count: fragments.len() as u64,
+            bytes: fragments
+                .iter()
+                .map(|fragment| fragment.data.len() as u64)
+                .sum(),
+            oldest_created_at: fragments.first().map(|fragment| fragment.created_at),
+        })
+    }
+
+    async fn record_pending_tx(&self, tx_hash: [u8; 32], fragment_ids: Vec<u32>) -> Result<()> {
+        let mut tables = self.tables();
+        if self.forget_sent_fragments {
+            for fragment_id in fragment_ids {
+                tables.fragments.remove(&fragment_id);
+            }
+            return Ok(());
+        }
+
+        let tx_id = next_id(&mut tables.last_ids.tx);
+        if tables.tx_by_hash(tx_hash).is_some() {
+            return Err(Error::Database(format!(
+                "a tx with hash `{}` already exists",
+                hex::encode(tx_hash)
+            )));
+        }
+
+        let mut links = BTreeSet::new();
+        for fragment_id in fragment_ids {
+            if !tables.fragments.contains_key(&fragment_id) {
+                return Err(Error::Database(format!(
+                    "fragment {fragment_id} does not exist"
+                )));
+            }
+            if !links.insert((tx_id, fragment_id)) {
+                return Err(Error::Database(format!(
+                    "fragment {fragment_id} is listed more than once"
+                )));
+            }
+        }
+
+        tables.txs.insert(
+            tx_id,
+            TxRow {
+                hash: tx_hash,
+                state: TransactionState::Pending,
+                finalized_at: None,
+            },
+        );
+        tables.tx_fragments.extend(links);
+
+        Ok(())
+    }
+
+    async fn get_pending_txs(&self) -> Result<Vec<SubmissionTx>> {
+        let tables = self.tables();
+
+        Ok(tables
+            .txs
+            .iter()
+            .filter(|(_, tx)| tx.state == TransactionState::Pending)
+            .map(|(id, tx)| tables.submission_tx(*id, tx))
+            .collect())
+    }
+
+    async fn has_pending_txs(&self) -> Result<bool> {
+        Ok(self
+            .tables()
+            .txs
+            .values()
+            .any(|tx| tx.state == TransactionState::Pending))
+    }
+
+    async fn state_submission_w_latest_block(&self) -> Result<Option<StateSubmission>> {
+        Ok(self
+            .tables()
+            .state_submissions
+            .values()
+            .max_by_key(|submission| submission.block_height)
+            .cloned())
+    }
+
+    async fn latest_finalized_state_height(&self) -> Result<Option<u32>> {
+        let tables = self.tables();
+        let finalized = tables.fragments_carried_by(&[TransactionState::Finalized]);
+
+        Ok(tables
+            .state_submissions
+            .iter()
+            .filter(|(id, _)| {
+                let mut fragments = tables
+                    .fragments
+                    .iter()
+                    .filter(|(_, fragment)| fragment.submission_id == Some(**id))
+                    .peekable();
+
+                fragments.peek().is_some()
+                    && fragments.all(|(fragment_id, _)| finalized.contains(fragment_id))
+            })
+            .map(|(_, submission)| submission.block_height)
+            .max())
+    }
+
+    async fn update_submission_tx_state(
+        &self,
+        hash: [u8; 32],
+        state: TransactionState,
+    ) -> Result<()> {
+        if let Some(tx) = self.tables().tx_by_hash(hash) {
+            tx.finalized_at = (state == TransactionState::Finalized).then(|| db_time(Utc::now()));
+            tx.state = state;
+        }
+
+        Ok(())
+    }
+
+    async fn fragment_finality_times(
+        &self,
+        tx_hash: [u8; 32],
+    ) -> Result<Vec<(DateTime<Utc>, DateTime<Utc>)>> {
+        let tables = self.tables();
+        let Some((tx_id, finalized_at)) = tables
+            .txs
+            .iter()
+            .find(|(_, tx)| tx.hash == tx_hash)
+            .and_then(|(id, tx)| Some((*id, tx.finalized_at?)))
+        else {
+            return Ok(vec![]);
+        };
+
+        Ok(tables
+            .tx_fragments
+            .range((tx_id, 0)..=(tx_id, u32::MAX))
+            .map(|(_, fragment_id)| (tables.fragments[fragment_id].created_at, finalized_at))
+            .collect())
+    }
+
+    async fn drop_pending_tx(&self, hash: [u8; 32]) -> Result<bool> {
+        match self.tables().tx_by_hash(hash) {
+            Some(tx) if tx.state == TransactionState::Pending => {
+                tx.state = TransactionState::Failed;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn requeue_fragments(&self, fragment_ids: Vec<u32>) -> Result<u64> {
+        let mut detached = HashSet::new();
+        self.tables().tx_fragments.retain(|(_, fragment_id)| {
+            if fragment_ids.contains(fragment_id) {
+                detached.insert(*fragment_id);
+                return false;
+            }
+            true
+        });
+
+        Ok(detached.len() as u64)
+    }
+
+    async fn record_admin_action(&self, action: AdminAction) -> Result<()> {
+        self.tables()
+            .admin_actions
+            .push((action, db_time(Utc::now())));
+        Ok(())
+    }
+
+    async fn block_submissions(&self, page: Page) -> Result<Vec<BlockSubmission>> {
+        Ok(paginate(
+            self.tables()
+                .block_submissions
+                .values()
+                .rev()
+                .map(|row| row.submission.clone()),
+            page,
+            |submission| u64::from(submission.block_height),
+        )
+        .collect())
+    }
+
+    async fn block_submission_by_height(&self, height: u32) -> Result<Option<BlockSubmission>> {
+        Ok(self
+            .tables()
+            .block_submissions
+            .get(&height)
+            .map(|row| row.submission.clone()))
+    }
+
+    async fn block_submission_by_hash(
+        &self,
+        fuel_block_hash: [u8; 32],
+    ) -> Result<Option<BlockSubmission>> {
+        Ok(self
+            .tables()
+            .submission_by_hash(fuel_block_hash)
+            .map(|row| row.submission.clone()))
+    }
+
+    async fn state_submissions(&self, page: Page) -> Result<Vec<StateSubmissionDetails>> {
+        let tables = self.tables();
+        let finalized = tables.fragments_carried_by(&[TransactionState::Finalized]);
+        let pending = tables.fragments_carried_by(&[TransactionState::Pending]);
+
+        let mut submissions: Vec<_> = tables.state_submissions.values().collect();
+        submissions.sort_by_key(|submission| std::cmp::Reverse(submission.block_height));
+
+        let submissions = paginate(submissions.into_iter(), page, |submission| {
+            u64::from(submission.block_height)
+        });
+
+        Ok(submissions
+            .map(|submission| {
+                let mut fragments: Vec<_> = tables
+                    .fragments
+                    .values()
+                    .filter(|fragment| fragment.submission_id == submission.id)
+                    .map(|fragment| {
+                        let id = fragment.id.expect("stored fragments to have an id");
+                        let status = if finalized.contains(&id) {
+                            FragmentStatus::Finalized
+                        } else if pending.contains(&id) {
+                            FragmentStatus::Pending
+                        } else {
+                            FragmentStatus::Unsubmitted
+                        };
+
+                        FragmentDetails {
+                            id,
+                            fragment_idx: fragment.fragment_idx,
+                            size: fragment.data.len() as u64,
+                            created_at: fragment.created_at,
+                            status,
+                        }
+                    })
+                    .collect();
+                fragments.sort_by_key(|fragment| fragment.fragment_idx);
+
+                StateSubmissionDetails {
+                    submission: submission.clone(),
+                    fragments,
+                }
+            })
+            .collect())
+    }
+
+    async fn submission_txs(
+        &self,
+        page: Page,
+        state: Option<TransactionState>,
+    ) -> Result<Vec<SubmissionTxDetails>> {
+        let tables = self.tables();
+        let txs = tables
+            .txs
+            .iter()
+            .rev()
+            .filter(|(_, tx)| state.as_ref().map_or(true, |state| tx.state == *state));
+
+        Ok(paginate(txs, page, |(id, _)| u64::from(**id))
+            .map(|(id, tx)| {
+                let mut fragments: Vec<_> = tables
+                    .tx_fragments
+                    .range((*id, 0)..=(*id, u32::MAX))
+                    .map(|(_, fragment_id)| {
+                        let fragment = &tables.fragments[fragment_id];
+                        let submission_id = fragment
+                            .submission_id
+                            .expect("stored fragments to belong to a submission");
+
+                        TxFragment {
+                            fragment_id: *fragment_id,
+                            fragment_idx: fragment.fragment_idx,
+                            submission_block_height: tables.state_submissions[&submission_id]
+                                .block_height,
+                        }
+                    })
+                    .collect();
+                fragments.sort_by_key(|fragment| {
+                    (fragment.submission_block_height, fragment.fragment_idx)
+                });
+
+                SubmissionTxDetails {
+                    tx: tables.submission_tx(*id, tx),
+                    fragments,
+                }
+            })
+            .collect())
+    }
+
+    async fn ping(&self) -> Result<()> {
+        Ok(())
+    }
+}
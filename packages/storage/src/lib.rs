@@ -5,26 +5,113 @@ mod test_instance;
 #[cfg(feature = "test-helpers")]
 pub use test_instance::*;
 
+mod advisory_lock;
 mod error;
+mod in_memory;
 mod postgres;
+pub use advisory_lock::AdvisoryLock;
+pub use in_memory::InMemory;
 use ports::{
     storage::{Result, Storage},
-    types::{BlockSubmission, StateFragment, StateSubmission, SubmissionTx, TransactionState},
+    types::{
+        AdminAction, BlockSubmission, DateTime, L1Height, Page, StateFragment, StateSubmission,
+        StateSubmissionDetails, SubmissionTx, SubmissionTxDetails, TransactionState,
+        UnsubmittedFragments, Utc,
+    },
 };
-pub use postgres::{DbConfig, Postgres};
+pub use postgres::{DbConfig, MigrationStatus, Postgres};
 
 #[async_trait::async_trait]
 impl Storage for Postgres {
     async fn insert(&self, submission: BlockSubmission) -> Result<()> {
-        self.insert_submission(submission).await
+        Ok(self.insert_submission(submission).await?)
     }
 
     async fn submission_w_latest_block(&self) -> Result<Option<BlockSubmission>> {
-        self.get_latest_submission().await
+        Ok(self.get_latest_submission().await?)
     }
 
     async fn set_submission_completed(&self, fuel_block_hash: [u8; 32]) -> Result<BlockSubmission> {
-        self.mark_submission_completed(fuel_block_hash).await
+        Ok(self.mark_submission_completed(fuel_block_hash).await?)
+    }
+
+    async fn revert_submission_completion(
+        &self,
+        fuel_block_hash: [u8; 32],
+    ) -> Result<BlockSubmission> {
+        Ok(self.mark_submission_incomplete(fuel_block_hash).await?)
+    }
+
+    async fn last_processed_l1_height(&self) -> Result<Option<L1Height>> {
+        Ok(self.get_last_processed_l1_height().await?)
+    }
+
+    async fn set_last_processed_l1_height(&self, height: L1Height) -> Result<()> {
+        Ok(self.upsert_last_processed_l1_height(height).await?)
+    }
+
+    async fn reserve_nonce(&self, address: [u8; 20], pending_nonce: u64) -> Result<u64> {
+        Ok(self.reserve_l1_nonce(address, pending_nonce).await?)
+    }
+
+    async fn record_nonce_tx(
+        &self,
+        address: [u8; 20],
+        nonce: u64,
+        tx_hash: [u8; 32],
+    ) -> Result<()> {
+        Ok(self.set_nonce_tx_hash(address, nonce, tx_hash).await?)
+    }
+
+    async fn release_nonce(&self, address: [u8; 20], nonce: u64) -> Result<()> {
+        Ok(self.mark_nonce_released(address, nonce).await?)
+    }
+
+    async fn unused_nonces(&self, address: [u8; 20]) -> Result<Vec<u64>> {
+        Ok(self.get_released_nonces(address).await?)
+    }
+
+    async fn reconcile_nonces(&self, address: [u8; 20], pending_nonce: u64) -> Result<Vec<u64>> {
+        Ok(self.reset_next_nonce(address, pending_nonce).await?)
+    }
+
+    async fn completed_submission_w_latest_block(&self) -> Result<Option<BlockSubmission>> {
+        Ok(self.get_latest_completed_submission().await?)
+    }
+
+    async fn unsubmitted_fragments_summary(&self) -> Result<UnsubmittedFragments> {
+        Ok(self.get_unsubmitted_fragments_summary().await?)
+    }
+
+    async fn latest_finalized_state_height(&self) -> Result<Option<u32>> {
+        Ok(self.get_latest_finalized_state_height().await?)
+    }
+
+    async fn block_submissions(&self, page: Page) -> Result<Vec<BlockSubmission>> {
+        Ok(self.get_block_submissions(page).await?)
+    }
+
+    async fn block_submission_by_height(&self, height: u32) -> Result<Option<BlockSubmission>> {
+        Ok(self.get_block_submission_by_height(height).await?)
+    }
+
+    async fn block_submission_by_hash(
+        &self,
+        fuel_block_hash: [u8; 32],
+    ) -> Result<Option<BlockSubmission>> {
+        Ok(self.get_block_submission_by_hash(fuel_block_hash).await?)
+    }
+
+    async fn state_submissions(&self, page: Page) -> Result<Vec<StateSubmissionDetails>> {
+        Ok(self.get_state_submissions(page).await?)
+    }
+
+    async fn submission_txs(
+        &self,
+        page: Page,
+        state: Option<TransactionState>,
+    ) -> Result<Vec<SubmissionTxDetails>> {
+        Ok(self.get_submission_txs(page, state).await?)
     }
 
     async fn insert_state_submission(
@@ -32,27 +119,27 @@ impl Storage for Postgres {
         submission: StateSubmission,
         fragments: Vec<StateFragment>,
     ) -> Result<()> {
-        self.insert_state_submission(submission, fragments).await
+        Ok(self.insert_state_submission(submission, fragments).await?)
     }
 
-    async fn get_unsubmitted_fragments(&self) -> Result<Vec<StateFragment>> {
-        self.get_unsubmitted_fragments().await
+    async fn get_unsubmitted_fragments(&self, max_fragments: usize) -> Result<Vec<StateFragment>> {
+        Ok(self.get_unsubmitted_fragments(max_fragments).await?)
     }
 
     async fn record_pending_tx(&self, tx_hash: [u8; 32], fragment_ids: Vec<u32>) -> Result<()> {
-        self.record_pending_tx(tx_hash, fragment_ids).await
+        Ok(self.record_pending_tx(tx_hash, fragment_ids).await?)
     }
 
     async fn get_pending_txs(&self) -> Result<Vec<SubmissionTx>> {
-        self.get_pending_txs().await
+        Ok(self.get_pending_txs().await?)
     }
 
     async fn has_pending_txs(&self) -> Result<bool> {
-        self.has_pending_txs().await
+        Ok(self.has_pending_txs().await?)
     }
 
     async fn state_submission_w_latest_block(&self) -> Result<Option<StateSubmission>> {
-        self.get_latest_state_submission().await
+        Ok(self.get_latest_state_submission().await?)
     }
 
     async fn update_submission_tx_state(
@@ -60,7 +147,49 @@ impl Storage for Postgres {
         hash: [u8; 32],
         state: TransactionState,
     ) -> Result<()> {
-        self.update_submission_tx_state(hash, state).await
+        Ok(self.update_submission_tx_state(hash, state).await?)
+    }
+
+    async fn reopen_submission(&self, submission: BlockSubmission) -> Result<()> {
+        Ok(self.upsert_submission(submission).await?)
+    }
+
+    async fn drop_pending_tx(&self, hash: [u8; 32]) -> Result<bool> {
+        Ok(self.mark_pending_tx_failed(hash).await?)
+    }
+
+    async fn requeue_fragments(&self, fragment_ids: Vec<u32>) -> Result<u64> {
+        Ok(self.detach_fragments_from_txs(fragment_ids).await?)
+    }
+
+    async fn record_admin_action(&self, action: AdminAction) -> Result<()> {
+        Ok(self.insert_admin_action(action).await?)
+    }
+
+    async fn record_commit_sent(
+        &self,
+        fuel_block_hash: [u8; 32],
+        fuel_block_time: Option<DateTime<Utc>>,
+        sent_at: DateTime<Utc>,
+    ) -> Result<()> {
+        Ok(self
+            .set_commit_sent(fuel_block_hash, fuel_block_time, sent_at)
+            .await?)
+    }
+
+    async fn commit_sent_at(&self, fuel_block_hash: [u8; 32]) -> Result<Option<DateTime<Utc>>> {
+        Ok(self.get_commit_sent_at(fuel_block_hash).await?)
+    }
+
+    async fn fragment_finality_times(
+        &self,
+        tx_hash: [u8; 32],
+    ) -> Result<Vec<(DateTime<Utc>, DateTime<Utc>)>> {
+        Ok(self.get_fragment_finality_times(tx_hash).await?)
+    }
+
+    async fn ping(&self) -> Result<()> {
+        Ok(self.ping().await?)
     }
 }
 
@@ -68,54 +197,192 @@ impl Storage for Postgres {
 mod tests {
     use ports::{
         storage::{Error, Result, Storage},
-        types::{BlockSubmission, StateFragment, StateSubmission, TransactionState},
+        types::{
+            BlockSubmission, FragmentStatus, Page, StateFragment, StateSubmission, TransactionState,
+        },
     };
     use rand::{thread_rng, Rng};
     use storage as _;
 
-    use crate::PostgresProcess;
+    use crate::{InMemory, PostgresProcess};
+
+    // Every test is run against each backend, as they are to behave the same.
+    macro_rules! conformance_tests {
+        ($($test:ident),* $(,)?) => {
+            mod postgres {
+                $(
+                    #[tokio::test]
+                    async fn $test() -> super::Result<()> {
+                        let process = super::PostgresProcess::shared().await?;
+                        super::$test(process.create_random_db().await?).await
+                    }
+                )*
+            }
+
+            mod in_memory {
+                $(
+                    #[tokio::test]
+                    async fn $test() -> super::Result<()> {
+                        super::$test(super::InMemory::new()).await
+                    }
+                )*
+            }
+        };
+    }
+
+    conformance_tests!(
+        can_insert_and_find_latest_block,
+        can_update_completion_status,
+        updating_a_missing_submission_causes_an_error,
+        can_revert_completion_status,
+        block_submissions_are_unique_by_hash_and_height,
+        reopening_replaces_the_submission_at_the_same_height,
+        last_processed_l1_height_is_overwritten,
+        nonces_are_reserved_from_the_pending_nonce,
+        released_nonces_are_reserved_again_first,
+        recording_tx_of_unreserved_nonce_fails,
+        reservations_l1_moved_past_are_pruned,
+        reconciliation_reports_reservations_l1_never_saw,
+        insert_state_submission,
+        state_submissions_are_unique_by_height,
+        record_pending_tx,
+        txs_are_unique_by_hash,
+        update_submission_tx_state,
+        finalized_tx_reports_when_its_fragments_were_imported,
+        remembers_when_the_commit_was_sent,
+        unsubmitted_fragments_are_not_in_pending_or_finalized_tx,
+        unsubmitted_fragments_are_the_oldest_few,
+        unsubmitted_fragments_are_capped_at_the_requested_count,
+        state_is_finalized_once_all_its_fragments_are,
+        state_submissions_report_the_status_of_their_fragments,
+        submission_txs_can_be_filtered_by_state,
+        dropped_tx_frees_its_fragments,
+        requeued_fragments_are_unsubmitted_again,
+        block_submissions_are_paginated_latest_first,
+        block_submissions_can_be_paged_below_a_height,
+        submission_txs_can_be_paged_below_an_id,
+    );
+
+    #[tokio::test]
+    async fn no_migrations_are_pending_once_migrated() -> Result<()> {
+        // given
+        let process = PostgresProcess::shared().await?;
+        let db = process.create_random_db().await?;
+
+        // when
+        let status = db.migration_status().await?;
+
+        // then
+        assert!(status.is_compatible());
+        assert!(status.pending.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sent_fragments_can_be_forgotten_instead_of_pending() -> Result<()> {
+        // given
+        let db = InMemory::new().forgetting_sent_fragments();
+        let (state, fragments) = given_state_and_fragments();
+        db.insert_state_submission(state, fragments).await?;
+        let first = db.get_unsubmitted_fragments(1).await?[0].id.unwrap();
+
+        // when
+        db.record_pending_tx([1; 32], vec![first]).await?;
+
+        // then
+        assert!(!db.has_pending_txs().await?);
+        let next = db.get_unsubmitted_fragments(1).await?[0].id.unwrap();
+        assert_ne!(next, first);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn only_one_replica_leads_at_a_time() -> Result<()> {
+        use ports::storage::LeaderElection;
+
+        // given
+        let process = PostgresProcess::shared().await?;
+        let db = process.create_random_db().await?;
+        let leader = db.leader_election();
+        let follower = db.leader_election();
+        assert!(leader.try_lead().await?);
+
+        // when
+        let follower_led = follower.try_lead().await?;
+        let leader_still_leads = leader.try_lead().await?;
+        leader.release().await;
+
+        // then
+        assert!(!follower_led);
+        assert!(leader_still_leads);
+        assert!(follower.try_lead().await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn only_the_leader_confirms_the_lead() -> Result<()> {
+        use ports::storage::LeaderElection;
+
+        // given
+        let process = PostgresProcess::shared().await?;
+        let db = process.create_random_db().await?;
+        let leader = db.leader_election();
+        let follower = db.leader_election();
+        assert!(leader.try_lead().await?);
+
+        // when
+        let follower_confirmed = follower.confirm_lead().await?;
+        let leader_confirmed = leader.confirm_lead().await?;
+        leader.release().await;
+
+        // then
+        assert!(!follower_confirmed);
+        assert!(leader_confirmed);
+        assert!(!leader.confirm_lead().await?);
+
+        Ok(())
+    }
+
+    // As many as an L1 block has blobs for.
+    const MAX_FRAGMENTS: usize = 6;
 
     fn random_non_zero_height() -> u32 {
         thread_rng().gen_range(1..u32::MAX)
     }
 
-    #[tokio::test]
-    async fn can_insert_and_find_latest_block() {
-        let process = PostgresProcess::shared().await.unwrap();
-        let db = process.create_random_db().await.unwrap();
+    async fn can_insert_and_find_latest_block(db: impl Storage) -> Result<()> {
         let latest_height = random_non_zero_height();
 
         let latest_submission = given_incomplete_submission(latest_height);
-        db.insert(latest_submission.clone()).await.unwrap();
+        db.insert(latest_submission.clone()).await?;
 
         let older_submission = given_incomplete_submission(latest_height - 1);
-        db.insert(older_submission).await.unwrap();
+        db.insert(older_submission).await?;
 
-        let actual = db.submission_w_latest_block().await.unwrap().unwrap();
+        let actual = db.submission_w_latest_block().await?.unwrap();
 
         assert_eq!(actual, latest_submission);
-    }
 
-    #[tokio::test]
-    async fn can_update_completion_status() {
-        let process = PostgresProcess::shared().await.unwrap();
-        let db = process.create_random_db().await.unwrap();
+        Ok(())
+    }
 
+    async fn can_update_completion_status(db: impl Storage) -> Result<()> {
         let height = random_non_zero_height();
         let submission = given_incomplete_submission(height);
         let block_hash = submission.block_hash;
-        db.insert(submission).await.unwrap();
+        db.insert(submission).await?;
 
-        let submission = db.set_submission_completed(block_hash).await.unwrap();
+        let submission = db.set_submission_completed(block_hash).await?;
 
         assert!(submission.completed);
-    }
 
-    #[tokio::test]
-    async fn updating_a_missing_submission_causes_an_error() {
-        let process = PostgresProcess::shared().await.unwrap();
-        let db = process.create_random_db().await.unwrap();
+        Ok(())
+    }
 
+    async fn updating_a_missing_submission_causes_an_error(db: impl Storage) -> Result<()> {
         let height = random_non_zero_height();
         let submission = given_incomplete_submission(height);
         let block_hash = submission.block_hash;
@@ -128,6 +395,168 @@ mod tests {
 
         let block_hash = hex::encode(block_hash);
         assert_eq!(msg, format!("Cannot set submission to completed! Submission of block: `{block_hash}` not found in DB."));
+
+        Ok(())
+    }
+
+    async fn can_revert_completion_status(db: impl Storage) -> Result<()> {
+        // given
+        let submission = given_incomplete_submission(random_non_zero_height());
+        let block_hash = submission.block_hash;
+        db.insert(submission).await?;
+        db.set_submission_completed(block_hash).await?;
+
+        // when
+        let submission = db.revert_submission_completion(block_hash).await?;
+
+        // then
+        assert!(!submission.completed);
+
+        Ok(())
+    }
+
+    async fn block_submissions_are_unique_by_hash_and_height(db: impl Storage) -> Result<()> {
+        // given
+        let submission = given_incomplete_submission(10);
+        db.insert(submission.clone()).await?;
+
+        // when
+        let same_height = db
+            .insert(BlockSubmission {
+                block_hash: [7; 32],
+                ..submission.clone()
+            })
+            .await;
+        let same_hash = db
+            .insert(BlockSubmission {
+                block_height: 11,
+                ..submission.clone()
+            })
+            .await;
+
+        // then
+        assert!(matches!(same_height, Err(Error::Database(_))));
+        assert!(matches!(same_hash, Err(Error::Database(_))));
+        assert_eq!(db.submission_w_latest_block().await?, Some(submission));
+
+        Ok(())
+    }
+
+    async fn reopening_replaces_the_submission_at_the_same_height(db: impl Storage) -> Result<()> {
+        // given
+        let submission = BlockSubmission {
+            completed: true,
+            ..given_incomplete_submission(10)
+        };
+        db.insert(submission.clone()).await?;
+        let reopened = BlockSubmission {
+            block_hash: [7; 32],
+            completed: false,
+            ..submission.clone()
+        };
+
+        // when
+        db.reopen_submission(reopened.clone()).await?;
+
+        // then
+        assert_eq!(db.block_submission_by_height(10).await?, Some(reopened));
+        assert_eq!(
+            db.block_submission_by_hash(submission.block_hash).await?,
+            None
+        );
+
+        Ok(())
+    }
+
+    async fn last_processed_l1_height_is_overwritten(db: impl Storage) -> Result<()> {
+        // given
+        assert_eq!(db.last_processed_l1_height().await?, None);
+
+        // when
+        db.set_last_processed_l1_height(10u32.into()).await?;
+        db.set_last_processed_l1_height(12u32.into()).await?;
+
+        // then
+        assert_eq!(db.last_processed_l1_height().await?, Some(12u32.into()));
+
+        Ok(())
+    }
+
+    async fn nonces_are_reserved_from_the_pending_nonce(db: impl Storage) -> Result<()> {
+        // given
+        let address = [1; 20];
+
+        // when
+        let first = db.reserve_nonce(address, 5).await?;
+        let second = db.reserve_nonce(address, 5).await?;
+        let after_external_tx = db.reserve_nonce(address, 9).await?;
+
+        // then
+        assert_eq!([first, second, after_external_tx], [5, 6, 9]);
+
+        Ok(())
+    }
+
+    async fn released_nonces_are_reserved_again_first(db: impl Storage) -> Result<()> {
+        // given
+        let address = [1; 20];
+        let used = db.reserve_nonce(address, 0).await?;
+        let failed = db.reserve_nonce(address, 0).await?;
+        db.record_nonce_tx(address, used, [2; 32]).await?;
+        db.release_nonce(address, used).await?;
+        db.release_nonce(address, failed).await?;
+        assert_eq!(db.unused_nonces(address).await?, vec![failed]);
+
+        // when
+        let nonce = db.reserve_nonce(address, 1).await?;
+
+        // then
+        assert_eq!(nonce, failed);
+        assert!(db.unused_nonces(address).await?.is_empty());
+
+        Ok(())
+    }
+
+    async fn recording_tx_of_unreserved_nonce_fails(db: impl Storage) -> Result<()> {
+        // when
+        let result = db.record_nonce_tx([1; 20], 3, [2; 32]).await;
+
+        // then
+        assert!(matches!(result, Err(Error::Database(_))));
+
+        Ok(())
+    }
+
+    async fn reservations_l1_moved_past_are_pruned(db: impl Storage) -> Result<()> {
+        // given
+        let address = [1; 20];
+        let maybe_sent = db.reserve_nonce(address, 0).await?;
+
+        // when
+        db.reserve_nonce(address, maybe_sent + 1).await?;
+
+        // then
+        let result = db.record_nonce_tx(address, maybe_sent, [2; 32]).await;
+        assert!(matches!(result, Err(Error::Database(_))));
+
+        Ok(())
+    }
+
+    async fn reconciliation_reports_reservations_l1_never_saw(db: impl Storage) -> Result<()> {
+        // given
+        let address = [1; 20];
+        for _ in 0..4 {
+            db.reserve_nonce(address, 0).await?;
+        }
+
+        // when
+        let lost = db.reconcile_nonces(address, 2).await?;
+
+        // then
+        assert_eq!(lost, vec![2, 3]);
+        assert_eq!(db.reserve_nonce(address, 2).await?, 2);
+
+        Ok(())
     }
 
     fn given_incomplete_submission(fuel_block_height: u32) -> BlockSubmission {
@@ -136,27 +565,43 @@ mod tests {
         submission
     }
 
-    #[tokio::test]
-    async fn insert_state_submission() -> Result<()> {
-        let process = PostgresProcess::shared().await?;
-        let db = process.create_random_db().await?;
-
+    async fn insert_state_submission(db: impl Storage) -> Result<()> {
         let (state, fragments) = given_state_and_fragments();
 
         db.insert_state_submission(state, fragments.clone()).await?;
 
-        let db_fragments = db.get_unsubmitted_fragments().await?;
+        let db_fragments = db.get_unsubmitted_fragments(MAX_FRAGMENTS).await?;
 
         assert_eq!(db_fragments.len(), fragments.len());
 
         Ok(())
     }
 
-    #[tokio::test]
-    async fn record_pending_tx() -> Result<()> {
-        let process = PostgresProcess::shared().await?;
-        let db = process.create_random_db().await?;
+    async fn state_submissions_are_unique_by_height(db: impl Storage) -> Result<()> {
+        // given
+        let (state, fragments) = given_state_and_fragments();
+        db.insert_state_submission(state.clone(), fragments.clone())
+            .await?;
+
+        // when
+        let result = db
+            .insert_state_submission(
+                StateSubmission {
+                    block_hash: [7; 32],
+                    ..state
+                },
+                fragments,
+            )
+            .await;
+
+        // then
+        assert!(matches!(result, Err(Error::Database(_))));
+        assert_eq!(db.unsubmitted_fragments_summary().await?.count, 5);
+
+        Ok(())
+    }
 
+    async fn record_pending_tx(db: impl Storage) -> Result<()> {
         let (state, fragments) = given_state_and_fragments();
         db.insert_state_submission(state, fragments.clone()).await?;
         let tx_hash = [1; 32];
@@ -175,18 +620,37 @@ mod tests {
         Ok(())
     }
 
-    #[tokio::test]
-    async fn update_submission_tx_state() -> Result<()> {
-        let process = PostgresProcess::shared().await?;
-        let db = process.create_random_db().await?;
+    async fn txs_are_unique_by_hash(db: impl Storage) -> Result<()> {
+        // given
+        let (state, fragments) = given_state_and_fragments();
+        db.insert_state_submission(state, fragments).await?;
+        db.record_pending_tx([1; 32], vec![1]).await?;
 
+        // when
+        let result = db.record_pending_tx([1; 32], vec![2]).await;
+
+        // then
+        assert!(matches!(result, Err(Error::Database(_))));
+        let unsubmitted: Vec<_> = db
+            .get_unsubmitted_fragments(MAX_FRAGMENTS)
+            .await?
+            .into_iter()
+            .map(|fragment| fragment.id.expect("has id"))
+            .collect();
+        assert_eq!(unsubmitted, vec![2, 3, 4, 5]);
+
+        Ok(())
+    }
+
+    async fn update_submission_tx_state(db: impl Storage) -> Result<()> {
         let (state, fragments) = given_state_and_fragments();
         db.insert_state_submission(state, fragments.clone()).await?;
         let tx_hash = [1; 32];
         let fragment_ids = vec![1];
         db.record_pending_tx(tx_hash, fragment_ids).await?;
 
-        db.update_submission_tx_state(tx_hash, TransactionState::Finalized).await?;
+        db.update_submission_tx_state(tx_hash, TransactionState::Finalized)
+            .await?;
 
         let has_pending_tx = db.has_pending_txs().await?;
         let pending_tx = db.get_pending_txs().await?;
@@ -197,29 +661,67 @@ mod tests {
         Ok(())
     }
 
-    #[tokio::test]
-    async fn unsubmitted_fragments_are_not_in_pending_or_finalized_tx() -> Result<()> {
-        let process = PostgresProcess::shared().await?;
-        let db = process.create_random_db().await?;
+    async fn finalized_tx_reports_when_its_fragments_were_imported(db: impl Storage) -> Result<()> {
+        let (state, fragments) = given_state_and_fragments();
+        db.insert_state_submission(state, fragments.clone()).await?;
+        let tx_hash = [1; 32];
+        db.record_pending_tx(tx_hash, vec![1, 2]).await?;
+
+        assert!(db.fragment_finality_times(tx_hash).await?.is_empty());
+
+        db.update_submission_tx_state(tx_hash, TransactionState::Finalized)
+            .await?;
+        let times = db.fragment_finality_times(tx_hash).await?;
+
+        assert_eq!(times.len(), 2);
+        assert!(times
+            .iter()
+            .all(|(imported_at, finalized_at)| imported_at <= finalized_at));
+
+        Ok(())
+    }
+
+    async fn remembers_when_the_commit_was_sent(db: impl Storage) -> Result<()> {
+        let submission = given_incomplete_submission(10);
+        let block_hash = submission.block_hash;
+        db.insert(submission).await?;
+        assert_eq!(db.commit_sent_at(block_hash).await?, None);
+
+        let sent_at = ports::types::Utc::now();
+        db.record_commit_sent(block_hash, None, sent_at).await?;
+
+        let recorded = db
+            .commit_sent_at(block_hash)
+            .await?
+            .expect("to be recorded");
+        assert_eq!(recorded.timestamp_micros(), sent_at.timestamp_micros());
 
+        Ok(())
+    }
+
+    async fn unsubmitted_fragments_are_not_in_pending_or_finalized_tx(
+        db: impl Storage,
+    ) -> Result<()> {
         let (state, fragments) = given_state_and_fragments();
         db.insert_state_submission(state, fragments.clone()).await?;
 
         let tx_hash = [1; 32];
         let fragment_ids = vec![1, 2];
         db.record_pending_tx(tx_hash, fragment_ids).await?;
-        db.update_submission_tx_state(tx_hash, TransactionState::Failed).await?;
+        db.update_submission_tx_state(tx_hash, TransactionState::Failed)
+            .await?;
 
         let tx_hash = [2; 32];
         let fragment_ids = vec![2];
         db.record_pending_tx(tx_hash, fragment_ids).await?;
-        db.update_submission_tx_state(tx_hash, TransactionState::Finalized).await?;
+        db.update_submission_tx_state(tx_hash, TransactionState::Finalized)
+            .await?;
 
         let tx_hash = [3; 32];
         let fragment_ids = vec![3];
         db.record_pending_tx(tx_hash, fragment_ids).await?;
 
-        let db_fragments = db.get_unsubmitted_fragments().await?;
+        let db_fragments = db.get_unsubmitted_fragments(MAX_FRAGMENTS).await?;
         let db_fragment_id: Vec<_> = db_fragments.iter().map(|f| f.id.expect("has id")).collect();
 
         assert_eq!(db_fragment_id, vec![1, 4, 5]);
@@ -227,6 +729,269 @@ mod tests {
         Ok(())
     }
 
+    async fn unsubmitted_fragments_are_the_oldest_few(db: impl Storage) -> Result<()> {
+        // given
+        let given_fragments = |ages: [i64; 4]| {
+            ages.into_iter()
+                .enumerate()
+                .map(|(idx, age)| StateFragment {
+                    id: None,
+                    submission_id: None,
+                    fragment_idx: idx as u32,
+                    data: vec![idx as u8],
+                    created_at: ports::types::DateTime::from_timestamp(1_700_000_000 - age, 0)
+                        .expect("valid timestamp"),
+                })
+                .collect()
+        };
+        let (state, _) = given_state_and_fragments();
+        db.insert_state_submission(state.clone(), given_fragments([8, 6, 4, 2]))
+            .await?;
+        db.insert_state_submission(
+            StateSubmission {
+                block_height: 2,
+                ..state
+            },
+            given_fragments([7, 5, 3, 1]),
+        )
+        .await?;
+
+        // when
+        let unsubmitted: Vec<_> = db
+            .get_unsubmitted_fragments(MAX_FRAGMENTS)
+            .await?
+            .into_iter()
+            .map(|fragment| fragment.id.expect("has id"))
+            .collect();
+
+        // then
+        assert_eq!(unsubmitted, vec![1, 5, 2, 6, 3, 7]);
+
+        Ok(())
+    }
+
+    async fn unsubmitted_fragments_are_capped_at_the_requested_count(
+        db: impl Storage,
+    ) -> Result<()> {
+        // given
+        let (state, fragments) = given_state_and_fragments();
+        db.insert_state_submission(state, fragments).await?;
+        db.record_pending_tx([1; 32], vec![1]).await?;
+
+        // when
+        let unsubmitted: Vec<_> = db
+            .get_unsubmitted_fragments(2)
+            .await?
+            .into_iter()
+            .map(|fragment| fragment.id.expect("has id"))
+            .collect();
+
+        // then
+        assert_eq!(unsubmitted, vec![2, 3]);
+
+        Ok(())
+    }
+
+    async fn state_is_finalized_once_all_its_fragments_are(db: impl Storage) -> Result<()> {
+        // given
+        let (state, fragments) = given_state_and_fragments();
+        db.insert_state_submission(state, fragments).await?;
+        db.record_pending_tx([1; 32], vec![1, 2, 3, 4]).await?;
+        db.update_submission_tx_state([1; 32], TransactionState::Finalized)
+            .await?;
+        assert_eq!(db.latest_finalized_state_height().await?, None);
+
+        // when
+        db.record_pending_tx([2; 32], vec![5]).await?;
+        db.update_submission_tx_state([2; 32], TransactionState::Finalized)
+            .await?;
+
+        // then
+        assert_eq!(db.latest_finalized_state_height().await?, Some(1));
+
+        Ok(())
+    }
+
+    async fn state_submissions_report_the_status_of_their_fragments(
+        db: impl Storage,
+    ) -> Result<()> {
+        let (state, fragments) = given_state_and_fragments();
+        db.insert_state_submission(state, fragments).await?;
+
+        db.record_pending_tx([1; 32], vec![1]).await?;
+        db.update_submission_tx_state([1; 32], TransactionState::Finalized)
+            .await?;
+        db.record_pending_tx([2; 32], vec![2]).await?;
+
+        let page = Page {
+            offset: 0,
+            limit: 10,
+            before: None,
+        };
+        let submissions = db.state_submissions(page).await?;
+
+        assert_eq!(submissions.len(), 1);
+        let statuses: Vec<_> = submissions[0]
+            .fragments
+            .iter()
+            .map(|fragment| fragment.status)
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                FragmentStatus::Finalized,
+                FragmentStatus::Pending,
+                FragmentStatus::Unsubmitted,
+                FragmentStatus::Unsubmitted,
+                FragmentStatus::Unsubmitted,
+            ]
+        );
+
+        Ok(())
+    }
+
+    async fn submission_txs_can_be_filtered_by_state(db: impl Storage) -> Result<()> {
+        let (state, fragments) = given_state_and_fragments();
+        db.insert_state_submission(state, fragments).await?;
+
+        db.record_pending_tx([1; 32], vec![1, 2]).await?;
+        db.update_submission_tx_state([1; 32], TransactionState::Failed)
+            .await?;
+        db.record_pending_tx([2; 32], vec![1, 2]).await?;
+
+        let page = Page {
+            offset: 0,
+            limit: 10,
+            before: None,
+        };
+        let txs = db
+            .submission_txs(page, Some(TransactionState::Pending))
+            .await?;
+
+        assert_eq!(txs.len(), 1);
+        assert_eq!(txs[0].tx.hash, [2; 32]);
+        let fragment_ids: Vec<_> = txs[0]
+            .fragments
+            .iter()
+            .map(|fragment| fragment.fragment_id)
+            .collect();
+        assert_eq!(fragment_ids, vec![1, 2]);
+        assert!(txs[0]
+            .fragments
+            .iter()
+            .all(|fragment| fragment.submission_block_height == 1));
+
+        Ok(())
+    }
+
+    async fn dropped_tx_frees_its_fragments(db: impl Storage) -> Result<()> {
+        let (state, fragments) = given_state_and_fragments();
+        db.insert_state_submission(state, fragments).await?;
+        db.record_pending_tx([1; 32], vec![1, 2, 3, 4, 5]).await?;
+
+        assert!(db.drop_pending_tx([1; 32]).await?);
+        assert!(!db.drop_pending_tx([1; 32]).await?);
+
+        assert!(!db.has_pending_txs().await?);
+        assert_eq!(db.get_unsubmitted_fragments(MAX_FRAGMENTS).await?.len(), 5);
+
+        Ok(())
+    }
+
+    async fn requeued_fragments_are_unsubmitted_again(db: impl Storage) -> Result<()> {
+        let (state, fragments) = given_state_and_fragments();
+        db.insert_state_submission(state, fragments).await?;
+        db.record_pending_tx([1; 32], vec![1, 2, 3, 4, 5]).await?;
+        db.update_submission_tx_state([1; 32], TransactionState::Finalized)
+            .await?;
+
+        let requeued = db.requeue_fragments(vec![2, 3]).await?;
+
+        assert_eq!(requeued, 2);
+        let unsubmitted: Vec<_> = db
+            .get_unsubmitted_fragments(MAX_FRAGMENTS)
+            .await?
+            .into_iter()
+            .map(|fragment| fragment.id.expect("has id"))
+            .collect();
+        assert_eq!(unsubmitted, vec![2, 3]);
+
+        Ok(())
+    }
+
+    async fn block_submissions_are_paginated_latest_first(db: impl Storage) -> Result<()> {
+        for height in 1..=5 {
+            db.insert(given_incomplete_submission(height)).await?;
+        }
+
+        let page = Page {
+            offset: 1,
+            limit: 2,
+            before: None,
+        };
+        let heights: Vec<_> = db
+            .block_submissions(page)
+            .await?
+            .into_iter()
+            .map(|submission| submission.block_height)
+            .collect();
+
+        assert_eq!(heights, vec![4, 3]);
+
+        Ok(())
+    }
+
+    async fn block_submissions_can_be_paged_below_a_height(db: impl Storage) -> Result<()> {
+        for height in 1..=5 {
+            db.insert(given_incomplete_submission(height)).await?;
+        }
+
+        let page = Page {
+            offset: 0,
+            limit: 2,
+            before: Some(4),
+        };
+        let heights: Vec<_> = db
+            .block_submissions(page)
+            .await?
+            .into_iter()
+            .map(|submission| submission.block_height)
+            .collect();
+
+        assert_eq!(heights, vec![3, 2]);
+
+        Ok(())
+    }
+
+    async fn submission_txs_can_be_paged_below_an_id(db: impl Storage) -> Result<()> {
+        let (state, fragments) = given_state_and_fragments();
+        db.insert_state_submission(state, fragments).await?;
+        for (hash, fragment_id) in [([1; 32], 1), ([2; 32], 2), ([3; 32], 3)] {
+            db.record_pending_tx(hash, vec![fragment_id]).await?;
+        }
+
+        let all_txs = |before| {
+            db.submission_txs(
+                Page {
+                    offset: 0,
+                    limit: 10,
+                    before,
+                },
+                None,
+            )
+        };
+        let newest_id = all_txs(None).await?[0].tx.id.expect("has id");
+        let hashes: Vec<_> = all_txs(Some(u64::from(newest_id)))
+            .await?
+            .into_iter()
+            .map(|details| details.tx.hash)
+            .collect();
+
+        assert_eq!(hashes, vec![[2; 32], [1; 32]]);
+
+        Ok(())
+    }
+
     fn given_state_and_fragments() -> (StateSubmission, Vec<StateFragment>) {
         (
             StateSubmission {
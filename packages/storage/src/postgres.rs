@@ -1,16 +1,41 @@
+use std::collections::HashMap;
+
 use ports::types::{
-    BlockSubmission, StateFragment, StateSubmission, SubmissionTx, TransactionState,
+    AdminAction, BlockSubmission, DateTime, FragmentDetails, FragmentStatus, L1Height, Page,
+    StateFragment, StateSubmission, StateSubmissionDetails, SubmissionTx, SubmissionTxDetails,
+    TransactionState, TxFragment, UnsubmittedFragments, Utc,
 };
 use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
 
 use super::error::{Error, Result};
-use crate::tables;
+use crate::{tables, AdvisoryLock};
+
+// arbitrary, only has to be the same for all replicas and not clash with other advisory locks
+const LEADER_LOCK_KEY: i64 = 0x00c0_4417_7e4c;
 
 #[derive(Clone)]
 pub struct Postgres {
     connection_pool: sqlx::Pool<sqlx::Postgres>,
 }
 
+/// Versions of the migrations that differ between the database and this version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationStatus {
+    /// Yet to be applied, which happens on startup.
+    pub pending: Vec<i64>,
+    /// Applied, but changed since.
+    pub modified: Vec<i64>,
+    /// Applied by a newer version.
+    pub unknown: Vec<i64>,
+}
+
+impl MigrationStatus {
+    /// Whether the migrations can be brought up to date by applying the pending ones.
+    pub fn is_compatible(&self) -> bool {
+        self.modified.is_empty() && self.unknown.is_empty()
+    }
+}
+
 #[derive(Debug, Clone, serde::Deserialize)]
 pub struct DbConfig {
     /// The hostname or IP address of the `PostgreSQL` server.
@@ -74,6 +99,11 @@ impl Postgres {
         self.connection_pool.close().await;
     }
 
+    /// Elects a leader among the replicas sharing the database.
+    pub fn leader_election(&self) -> AdvisoryLock {
+        AdvisoryLock::new(self.connection_pool.clone(), LEADER_LOCK_KEY)
+    }
+
     pub async fn migrate(&self) -> ports::storage::Result<()> {
         sqlx::migrate!()
             .run(&self.connection_pool)
@@ -82,6 +112,68 @@ impl Postgres {
         Ok(())
     }
 
+    /// How the migrations applied to the database compare to the ones this version ships with.
+    pub async fn migration_status(&self) -> ports::storage::Result<MigrationStatus> {
+        Ok(self.get_migration_status().await?)
+    }
+
+    async fn get_migration_status(&self) -> Result<MigrationStatus> {
+        use sqlx::migrate::Migrate;
+
+        let mut connection = self.connection_pool.acquire().await?;
+
+        // not created before the first migration is applied
+        let has_migrations_table: bool =
+            sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+                .fetch_one(&mut *connection)
+                .await?;
+        let applied = if has_migrations_table {
+            connection.list_applied_migrations().await?
+        } else {
+            vec![]
+        };
+
+        let migrator = sqlx::migrate!();
+        let known: Vec<_> = migrator
+            .iter()
+            .filter(|migration| !migration.migration_type.is_down_migration())
+            .collect();
+
+        let pending = known
+            .iter()
+            .filter(|migration| applied.iter().all(|a| a.version != migration.version))
+            .map(|migration| migration.version)
+            .collect();
+        let modified = known
+            .iter()
+            .filter(|migration| {
+                applied
+                    .iter()
+                    .any(|a| a.version == migration.version && a.checksum != migration.checksum)
+            })
+            .map(|migration| migration.version)
+            .collect();
+        let unknown = applied
+            .iter()
+            .filter(|a| known.iter().all(|migration| migration.version != a.version))
+            .map(|a| a.version)
+            .collect();
+
+        Ok(MigrationStatus {
+            pending,
+            modified,
+            unknown,
+        })
+    }
+
+    pub(crate) async fn ping(&self) -> Result<()> {
+        sqlx::query("SELECT 1")
+            .execute(&self.connection_pool)
+            .await?;
+
+        Ok(())
+    }
+
     #[cfg(feature = "test-helpers")]
     pub(crate) async fn execute(&self, query: &str) -> Result<()> {
         sqlx::query(query).execute(&self.connection_pool).await?;
@@ -102,6 +194,21 @@ impl Postgres {
         Ok(())
     }
 
+    pub(crate) async fn upsert_submission(&self, submission: BlockSubmission) -> Result<()> {
+        let row = tables::L1FuelBlockSubmission::from(submission);
+        sqlx::query!(
+            "INSERT INTO l1_fuel_block_submission (fuel_block_hash, fuel_block_height, completed, submittal_height) VALUES ($1, $2, $3, $4)
+            ON CONFLICT (fuel_block_height) DO UPDATE SET fuel_block_hash = EXCLUDED.fuel_block_hash, completed = EXCLUDED.completed, submittal_height = EXCLUDED.submittal_height",
+            row.fuel_block_hash,
+            row.fuel_block_height,
+            row.completed,
+            row.submittal_height
+        )
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(())
+    }
+
     pub(crate) async fn get_latest_submission(&self) -> Result<Option<BlockSubmission>> {
         sqlx::query_as!(
             tables::L1FuelBlockSubmission,
@@ -113,6 +220,18 @@ impl Postgres {
         .transpose()
     }
 
+    pub(crate) async fn get_latest_completed_submission(&self) -> Result<Option<BlockSubmission>> {
+        sqlx::query_as!(
+            tables::L1FuelBlockSubmission,
+            "SELECT * FROM l1_fuel_block_submission WHERE completed = true
+            ORDER BY fuel_block_height DESC LIMIT 1"
+        )
+        .fetch_optional(&self.connection_pool)
+        .await?
+        .map(BlockSubmission::try_from)
+        .transpose()
+    }
+
     pub(crate) async fn mark_submission_completed(
         &self,
         fuel_block_hash: [u8; 32],
@@ -131,11 +250,243 @@ impl Postgres {
             .ok_or_else(|| {
                 let hash = hex::encode(fuel_block_hash);
                 Error::Database(format!(
-                    "Cannot mark submission as completed! Submission of block `{hash}` not found in DB."
+                    "Cannot set submission to completed! Submission of block: `{hash}` not found in DB."
                 ))
             })
     }
 
+    pub(crate) async fn mark_submission_incomplete(
+        &self,
+        fuel_block_hash: [u8; 32],
+    ) -> Result<BlockSubmission> {
+        let updated_row = sqlx::query_as!(
+            tables::L1FuelBlockSubmission,
+            "UPDATE l1_fuel_block_submission SET completed = false WHERE fuel_block_hash = $1 RETURNING *",
+            fuel_block_hash.as_slice(),
+        )
+        .fetch_optional(&self.connection_pool)
+        .await?;
+
+        updated_row
+            .map(BlockSubmission::try_from)
+            .transpose()?
+            .ok_or_else(|| {
+                let hash = hex::encode(fuel_block_hash);
+                Error::Database(format!(
+                    "Cannot revert submission completion! Submission of block `{hash}` not found in DB."
+                ))
+            })
+    }
+
+    pub(crate) async fn set_commit_sent(
+        &self,
+        fuel_block_hash: [u8; 32],
+        fuel_block_time: Option<DateTime<Utc>>,
+        sent_at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query!(
+            "UPDATE l1_fuel_block_submission SET fuel_block_time = $2, commit_sent_at = $3
+            WHERE fuel_block_hash = $1",
+            fuel_block_hash.as_slice(),
+            fuel_block_time,
+            sent_at,
+        )
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(())
+    }
+
+    pub(crate) async fn get_commit_sent_at(
+        &self,
+        fuel_block_hash: [u8; 32],
+    ) -> Result<Option<DateTime<Utc>>> {
+        Ok(sqlx::query!(
+            "SELECT commit_sent_at FROM l1_fuel_block_submission WHERE fuel_block_hash = $1",
+            fuel_block_hash.as_slice(),
+        )
+        .fetch_optional(&self.connection_pool)
+        .await?
+        .and_then(|row| row.commit_sent_at))
+    }
+
+    pub(crate) async fn get_last_processed_l1_height(&self) -> Result<Option<L1Height>> {
+        sqlx::query!("SELECT last_processed_height FROM l1_event_cursor")
+            .fetch_optional(&self.connection_pool)
+            .await?
+            .map(|row| {
+                L1Height::try_from(row.last_processed_height)
+                    .map_err(|e| Error::Conversion(e.to_string()))
+            })
+            .transpose()
+    }
+
+    pub(crate) async fn upsert_last_processed_l1_height(&self, height: L1Height) -> Result<()> {
+        sqlx::query!(
+            "INSERT INTO l1_event_cursor (last_processed_height) VALUES ($1)
+            ON CONFLICT (id) DO UPDATE SET last_processed_height = EXCLUDED.last_processed_height",
+            i64::from(height)
+        )
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(())
+    }
+
+    pub(crate) async fn reserve_l1_nonce(
+        &self,
+        address: [u8; 20],
+        pending_nonce: u64,
+    ) -> Result<u64> {
+        let pending_nonce = nonce_to_db(pending_nonce)?;
+        let mut transaction = self.connection_pool.begin().await?;
+
+        // also locks the wallet's row until the reservation is done
+        sqlx::query!(
+            "INSERT INTO l1_nonces (address, next_nonce) VALUES ($1, $2)
+            ON CONFLICT (address) DO UPDATE SET next_nonce = GREATEST(l1_nonces.next_nonce, EXCLUDED.next_nonce)",
+            address.as_slice(),
+            pending_nonce
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        // L1 has seen txs with these, whether or not they were released or sent by us
+        sqlx::query!(
+            "DELETE FROM l1_nonce_reservations WHERE address = $1 AND nonce < $2",
+            address.as_slice(),
+            pending_nonce
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        let released = sqlx::query!(
+            "UPDATE l1_nonce_reservations SET released = false, reserved_at = now()
+            WHERE address = $1 AND nonce = (SELECT MIN(nonce) FROM l1_nonce_reservations WHERE address = $1 AND released)
+            RETURNING nonce",
+            address.as_slice()
+        )
+        .fetch_optional(&mut *transaction)
+        .await?;
+
+        let nonce = if let Some(released) = released {
+            released.nonce
+        } else {
+            let nonce = sqlx::query!(
+                "UPDATE l1_nonces SET next_nonce = next_nonce + 1 WHERE address = $1 RETURNING next_nonce - 1 AS \"nonce!\"",
+                address.as_slice()
+            )
+            .fetch_one(&mut *transaction)
+            .await?
+            .nonce;
+
+            sqlx::query!(
+                "INSERT INTO l1_nonce_reservations (address, nonce) VALUES ($1, $2)
+                ON CONFLICT (address, nonce) DO UPDATE SET tx_hash = NULL, released = false, reserved_at = now()",
+                address.as_slice(),
+                nonce
+            )
+            .execute(&mut *transaction)
+            .await?;
+
+            nonce
+        };
+
+        transaction.commit().await?;
+
+        nonce_from_db(nonce)
+    }
+
+    pub(crate) async fn set_nonce_tx_hash(
+        &self,
+        address: [u8; 20],
+        nonce: u64,
+        tx_hash: [u8; 32],
+    ) -> Result<()> {
+        let updated = sqlx::query!(
+            "UPDATE l1_nonce_reservations SET tx_hash = $3 WHERE address = $1 AND nonce = $2",
+            address.as_slice(),
+            nonce_to_db(nonce)?,
+            tx_hash.as_slice()
+        )
+        .execute(&self.connection_pool)
+        .await?
+        .rows_affected();
+
+        if updated == 0 {
+            return Err(Error::Database(format!(
+                "Cannot record tx of nonce {nonce}! It was not reserved."
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub(crate) async fn mark_nonce_released(&self, address: [u8; 20], nonce: u64) -> Result<()> {
+        sqlx::query!(
+            "UPDATE l1_nonce_reservations SET released = true
+            WHERE address = $1 AND nonce = $2 AND tx_hash IS NULL",
+            address.as_slice(),
+            nonce_to_db(nonce)?
+        )
+        .execute(&self.connection_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn get_released_nonces(&self, address: [u8; 20]) -> Result<Vec<u64>> {
+        sqlx::query!(
+            "SELECT nonce FROM l1_nonce_reservations WHERE address = $1 AND released ORDER BY nonce",
+            address.as_slice()
+        )
+        .fetch_all(&self.connection_pool)
+        .await?
+        .into_iter()
+        .map(|row| nonce_from_db(row.nonce))
+        .collect()
+    }
+
+    pub(crate) async fn reset_next_nonce(
+        &self,
+        address: [u8; 20],
+        pending_nonce: u64,
+    ) -> Result<Vec<u64>> {
+        let pending_nonce = nonce_to_db(pending_nonce)?;
+        let mut transaction = self.connection_pool.begin().await?;
+
+        sqlx::query!(
+            "INSERT INTO l1_nonces (address, next_nonce) VALUES ($1, $2)
+            ON CONFLICT (address) DO UPDATE SET next_nonce = EXCLUDED.next_nonce",
+            address.as_slice(),
+            pending_nonce
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        let mut lost = sqlx::query!(
+            "DELETE FROM l1_nonce_reservations WHERE address = $1 AND nonce >= $2 RETURNING nonce",
+            address.as_slice(),
+            pending_nonce
+        )
+        .fetch_all(&mut *transaction)
+        .await?
+        .into_iter()
+        .map(|row| nonce_from_db(row.nonce))
+        .collect::<Result<Vec<_>>>()?;
+        lost.sort_unstable();
+
+        sqlx::query!(
+            "DELETE FROM l1_nonce_reservations WHERE address = $1 AND nonce < $2",
+            address.as_slice(),
+            pending_nonce
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+
+        Ok(lost)
+    }
+
     pub(crate) async fn insert_state_submission(
         &self,
         state: StateSubmission,
@@ -174,24 +525,17 @@ impl Postgres {
         Ok(())
     }
 
-    pub(crate) async fn get_unsubmitted_fragments(&self) -> Result<Vec<StateFragment>> {
-        const BLOB_LIMIT: i64 = 6;
+    pub(crate) async fn get_unsubmitted_fragments(
+        &self,
+        max_fragments: usize,
+    ) -> Result<Vec<StateFragment>> {
+        let limit = i64::try_from(max_fragments)
+            .map_err(|e| Error::Conversion(format!("max fragments {max_fragments}: {e}")))?;
         let rows = sqlx::query_as!(
             tables::L1StateFragment,
-            "SELECT l1_fragments.*
-            FROM l1_fragments
-            WHERE l1_fragments.id NOT IN (
-                SELECT l1_fragments.id
-                FROM l1_fragments
-                JOIN l1_transaction_fragments ON l1_fragments.id = l1_transaction_fragments.fragment_id
-                JOIN l1_transactions ON l1_transaction_fragments.transaction_id = l1_transactions.id
-                WHERE l1_transactions.state IN ($1, $2)
-            )
-            ORDER BY l1_fragments.created_at
-            LIMIT $3;",
-            TransactionState::Finalized.into_i16(),
-            TransactionState::Pending.into_i16(),
-            BLOB_LIMIT
+            "SELECT * FROM l1_fragments WHERE status = $1 ORDER BY created_at LIMIT $2",
+            FragmentStatus::Unsubmitted.into_i16(),
+            limit
         )
         .fetch_all(&self.connection_pool)
         .await?
@@ -201,6 +545,56 @@ impl Postgres {
         rows.collect::<Result<Vec<_>>>()
     }
 
+    pub(crate) async fn get_unsubmitted_fragments_summary(&self) -> Result<UnsubmittedFragments> {
+        let row = sqlx::query!(
+            r#"SELECT
+                COUNT(*) AS "count!",
+                COALESCE(SUM(octet_length(data)), 0)::BIGINT AS "bytes!",
+                MIN(created_at) AS oldest_created_at
+            FROM l1_fragments
+            WHERE status = $1"#,
+            FragmentStatus::Unsubmitted.into_i16(),
+        )
+        .fetch_one(&self.connection_pool)
+        .await?;
+
+        let to_u64 = |value: i64| {
+            u64::try_from(value).map_err(|e| Error::Conversion(format!("{value}: {e}")))
+        };
+
+        Ok(UnsubmittedFragments {
+            count: to_u64(row.count)?,
+            bytes: to_u64(row.bytes)?,
+            oldest_created_at: row.oldest_created_at,
+        })
+    }
+
+    pub(crate) async fn get_latest_finalized_state_height(&self) -> Result<Option<u32>> {
+        let height = sqlx::query!(
+            "SELECT MAX(l1_submissions.fuel_block_height) AS height
+            FROM l1_submissions
+            WHERE EXISTS (
+                SELECT 1 FROM l1_fragments WHERE l1_fragments.submission_id = l1_submissions.id
+            )
+            AND NOT EXISTS (
+                SELECT 1
+                FROM l1_fragments
+                WHERE l1_fragments.submission_id = l1_submissions.id
+                AND l1_fragments.status <> $1
+            );",
+            FragmentStatus::Finalized.into_i16(),
+        )
+        .fetch_one(&self.connection_pool)
+        .await?
+        .height;
+
+        height
+            .map(|height| {
+                u32::try_from(height).map_err(|e| Error::Conversion(format!("{height}: {e}")))
+            })
+            .transpose()
+    }
+
     pub(crate) async fn record_pending_tx(
         &self,
         tx_hash: [u8; 32],
@@ -217,16 +611,19 @@ impl Postgres {
         .await?
         .id;
 
-        for fragment_id in fragment_ids {
+        for fragment_id in &fragment_ids {
             sqlx::query!(
                 "INSERT INTO l1_transaction_fragments (transaction_id, fragment_id) VALUES ($1, $2)",
                 transaction_id,
-                fragment_id as i64
+                *fragment_id as i64
             )
             .execute(&mut *transaction)
             .await?;
         }
 
+        let fragment_ids: Vec<i32> = fragment_ids.into_iter().map(|id| id as i32).collect();
+        refresh_fragment_status(&mut transaction, &fragment_ids).await?;
+
         transaction.commit().await?;
         Ok(())
     }
@@ -272,13 +669,328 @@ impl Postgres {
         hash: [u8; 32],
         state: TransactionState,
     ) -> Result<()> {
-        sqlx::query!(
-            "UPDATE l1_transactions SET state = $1 WHERE hash = $2",
+        let mut transaction = self.connection_pool.begin().await?;
+
+        let updated = sqlx::query!(
+            "UPDATE l1_transactions SET state = $1, finalized_at = CASE WHEN $1::SMALLINT = $3::SMALLINT THEN now() END
+            WHERE hash = $2 RETURNING id",
             state.into_i16(),
             hash.as_slice(),
+            TransactionState::Finalized.into_i16(),
+        )
+        .fetch_optional(&mut *transaction)
+        .await?;
+
+        if let Some(updated) = updated {
+            refresh_tx_fragments_status(&mut transaction, updated.id).await?;
+        }
+
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    pub(crate) async fn get_fragment_finality_times(
+        &self,
+        tx_hash: [u8; 32],
+    ) -> Result<Vec<(DateTime<Utc>, DateTime<Utc>)>> {
+        Ok(sqlx::query!(
+            r#"SELECT l1_fragments.created_at, l1_transactions.finalized_at AS "finalized_at!"
+            FROM l1_transactions
+            JOIN l1_transaction_fragments ON l1_transaction_fragments.transaction_id = l1_transactions.id
+            JOIN l1_fragments ON l1_transaction_fragments.fragment_id = l1_fragments.id
+            WHERE l1_transactions.hash = $1 AND l1_transactions.finalized_at IS NOT NULL"#,
+            tx_hash.as_slice(),
+        )
+        .fetch_all(&self.connection_pool)
+        .await?
+        .into_iter()
+        .map(|row| (row.created_at, row.finalized_at))
+        .collect())
+    }
+
+    pub(crate) async fn mark_pending_tx_failed(&self, hash: [u8; 32]) -> Result<bool> {
+        let mut transaction = self.connection_pool.begin().await?;
+
+        let updated = sqlx::query!(
+            "UPDATE l1_transactions SET state = $1 WHERE hash = $2 AND state = $3 RETURNING id",
+            TransactionState::Failed.into_i16(),
+            hash.as_slice(),
+            TransactionState::Pending.into_i16(),
+        )
+        .fetch_optional(&mut *transaction)
+        .await?;
+
+        if let Some(updated) = &updated {
+            refresh_tx_fragments_status(&mut transaction, updated.id).await?;
+        }
+
+        transaction.commit().await?;
+        Ok(updated.is_some())
+    }
+
+    pub(crate) async fn detach_fragments_from_txs(&self, fragment_ids: Vec<u32>) -> Result<u64> {
+        let fragment_ids: Vec<i32> = fragment_ids.into_iter().map(|id| id as i32).collect();
+        let mut transaction = self.connection_pool.begin().await?;
+
+        let detached = sqlx::query!(
+            "WITH detached AS (
+                DELETE FROM l1_transaction_fragments WHERE fragment_id = ANY($1) RETURNING fragment_id
+            )
+            SELECT COUNT(DISTINCT fragment_id) AS \"count!\" FROM detached",
+            &fragment_ids,
+        )
+        .fetch_one(&mut *transaction)
+        .await?
+        .count;
+
+        refresh_fragment_status(&mut transaction, &fragment_ids).await?;
+
+        transaction.commit().await?;
+        Ok(detached as u64)
+    }
+
+    pub(crate) async fn insert_admin_action(&self, action: AdminAction) -> Result<()> {
+        sqlx::query!(
+            "INSERT INTO admin_audit_log (action, actor, details, outcome) VALUES ($1, $2, $3, $4)",
+            action.action,
+            action.actor,
+            action.details,
+            action.outcome,
         )
         .execute(&self.connection_pool)
         .await?;
         Ok(())
     }
+
+    pub(crate) async fn get_block_submissions(&self, page: Page) -> Result<Vec<BlockSubmission>> {
+        sqlx::query_as!(
+            tables::L1FuelBlockSubmission,
+            "SELECT * FROM l1_fuel_block_submission
+            WHERE $3::BIGINT IS NULL OR fuel_block_height < $3
+            ORDER BY fuel_block_height DESC LIMIT $1 OFFSET $2",
+            i64::from(page.limit),
+            i64::from(page.offset),
+            before_key(page),
+        )
+        .fetch_all(&self.connection_pool)
+        .await?
+        .into_iter()
+        .map(BlockSubmission::try_from)
+        .collect()
+    }
+
+    pub(crate) async fn get_block_submission_by_height(
+        &self,
+        height: u32,
+    ) -> Result<Option<BlockSubmission>> {
+        sqlx::query_as!(
+            tables::L1FuelBlockSubmission,
+            "SELECT * FROM l1_fuel_block_submission WHERE fuel_block_height = $1",
+            i64::from(height),
+        )
+        .fetch_optional(&self.connection_pool)
+        .await?
+        .map(BlockSubmission::try_from)
+        .transpose()
+    }
+
+    pub(crate) async fn get_block_submission_by_hash(
+        &self,
+        fuel_block_hash: [u8; 32],
+    ) -> Result<Option<BlockSubmission>> {
+        sqlx::query_as!(
+            tables::L1FuelBlockSubmission,
+            "SELECT * FROM l1_fuel_block_submission WHERE fuel_block_hash = $1",
+            fuel_block_hash.as_slice(),
+        )
+        .fetch_optional(&self.connection_pool)
+        .await?
+        .map(BlockSubmission::try_from)
+        .transpose()
+    }
+
+    pub(crate) async fn get_state_submissions(
+        &self,
+        page: Page,
+    ) -> Result<Vec<StateSubmissionDetails>> {
+        let submissions = sqlx::query_as!(
+            tables::L1StateSubmission,
+            "SELECT * FROM l1_submissions
+            WHERE $3::BIGINT IS NULL OR fuel_block_height < $3
+            ORDER BY fuel_block_height DESC LIMIT $1 OFFSET $2",
+            i64::from(page.limit),
+            i64::from(page.offset),
+            before_key(page),
+        )
+        .fetch_all(&self.connection_pool)
+        .await?;
+
+        let submission_ids: Vec<i32> = submissions.iter().map(|row| row.id as i32).collect();
+        let fragment_rows = sqlx::query!(
+            r#"SELECT
+                l1_fragments.id,
+                l1_fragments.submission_id,
+                l1_fragments.fragment_idx,
+                octet_length(l1_fragments.data) AS "size!",
+                l1_fragments.created_at,
+                l1_fragments.status
+            FROM l1_fragments
+            WHERE l1_fragments.submission_id = ANY($1)
+            ORDER BY l1_fragments.fragment_idx"#,
+            &submission_ids,
+        )
+        .fetch_all(&self.connection_pool)
+        .await?;
+
+        let mut fragments: HashMap<i32, Vec<FragmentDetails>> = HashMap::new();
+        for row in fragment_rows {
+            let status = FragmentStatus::from_i16(row.status).ok_or_else(|| {
+                Error::Conversion(format!(
+                    "status: {:?} is not a valid variant of `FragmentStatus`",
+                    row.status
+                ))
+            })?;
+
+            fragments
+                .entry(row.submission_id)
+                .or_default()
+                .push(FragmentDetails {
+                    id: row.id as u32,
+                    fragment_idx: row.fragment_idx as u32,
+                    size: row.size as u64,
+                    created_at: row.created_at,
+                    status,
+                });
+        }
+
+        submissions
+            .into_iter()
+            .map(|row| {
+                let fragments = fragments.remove(&(row.id as i32)).unwrap_or_default();
+                Ok(StateSubmissionDetails {
+                    submission: StateSubmission::try_from(row)?,
+                    fragments,
+                })
+            })
+            .collect()
+    }
+
+    pub(crate) async fn get_submission_txs(
+        &self,
+        page: Page,
+        state: Option<TransactionState>,
+    ) -> Result<Vec<SubmissionTxDetails>> {
+        let txs = sqlx::query_as!(
+            tables::L1SubmissionTx,
+            "SELECT * FROM l1_transactions
+            WHERE ($1::SMALLINT IS NULL OR state = $1) AND ($4::BIGINT IS NULL OR id < $4)
+            ORDER BY id DESC LIMIT $2 OFFSET $3",
+            state.map(|state| state.into_i16()),
+            i64::from(page.limit),
+            i64::from(page.offset),
+            before_key(page),
+        )
+        .fetch_all(&self.connection_pool)
+        .await?;
+
+        let tx_ids: Vec<i32> = txs.iter().map(|row| row.id as i32).collect();
+        let fragment_rows = sqlx::query!(
+            "SELECT
+                l1_transaction_fragments.transaction_id,
+                l1_fragments.id AS fragment_id,
+                l1_fragments.fragment_idx,
+                l1_submissions.fuel_block_height
+            FROM l1_transaction_fragments
+            JOIN l1_fragments ON l1_transaction_fragments.fragment_id = l1_fragments.id
+            JOIN l1_submissions ON l1_fragments.submission_id = l1_submissions.id
+            WHERE l1_transaction_fragments.transaction_id = ANY($1)
+            ORDER BY l1_submissions.fuel_block_height, l1_fragments.fragment_idx",
+            &tx_ids,
+        )
+        .fetch_all(&self.connection_pool)
+        .await?;
+
+        let mut fragments: HashMap<i32, Vec<TxFragment>> = HashMap::new();
+        for row in fragment_rows {
+            let submission_block_height = u32::try_from(row.fuel_block_height).map_err(|e| {
+                Error::Conversion(format!("fuel_block_height {}: {e}", row.fuel_block_height))
+            })?;
+
+            fragments
+                .entry(row.transaction_id)
+                .or_default()
+                .push(TxFragment {
+                    fragment_id: row.fragment_id as u32,
+                    fragment_idx: row.fragment_idx as u32,
+                    submission_block_height,
+                });
+        }
+
+        txs.into_iter()
+            .map(|row| {
+                let fragments = fragments.remove(&(row.id as i32)).unwrap_or_default();
+                Ok(SubmissionTxDetails {
+                    tx: SubmissionTx::try_from(row)?,
+                    fragments,
+                })
+            })
+            .collect()
+    }
+}
+
+// keys beyond what a BIGINT holds are above every row
+fn before_key(page: Page) -> Option<i64> {
+    page.before
+        .map(|before| i64::try_from(before).unwrap_or(i64::MAX))
+}
+
+/// Sets the status of the fragments to the furthest any tx carrying them got.
+async fn refresh_fragment_status(
+    connection: &mut sqlx::PgConnection,
+    fragment_ids: &[i32],
+) -> Result<()> {
+    sqlx::query!(
+        "UPDATE l1_fragments SET status = COALESCE((
+            SELECT MAX(CASE l1_transactions.state WHEN $2 THEN $4::SMALLINT WHEN $3 THEN $5::SMALLINT ELSE $6::SMALLINT END)
+            FROM l1_transaction_fragments
+            JOIN l1_transactions ON l1_transaction_fragments.transaction_id = l1_transactions.id
+            WHERE l1_transaction_fragments.fragment_id = l1_fragments.id
+        ), $6::SMALLINT)
+        WHERE l1_fragments.id = ANY($1)",
+        fragment_ids,
+        TransactionState::Finalized.into_i16(),
+        TransactionState::Pending.into_i16(),
+        FragmentStatus::Finalized.into_i16(),
+        FragmentStatus::Pending.into_i16(),
+        FragmentStatus::Unsubmitted.into_i16(),
+    )
+    .execute(connection)
+    .await?;
+
+    Ok(())
+}
+
+async fn refresh_tx_fragments_status(
+    connection: &mut sqlx::PgConnection,
+    transaction_id: i32,
+) -> Result<()> {
+    let fragment_ids: Vec<i32> = sqlx::query!(
+        "SELECT fragment_id FROM l1_transaction_fragments WHERE transaction_id = $1",
+        transaction_id
+    )
+    .fetch_all(&mut *connection)
+    .await?
+    .into_iter()
+    .map(|row| row.fragment_id)
+    .collect();
+
+    refresh_fragment_status(connection, &fragment_ids).await
+}
+
+fn nonce_to_db(nonce: u64) -> Result<i64> {
+    i64::try_from(nonce).map_err(|e| Error::Conversion(format!("nonce {nonce}: {e}")))
+}
+
+fn nonce_from_db(nonce: i64) -> Result<u64> {
+    u64::try_from(nonce).map_err(|e| Error::Conversion(format!("nonce {nonce}: {e}")))
 }
@@ -1,5 +1,5 @@
 use ports::types::{
-    BlockSubmission, StateFragment, StateSubmission, SubmissionTx, TransactionState,
+    BlockSubmission, L1Height, StateFragment, StateSubmission, SubmissionTx, TransactionState,
 };
 use sqlx::types::chrono;
 
@@ -15,21 +15,20 @@ pub struct L1FuelBlockSubmission {
     pub fuel_block_height: i64,
     pub completed: bool,
     pub submittal_height: i64,
+    pub fuel_block_time: Option<chrono::DateTime<chrono::Utc>>,
+    pub commit_sent_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 impl TryFrom<L1FuelBlockSubmission> for BlockSubmission {
     type Error = crate::error::Error;
 
     fn try_from(value: L1FuelBlockSubmission) -> Result<Self, Self::Error> {
-        let block_hash: [u8; 32] = value
-            .fuel_block_hash
-            .try_into()
-            .map_err(|_| {
-                crate::error::Error::Conversion(format!(
-                    "Expected 32 bytes for `fuel_block_hash`, but got: {:?} from db",
-                    value.fuel_block_hash
-                ))
-            })?;
+        let Ok(block_hash) = <[u8; 32]>::try_from(value.fuel_block_hash.as_slice()) else {
+            bail!(
+                "Expected 32 bytes for `fuel_block_hash`, but got: {:?} from db",
+                value.fuel_block_hash
+            );
+        };
 
         let block_height = u32::try_from(value.fuel_block_height).map_err(|_| {
             crate::error::Error::Conversion(format!(
@@ -38,12 +37,12 @@ impl TryFrom<L1FuelBlockSubmission> for BlockSubmission {
             ))
         })?;
 
-        let submittal_height = u64::try_from(value.submittal_height).map_err(|_| {
-            crate::error::Error::Conversion(format!(
-                "`submittal_height` from db cannot fit in a `u64`. Got: {:?}",
+        let Ok(submittal_height) = L1Height::try_from(value.submittal_height) else {
+            bail!(
+                "`submittal_height` from db is not a valid L1 height. Got: {:?}",
                 value.submittal_height
-            ))
-        })?;
+            );
+        };
 
         Ok(Self {
             block_hash,
@@ -61,6 +60,8 @@ impl From<BlockSubmission> for L1FuelBlockSubmission {
             fuel_block_height: value.block_height.into(),
             completed: value.completed,
             submittal_height: value.submittal_height.into(),
+            fuel_block_time: None,
+            commit_sent_at: None,
         }
     }
 }
@@ -76,15 +77,12 @@ impl TryFrom<L1StateSubmission> for StateSubmission {
     type Error = crate::error::Error;
 
     fn try_from(value: L1StateSubmission) -> Result<Self, Self::Error> {
-        let block_hash: [u8; 32] = value
-            .fuel_block_hash
-            .try_into()
-            .map_err(|_| {
-                crate::error::Error::Conversion(format!(
-                    "Expected 32 bytes for `fuel_block_hash`, but got: {:?} from db",
-                    value.fuel_block_hash
-                ))
-            })?;
+        let Ok(block_hash) = <[u8; 32]>::try_from(value.fuel_block_hash.as_slice()) else {
+            bail!(
+                "Expected 32 bytes for `fuel_block_hash`, but got: {:?} from db",
+                value.fuel_block_hash
+            );
+        };
 
         let block_height = u32::try_from(value.fuel_block_height).map_err(|_| {
             crate::error::Error::Conversion(format!(
@@ -118,6 +116,7 @@ pub struct L1StateFragment {
     pub fragment_idx: i64,
     pub data: Vec<u8>,
     pub created_at: chrono::DateTime<chrono::Utc>,
+    pub status: i16,
 }
 
 impl TryFrom<L1StateFragment> for StateFragment {
@@ -142,6 +141,7 @@ impl From<StateFragment> for L1StateFragment {
             fragment_idx: value.fragment_idx as i64,
             data: value.data,
             created_at: value.created_at,
+            status: ports::types::FragmentStatus::Unsubmitted.into_i16(),
         }
     }
 }
@@ -151,21 +151,19 @@ pub struct L1SubmissionTx {
     pub id: i64,
     pub hash: Vec<u8>,
     pub state: i16,
+    pub finalized_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 impl TryFrom<L1SubmissionTx> for SubmissionTx {
     type Error = crate::error::Error;
 
     fn try_from(value: L1SubmissionTx) -> Result<Self, Self::Error> {
-        let hash: [u8; 32] = value
-            .hash
-            .try_into()
-            .map_err(|_| {
-                crate::error::Error::Conversion(format!(
-                    "Expected 32 bytes for transaction hash, but got: {:?} from db",
-                    value.hash
-                ))
-            })?;
+        let Ok(hash) = <[u8; 32]>::try_from(value.hash.as_slice()) else {
+            bail!(
+                "Expected 32 bytes for transaction hash, but got: {:?} from db",
+                value.hash
+            );
+        };
 
         let state = TransactionState::from_i16(value.state).ok_or_else(|| {
             crate::error::Error::Conversion(format!(
@@ -188,6 +186,7 @@ impl From<SubmissionTx> for L1SubmissionTx {
             id: value.id.unwrap_or_default() as i64,
             hash: value.hash.to_vec(),
             state: value.state.into_i16(),
+            finalized_at: None,
         }
     }
 }
@@ -51,7 +51,6 @@ impl Image for PostgresImage {
 pub struct PostgresProcess {
     username: String,
     password: String,
-    initial_db: String,
     container: testcontainers::ContainerAsync<PostgresImage>,
 }
 
@@ -77,7 +76,7 @@ impl PostgresProcess {
         let container = PostgresImage {
             username: username.clone(),
             password: password.clone(),
-            initial_db: initial_db.clone(),
+            initial_db,
         }
         .start()
         .await
@@ -86,7 +85,6 @@ impl PostgresProcess {
         Ok(Self {
             username,
             password,
-            initial_db,
             container,
         })
     }
@@ -12,6 +12,8 @@ use crate::block::ValidatedFuelBlock;
 pub enum Error {
     #[error("{0}")]
     BlockValidation(String),
+    #[error("unsupported consensus: {0}")]
+    UnsupportedConsensus(String),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -19,4 +21,11 @@ pub type Result<T> = std::result::Result<T, Error>;
 #[cfg_attr(feature = "test-helpers", mockall::automock)]
 pub trait Validator: Send + Sync {
     fn validate(&self, fuel_block: &FuelBlock) -> Result<ValidatedFuelBlock>;
+    /// Same as `validate`, but also checks that `transactions` are the ones committed to by the
+    /// header's `transactions_root`.
+    fn validate_with_transactions(
+        &self,
+        fuel_block: &FuelBlock,
+        transactions: &[Vec<u8>],
+    ) -> Result<ValidatedFuelBlock>;
 }
@@ -1,31 +1,62 @@
 use fuel_core_client::client::types::{
-    block::{Block, Consensus, Header, PoAConsensus},
+    block::{Block, Consensus, Genesis, Header, PoAConsensus},
     primitives::{BlockId, Bytes32},
 };
 use fuel_crypto::{Hasher, Message};
+use fuel_merkle::binary::root_calculator::MerkleRootCalculator;
 
 use crate::{block::ValidatedFuelBlock, Error, Result, Validator};
 
 #[derive(Debug)]
 pub struct BlockValidator {
     producer_addr: [u8; 32],
+    chain_config_hash: Option<[u8; 32]>,
 }
 
 impl Validator for BlockValidator {
     fn validate(&self, fuel_block: &Block) -> Result<ValidatedFuelBlock> {
         self.internal_validate(fuel_block)
     }
+
+    fn validate_with_transactions(
+        &self,
+        fuel_block: &Block,
+        transactions: &[Vec<u8>],
+    ) -> Result<ValidatedFuelBlock> {
+        let validated_block = self.internal_validate(fuel_block)?;
+        Self::validate_transactions(fuel_block, transactions)?;
+
+        Ok(validated_block)
+    }
 }
 
 impl BlockValidator {
     pub fn new(producer_addr: [u8; 32]) -> Self {
-        Self { producer_addr }
+        Self {
+            producer_addr,
+            chain_config_hash: None,
+        }
+    }
+
+    /// Genesis blocks carry no producer signature. They are only accepted if their chain config
+    /// hash matches the one given here.
+    #[must_use]
+    pub fn with_chain_config_hash(mut self, chain_config_hash: [u8; 32]) -> Self {
+        self.chain_config_hash = Some(chain_config_hash);
+        self
     }
 
     fn internal_validate(&self, fuel_block: &Block) -> Result<ValidatedFuelBlock> {
-        self.validate_producer_addr(fuel_block)?;
-        Self::validate_block_id(fuel_block)?;
-        self.validate_block_signature(fuel_block)?;
+        match &fuel_block.consensus {
+            Consensus::PoAConsensus(poa) => self.validate_poa_block(fuel_block, poa)?,
+            Consensus::Genesis(genesis) => self.validate_genesis_block(fuel_block, genesis)?,
+            Consensus::Unknown => {
+                return Err(Error::UnsupportedConsensus(format!(
+                    "Fuel block `{:x}` has a consensus type unknown to the committer.",
+                    fuel_block.id
+                )))
+            }
+        }
 
         Ok(ValidatedFuelBlock {
             hash: *fuel_block.id,
@@ -33,6 +64,30 @@ impl BlockValidator {
         })
     }
 
+    fn validate_poa_block(&self, fuel_block: &Block, poa: &PoAConsensus) -> Result<()> {
+        self.validate_producer_addr(fuel_block)?;
+        Self::validate_block_id(fuel_block)?;
+        self.validate_block_signature(fuel_block, poa)
+    }
+
+    fn validate_genesis_block(&self, fuel_block: &Block, genesis: &Genesis) -> Result<()> {
+        let Some(expected_hash) = self.chain_config_hash else {
+            return Err(Error::BlockValidation(
+                "Received a genesis block but no chain config hash is configured.".to_string(),
+            ));
+        };
+
+        if *genesis.chain_config_hash != expected_hash {
+            return Err(Error::BlockValidation(format!(
+                "Genesis chain config hash `{}` does not match the expected hash `{}`.",
+                hex::encode(*genesis.chain_config_hash),
+                hex::encode(expected_hash)
+            )));
+        }
+
+        Self::validate_block_id(fuel_block)
+    }
+
     fn validate_producer_addr(&self, fuel_block: &Block) -> Result<()> {
         let Some(producer_addr) = fuel_block.block_producer().map(|key| key.hash()) else {
             return Err(Error::BlockValidation(
@@ -63,8 +118,30 @@ impl BlockValidator {
         Ok(())
     }
 
-    fn validate_block_signature(&self, fuel_block: &Block) -> Result<()> {
-        let recovered_producer_addr = self.recover_producer_addr(fuel_block)?;
+    fn validate_transactions(fuel_block: &Block, transactions: &[Vec<u8>]) -> Result<()> {
+        let expected_count = usize::from(fuel_block.header.transactions_count);
+        if transactions.len() != expected_count || fuel_block.transactions.len() != expected_count {
+            return Err(Error::BlockValidation(format!(
+                "Fuel block `{:x}` declares {expected_count} transactions, but {} ids and {} transactions were received.",
+                fuel_block.id,
+                fuel_block.transactions.len(),
+                transactions.len()
+            )));
+        }
+
+        let calculated_root = Self::calculate_transactions_root(transactions);
+        if fuel_block.header.transactions_root != calculated_root {
+            return Err(Error::BlockValidation(format!(
+                "Fuel block transactions root `{:x}` does not match the calculated root `{calculated_root:x}`.",
+                fuel_block.header.transactions_root,
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn validate_block_signature(&self, fuel_block: &Block, poa: &PoAConsensus) -> Result<()> {
+        let recovered_producer_addr = Self::recover_producer_addr(fuel_block, poa)?;
 
         if recovered_producer_addr != self.producer_addr {
             return Err(Error::BlockValidation(format!(
@@ -77,14 +154,9 @@ impl BlockValidator {
         Ok(())
     }
 
-    fn recover_producer_addr(&self, fuel_block: &Block) -> Result<[u8; 32]> {
-        let Consensus::PoAConsensus(PoAConsensus { signature }) = fuel_block.consensus else {
-            return Err(Error::BlockValidation(
-                "PoAConsensus signature not found or incorrect consensus type in Fuel block.".to_string(),
-            ));
-        };
-
-        let recovered_producer_addr = *signature
+    fn recover_producer_addr(fuel_block: &Block, poa: &PoAConsensus) -> Result<[u8; 32]> {
+        let recovered_producer_addr = *poa
+            .signature
             .recover(&Message::from_bytes(*fuel_block.id))
             .map_err(|e| {
                 Error::BlockValidation(format!(
@@ -115,6 +187,15 @@ impl BlockValidator {
         BlockId::from(hasher.digest())
     }
 
+    fn calculate_transactions_root(transactions: &[Vec<u8>]) -> Bytes32 {
+        let mut calculator = MerkleRootCalculator::new();
+        for tx in transactions {
+            calculator.push(tx);
+        }
+
+        Bytes32::from(calculator.root())
+    }
+
     fn application_hash(header: &Header) -> Bytes32 {
         let mut hasher = Hasher::default();
         let Header {
@@ -180,13 +261,57 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "PoAConsensus signature not found or incorrect consensus type in Fuel block.")]
-    fn validate_block_consensus_not_poa() {
+    fn validate_block_consensus_unknown() {
         let secret_key = given_secret_key();
         let mut fuel_block = given_a_block(Some(secret_key));
         fuel_block.consensus = Consensus::Unknown;
         let validator = BlockValidator::new(*secret_key.public_key().hash());
 
+        let result = validator.validate(&fuel_block);
+
+        let Err(Error::UnsupportedConsensus(msg)) = result else {
+            panic!("expected an unsupported consensus error, got: {result:?}");
+        };
+        assert!(msg.contains("consensus type unknown to the committer"));
+    }
+
+    #[test]
+    fn validate_genesis_block() {
+        let chain_config_hash = [1; 32];
+        let fuel_block = given_a_genesis_block(chain_config_hash);
+        let validator = BlockValidator::new([0; 32]).with_chain_config_hash(chain_config_hash);
+
+        let validated = validator.validate(&fuel_block).unwrap();
+
+        assert_eq!(validated.hash(), *fuel_block.id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Received a genesis block but no chain config hash is configured.")]
+    fn validate_genesis_block_without_configured_hash() {
+        let fuel_block = given_a_genesis_block([1; 32]);
+        let validator = BlockValidator::new([0; 32]);
+
+        validator.validate(&fuel_block).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "does not match the expected hash")]
+    fn validate_genesis_block_chain_config_hash_mismatch() {
+        let fuel_block = given_a_genesis_block([1; 32]);
+        let validator = BlockValidator::new([0; 32]).with_chain_config_hash([2; 32]);
+
+        validator.validate(&fuel_block).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "does not match the calculated block ID")]
+    fn validate_genesis_block_id_mismatch() {
+        let chain_config_hash = [1; 32];
+        let mut fuel_block = given_a_genesis_block(chain_config_hash);
+        fuel_block.header.height = 42;
+        let validator = BlockValidator::new([0; 32]).with_chain_config_hash(chain_config_hash);
+
         validator.validate(&fuel_block).unwrap();
     }
 
@@ -221,6 +346,47 @@ mod tests {
         validator.validate(&fuel_block).unwrap();
     }
 
+    #[test]
+    fn validate_fuel_block_with_transactions() {
+        let secret_key = given_secret_key();
+        let transactions = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        let fuel_block = given_a_block_with_transactions(secret_key, &transactions);
+        let validator = BlockValidator::new(*secret_key.public_key().hash());
+
+        validator
+            .validate_with_transactions(&fuel_block, &transactions)
+            .unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "does not match the calculated root")]
+    fn validate_transactions_root_mismatch() {
+        let secret_key = given_secret_key();
+        let transactions = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        let fuel_block = given_a_block_with_transactions(secret_key, &transactions);
+        let validator = BlockValidator::new(*secret_key.public_key().hash());
+
+        let tampered_transactions = vec![vec![1, 2, 3], vec![4, 5, 7]];
+        validator
+            .validate_with_transactions(&fuel_block, &tampered_transactions)
+            .unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "declares 2 transactions, but 2 ids and 1 transactions were received"
+    )]
+    fn validate_transactions_count_mismatch() {
+        let secret_key = given_secret_key();
+        let transactions = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        let fuel_block = given_a_block_with_transactions(secret_key, &transactions);
+        let validator = BlockValidator::new(*secret_key.public_key().hash());
+
+        validator
+            .validate_with_transactions(&fuel_block, &transactions[..1])
+            .unwrap();
+    }
+
     fn given_secret_key() -> SecretKey {
         let mut rng = StdRng::seed_from_u64(42);
 
@@ -248,13 +414,56 @@ mod tests {
             Block {
                 id,
                 header,
-                consensus: Consensus::Unknown,
+                consensus: Consensus::PoAConsensus(PoAConsensus {
+                    signature: Signature::default(),
+                }),
                 transactions: vec![],
                 block_producer: None,
             }
         }
     }
 
+    fn given_a_block_with_transactions(secret_key: SecretKey, transactions: &[Vec<u8>]) -> Block {
+        let mut header = given_header();
+        header.transactions_count = transactions.len() as u16;
+        header.transactions_root = BlockValidator::calculate_transactions_root(transactions);
+
+        let mut block = Block {
+            id: Default::default(),
+            header,
+            consensus: Consensus::Unknown,
+            transactions: transactions.iter().map(|_| Default::default()).collect(),
+            block_producer: Some(secret_key.public_key()),
+        };
+        block.id = BlockValidator::calculate_block_id(&block);
+
+        let signature = Signature::sign(&secret_key, &Message::from_bytes(*block.id));
+        block.consensus = Consensus::PoAConsensus(PoAConsensus { signature });
+
+        block
+    }
+
+    fn given_a_genesis_block(chain_config_hash: [u8; 32]) -> Block {
+        let header = given_header();
+        let id: Bytes32 = "0x57131ec6e99caafc08803aa946093e02c4303a305e5cc959ad84b775e668a5c3"
+            .parse()
+            .unwrap();
+
+        Block {
+            id,
+            header,
+            consensus: Consensus::Genesis(Genesis {
+                chain_config_hash: chain_config_hash.into(),
+                coins_root: Default::default(),
+                contracts_root: Default::default(),
+                messages_root: Default::default(),
+                transactions_root: Default::default(),
+            }),
+            transactions: vec![],
+            block_producer: None,
+        }
+    }
+
     fn given_header() -> Header {
         let application_hash = "0x017ab4b70ea129c29e932d44baddc185ad136bf719c4ada63a10b5bf796af91e"
             .parse()